@@ -5,6 +5,9 @@ use frostlab::dkg::DkgConfig;
 use frostlab::dkg::run_dealerless_dkg;
 use frostlab::ddh_dvrf::{run_ddh_dvrf_once};
 use frostlab::frost_ext::{frost_sign, frost_verify};
+use frostlab::messages::{AttestationMessage, DvrfInput};
+use frostlab::utils::{point_bytes_compressed, points_bytes_compressed};
+use k256::{ProjectivePoint, Scalar};
 
 /// (DKG + DDH-DVRF + FROST sign/verify)
 fn bench_full_protocol(c: &mut Criterion) {
@@ -18,21 +21,21 @@ fn bench_full_protocol(c: &mut Criterion) {
             let all_ids = out.all_ids();
 
             // 2️⃣ DDH-DVRF
-            let msg_dvrf = b"dvrfddhhello";
+            let msg_dvrf = DvrfInput::new(b"dvrfddhhello".to_vec());
             let signers = &all_ids[..cfg.min_signers as usize];
             let (_v, _points) = run_ddh_dvrf_once(
-                msg_dvrf,
+                &msg_dvrf,
                 &out.key_packages,
                 &out.public_key_package,
                 signers,
             );
 
             // 3️⃣ FROST signing
-            let msg_frost = b"attestation";
-            let sig = frost_sign(msg_frost, &out, signers, &mut rng).unwrap();
+            let msg_frost = AttestationMessage::new(b"attestation".to_vec());
+            let sig = frost_sign(&msg_frost, &out, signers, &mut rng).unwrap();
 
             // 4️⃣ Verify
-            let ok = frost_verify(msg_frost, &sig, &out).unwrap();
+            let ok = frost_verify(&msg_frost, &sig, &out).unwrap();
             assert!(ok);
 
             black_box(ok);
@@ -40,5 +43,21 @@ fn bench_full_protocol(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_full_protocol);
+/// Exporting one round's points: per-point inversion vs one batched inversion
+fn bench_compressed_export(c: &mut Criterion) {
+    let points: Vec<ProjectivePoint> = (1..=256u64)
+        .map(|k| ProjectivePoint::GENERATOR * Scalar::from(k) + ProjectivePoint::GENERATOR)
+        .collect();
+    c.bench_function("compress 256 points (one by one)", |b| {
+        b.iter(|| {
+            let out: Vec<[u8; 33]> = points.iter().map(point_bytes_compressed).collect();
+            black_box(out);
+        })
+    });
+    c.bench_function("compress 256 points (batch_normalize)", |b| {
+        b.iter(|| black_box(points_bytes_compressed(&points)))
+    });
+}
+
+criterion_group!(benches, bench_full_protocol, bench_compressed_export);
 criterion_main!(benches);
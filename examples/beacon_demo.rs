@@ -0,0 +1,18 @@
+//! A few beacon rounds, each quorum seeded by the previous round's value
+//!
+//!     cargo run --example beacon_demo -- 5
+
+use anyhow::Result;
+
+use frostlab::encoding::hex0x;
+use frostlab::scenarios::beacon_demo;
+
+fn main() -> Result<()> {
+    let rounds = std::env::args().nth(1).map(|s| s.parse()).transpose()?.unwrap_or(3);
+    let demo = beacon_demo(rounds)?;
+    println!("committee {}", hex0x(&demo.committee.group_id));
+    for r in &demo.rounds {
+        println!("round {}: {}", r.round, hex0x(&r.randomness()));
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Everything a contract needs for one dual-output round, as JSON-friendly hex
+//!
+//!     cargo run --example evm_export_demo
+
+use anyhow::Result;
+
+use frostlab::scenarios::evm_export_demo;
+
+fn main() -> Result<()> {
+    let export = evm_export_demo()?;
+    println!("{}", export.committee.to_json()?);
+    println!("attestation_abi: {}", export.attestation_abi);
+    println!("signature: {}", export.signature);
+    println!("randomness: {}", export.randomness);
+    println!("lagrange_abi: {}", export.lagrange_abi);
+    Ok(())
+}
@@ -0,0 +1,21 @@
+//! DKG → DVRF → signed round attestation, 4-of-5
+//!
+//!     cargo run --example full_demo
+
+use anyhow::Result;
+
+use frostlab::ddh_dvrf::id_as_u64;
+use frostlab::encoding::hex0x;
+use frostlab::scenarios::{demo_config, full_demo};
+
+fn main() -> Result<()> {
+    let demo = full_demo(demo_config())?;
+    let report = demo.committee.to_report();
+    println!("committee {} ({} of {})", report.group_id, report.min_signers, report.max_signers);
+    println!("randomness: {}", hex0x(&demo.cert.randomness()));
+    for p in &demo.cert.partials {
+        println!("  contributor id={}", id_as_u64(p.id));
+    }
+    println!("attestation: {}", hex0x(&demo.dual.attestation.to_abi()));
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Append-only, hash-chained log of every use of a participant's secret share.
+//!
+//! entry_hash_k = Keccak(prev_hash || seq || kind || session_id || message_hash || timestamp)
+//! with prev_hash_0 = 0^32, so editing, dropping or reordering any entry breaks the chain.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::keccak256;
+
+pub type Hash32 = [u8; 32];
+
+/// What the share was used for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareUse {
+    PartialEval,
+    SignatureShare,
+    /// share over a blinded challenge; the logged message is the challenge itself
+    BlindSignatureShare,
+    /// partial BLS signature on an attestation message
+    BlsSignatureShare,
+}
+
+impl ShareUse {
+    fn tag(&self) -> u8 {
+        match self {
+            ShareUse::PartialEval => 1,
+            ShareUse::SignatureShare => 2,
+            ShareUse::BlindSignatureShare => 3,
+            ShareUse::BlsSignatureShare => 4,
+        }
+    }
+}
+
+/// One log record
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub kind: ShareUse,
+    #[serde(with = "hex32")]
+    pub session_id: Hash32,
+    #[serde(with = "hex32")]
+    pub message_hash: Hash32,
+    /// unix seconds
+    pub timestamp: u64,
+    #[serde(with = "hex32")]
+    pub prev_hash: Hash32,
+    #[serde(with = "hex32")]
+    pub entry_hash: Hash32,
+}
+
+impl AuditEntry {
+    fn compute_hash(&self) -> Hash32 {
+        let mut buf = Vec::with_capacity(32 + 8 + 1 + 32 + 32 + 8);
+        buf.extend_from_slice(&self.prev_hash);
+        buf.extend_from_slice(&self.seq.to_be_bytes());
+        buf.push(self.kind.tag());
+        buf.extend_from_slice(&self.session_id);
+        buf.extend_from_slice(&self.message_hash);
+        buf.extend_from_slice(&self.timestamp.to_be_bytes());
+        keccak256(&buf)
+    }
+}
+
+/// Append-only audit log
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Hash of the last entry (0^32 for an empty log); publishing it commits to the whole history
+    pub fn head(&self) -> Hash32 {
+        self.entries.last().map(|e| e.entry_hash).unwrap_or([0u8; 32])
+    }
+
+    /// `timestamp` is clamped to the previous entry's, so a clock stepping back (NTP, VM
+    /// migration) cannot produce a log that `verify_chain` rejects
+    pub fn append(&mut self, kind: ShareUse, session_id: Hash32, message: &[u8], timestamp: u64) -> &AuditEntry {
+        let timestamp = self.entries.last().map_or(timestamp, |e| e.timestamp.max(timestamp));
+        let mut entry = AuditEntry {
+            seq: self.entries.len() as u64,
+            kind,
+            session_id,
+            message_hash: keccak256(message),
+            timestamp,
+            prev_hash: self.head(),
+            entry_hash: [0u8; 32],
+        };
+        entry.entry_hash = entry.compute_hash();
+        self.entries.push(entry);
+        self.entries.last().expect("just pushed")
+    }
+
+    pub fn export_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.entries)?)
+    }
+
+    /// Parse an exported log and check the whole chain
+    pub fn import_json(json: &str) -> Result<Self> {
+        let entries: Vec<AuditEntry> = serde_json::from_str(json)?;
+        verify_chain(&entries)?;
+        Ok(Self { entries })
+    }
+}
+
+/// Check sequence numbers, links and hashes; returns the head hash
+pub fn verify_chain(entries: &[AuditEntry]) -> Result<Hash32> {
+    let mut prev = [0u8; 32];
+    let mut last_ts = 0u64;
+    for (i, e) in entries.iter().enumerate() {
+        if e.seq != i as u64 { bail!("entry {i}: unexpected seq {}", e.seq); }
+        if e.prev_hash != prev { bail!("entry {i}: broken link"); }
+        if e.compute_hash() != e.entry_hash { bail!("entry {i}: hash mismatch"); }
+        if e.timestamp < last_ts { bail!("entry {i}: timestamp goes backwards"); }
+        prev = e.entry_hash;
+        last_ts = e.timestamp;
+    }
+    Ok(prev)
+}
+
+/// serde helper: [u8; 32] <-> "0x…" hex string
+pub(crate) mod hex32 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use crate::encoding::{from_hex0x, hex0x};
+
+    pub fn serialize<S: Serializer>(v: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex0x(v))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        let s = String::deserialize(d)?;
+        let raw = from_hex0x(&s).map_err(D::Error::custom)?;
+        raw.try_into().map_err(|_| D::Error::custom("expected 32 bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_chain_roundtrip_and_tamper() -> Result<()> {
+        let mut log = AuditLog::new();
+        log.append(ShareUse::PartialEval, [1u8; 32], b"dvrf msg", 100);
+        log.append(ShareUse::SignatureShare, [2u8; 32], b"attestation", 101);
+        log.append(ShareUse::PartialEval, [3u8; 32], b"dvrf msg 2", 101);
+
+        let head = verify_chain(log.entries())?;
+        assert_eq!(head, log.head());
+
+        let json = log.export_json()?;
+        let imported = AuditLog::import_json(&json)?;
+        assert_eq!(imported.entries(), log.entries());
+
+        // tamper: change what the share was used for
+        let mut entries = log.entries().to_vec();
+        entries[1].message_hash = keccak256(b"something else");
+        assert!(verify_chain(&entries).is_err());
+
+        // tamper: drop an entry
+        let mut entries = log.entries().to_vec();
+        entries.remove(1);
+        assert!(verify_chain(&entries).is_err());
+
+        // tamper: rewrite the tail consistently still changes the head
+        let mut forged = AuditLog::new();
+        forged.append(ShareUse::PartialEval, [1u8; 32], b"dvrf msg", 100);
+        assert_ne!(forged.head(), log.head());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clock_going_backwards_keeps_chain_valid() -> Result<()> {
+        let mut log = AuditLog::new();
+        log.append(ShareUse::PartialEval, [1u8; 32], b"a", 200);
+        assert_eq!(log.append(ShareUse::PartialEval, [2u8; 32], b"b", 150).timestamp, 200);
+        assert_eq!(log.append(ShareUse::PartialEval, [3u8; 32], b"c", 201).timestamp, 201);
+        verify_chain(log.entries())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! Round-based randomness beacon: the value of round r is the DDH-DVRF output on
+//! `"frostlab/beacon/round" || r`, published as a quorum certificate.
+
+use anyhow::{bail, Result};
+
+use crate::cert::{CertConfig, QuorumCert};
+use crate::ddh_dvrf::PublicKeyPackage;
+use crate::messages::DvrfInput;
+#[cfg(feature = "protocol")]
+use std::collections::BTreeMap;
+#[cfg(feature = "protocol")]
+use crate::ddh_dvrf::{run_ddh_dvrf_cert_with_rng, Identifier, KeyPackage};
+#[cfg(feature = "protocol")]
+use frost_secp256k1_evm::rand_core::{CryptoRng, OsRng, RngCore};
+
+pub const BEACON_LABEL: &[u8] = b"frostlab/beacon/round";
+
+/// DVRF input of round `round`
+pub fn beacon_input(round: u64) -> DvrfInput {
+    let mut raw = Vec::with_capacity(BEACON_LABEL.len() + 8);
+    raw.extend_from_slice(BEACON_LABEL);
+    raw.extend_from_slice(&round.to_be_bytes());
+    DvrfInput::new(raw)
+}
+
+/// One produced beacon round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaconRound {
+    pub round: u64,
+    pub cert: QuorumCert,
+}
+
+impl BeaconRound {
+    /// The certificate is valid and was produced for this round's input
+    pub fn verify<'a>(&self, public_key_package: &PublicKeyPackage, config: impl Into<CertConfig<'a>>) -> Result<()> {
+        if self.cert.msg != beacon_input(self.round) {
+            bail!("certificate is not for beacon round {}", self.round);
+        }
+        self.cert.verify(public_key_package, config)
+    }
+
+    pub fn randomness(&self) -> [u8; 32] {
+        self.cert.randomness()
+    }
+}
+
+/// Evaluate round `round` with `signers`
+#[cfg(feature = "protocol")]
+pub fn produce_round(
+    round: u64,
+    key_packages: &BTreeMap<Identifier, KeyPackage>,
+    public_key_package: &PublicKeyPackage,
+    signers: &[Identifier],
+) -> BeaconRound {
+    produce_round_with_rng(round, key_packages, public_key_package, signers, &mut OsRng)
+}
+
+/// `produce_round` with the proof nonces drawn from `rng`
+#[cfg(feature = "protocol")]
+pub fn produce_round_with_rng<R: RngCore + CryptoRng>(
+    round: u64,
+    key_packages: &BTreeMap<Identifier, KeyPackage>,
+    public_key_package: &PublicKeyPackage,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> BeaconRound {
+    let cert = run_ddh_dvrf_cert_with_rng(&beacon_input(round), key_packages, public_key_package, signers, rng);
+    BeaconRound { round, cert }
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_beacon_round_bound_to_number() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let r7 = produce_round(7, &out.key_packages, &out.public_key_package, &ids[..3]);
+        r7.verify(&out.public_key_package, cfg.min_signers)?;
+
+        // same value from any quorum
+        let again = produce_round(7, &out.key_packages, &out.public_key_package, &ids[1..]);
+        assert_eq!(again.randomness(), r7.randomness());
+
+        // relabelled round
+        let mut moved = r7;
+        moved.round = 8;
+        assert!(moved.verify(&out.public_key_package, cfg.min_signers).is_err());
+        Ok(())
+    }
+}
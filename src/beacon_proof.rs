@@ -0,0 +1,360 @@
+//! Light-client proofs of beacon inclusion: "round N of chain C output randomness X", checkable
+//! with one Schnorr verification against the group key and a few keccaks, so it fits inside a
+//! rollup's fraud/validity proof.
+//!
+//! chain_hash = keccak("frostlab/beacon-chain/v2" || group_id || group_key (33)
+//!                     || uint16 min_signers || uint64 genesis_time || uint64 period
+//!                     || BEACON_LABEL), i.e. every field of `ChainInfo`
+//! link_0     = chain_hash
+//! link_r     = keccak("frostlab/beacon-link/v1" || link_{r-1} || uint64 r || randomness_r)
+//!
+//! proof = chain_hash (32) || uint64 round (8) || uint64 timestamp (8) || randomness (32)
+//!         || prev_link (32) || transcript_hash (32) || signature R || z (65), packed, 209 bytes;
+//! the group signs the first 144 bytes as `AttestationMessage::tagged(BEACON_PROOF_TAG, ..)`. `transcript_hash` is the
+//! quorum certificate's `round_transcript_hash`, so the full round can still be audited.
+//!
+//! Round r is due at genesis_time + (r - 1)·period (unix seconds). A proof is only accepted
+//! if its timestamp is within `max_skew` of that slot and the slot is not in the future, so a
+//! relayer cannot hand out randomness of rounds that should not exist yet.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+
+use crate::beacon::{BeaconRound, BEACON_LABEL};
+use crate::committee::CommitteeInfo;
+use crate::ddh_dvrf::PublicKeyPackage;
+use crate::frost_ext::frost_verify_with_key;
+use crate::messages::{AttestationMessage, BEACON_PROOF_TAG};
+use crate::randomness_cert::round_transcript_hash;
+use crate::utils::keccak256;
+#[cfg(feature = "protocol")]
+use frost::rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "protocol")]
+use crate::{dkg::{DkgOutput, Identifier}, frost_ext::frost_sign};
+
+const CHAIN_DOMAIN: &[u8] = b"frostlab/beacon-chain/v2";
+const LINK_DOMAIN: &[u8] = b"frostlab/beacon-link/v1";
+
+const SIGNED_LEN: usize = 32 + 8 + 8 + 32 + 32 + 32;
+pub const BEACON_PROOF_LEN: usize = SIGNED_LEN + 65;
+
+/// What a light client pins: the committee the beacon runs under and its schedule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainInfo {
+    pub group_id: [u8; 32],
+    /// compressed SEC1 group key
+    pub group_key: [u8; 33],
+    pub min_signers: u16,
+    /// unix seconds of round 1
+    pub genesis_time: u64,
+    /// seconds between rounds
+    pub period: u64,
+}
+
+impl ChainInfo {
+    pub fn from_committee(committee: &CommitteeInfo, genesis_time: u64, period: u64) -> Result<Self> {
+        if period == 0 {
+            bail!("beacon period must be positive");
+        }
+        Ok(Self {
+            group_id: committee.group_id,
+            group_key: committee.group_key,
+            min_signers: committee.min_signers,
+            genesis_time,
+            period,
+        })
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        keccak256(&[
+            CHAIN_DOMAIN,
+            &self.group_id[..],
+            &self.group_key[..],
+            &self.min_signers.to_be_bytes(),
+            &self.genesis_time.to_be_bytes(),
+            &self.period.to_be_bytes(),
+            BEACON_LABEL,
+        ].concat())
+    }
+
+    /// Link before the first proved round
+    pub fn genesis_link(&self) -> [u8; 32] {
+        self.hash()
+    }
+
+    /// Unix time round `round` is due; None for round 0 or past u64
+    pub fn round_time(&self, round: u64) -> Option<u64> {
+        round.checked_sub(1)?.checked_mul(self.period)?.checked_add(self.genesis_time)
+    }
+
+    /// Latest round due at `now`, 0 before genesis
+    pub fn current_round(&self, now: u64) -> u64 {
+        match now.checked_sub(self.genesis_time) {
+            Some(elapsed) => elapsed / self.period + 1,
+            None => 0,
+        }
+    }
+
+    /// `timestamp` is within the skew of round `round`'s slot, and that slot has come by `now`
+    pub fn check_timing(&self, round: u64, timestamp: u64, now: u64, timing: &TimingPolicy) -> Result<()> {
+        let Some(due) = self.round_time(round) else { bail!("beacon round {} is not on the schedule", round) };
+        if timestamp.abs_diff(due) > timing.max_skew {
+            bail!("timestamp {} of round {} is more than {}s from its slot {}", timestamp, round, timing.max_skew, due);
+        }
+        if due > now.saturating_add(timing.max_skew) || timestamp > now.saturating_add(timing.max_skew) {
+            bail!("beacon round {} is not due until {} (now {})", round, due, now);
+        }
+        Ok(())
+    }
+}
+
+/// Acceptable clock difference between the chain schedule, the producer and the verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimingPolicy {
+    /// seconds
+    pub max_skew: u64,
+}
+
+impl Default for TimingPolicy {
+    fn default() -> Self {
+        Self { max_skew: 5 }
+    }
+}
+
+pub fn next_link(prev_link: &[u8; 32], round: u64, randomness: &[u8; 32]) -> [u8; 32] {
+    keccak256(&[LINK_DOMAIN, &prev_link[..], &round.to_be_bytes(), &randomness[..]].concat())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BeaconInclusionProof {
+    pub chain_hash: [u8; 32],
+    pub round: u64,
+    /// unix seconds the round was produced
+    pub timestamp: u64,
+    pub randomness: [u8; 32],
+    pub prev_link: [u8; 32],
+    pub transcript_hash: [u8; 32],
+    pub signature: frost::Signature,
+}
+
+fn signed_bytes(chain_hash: &[u8; 32], round: u64, timestamp: u64, randomness: &[u8; 32], prev_link: &[u8; 32], transcript_hash: &[u8; 32]) -> Vec<u8> {
+    [&chain_hash[..], &round.to_be_bytes(), &timestamp.to_be_bytes(), &randomness[..], &prev_link[..], &transcript_hash[..]].concat()
+}
+
+impl BeaconInclusionProof {
+    fn signed_bytes(&self) -> Vec<u8> {
+        signed_bytes(&self.chain_hash, self.round, self.timestamp, &self.randomness, &self.prev_link, &self.transcript_hash)
+    }
+
+    pub fn message(&self) -> AttestationMessage {
+        AttestationMessage::tagged(BEACON_PROOF_TAG, self.signed_bytes())
+    }
+
+    /// Chain head after this round
+    pub fn link(&self) -> [u8; 32] {
+        next_link(&self.prev_link, self.round, &self.randomness)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = self.signed_bytes();
+        out.extend_from_slice(&self.signature.serialize()?);
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != BEACON_PROOF_LEN {
+            bail!("beacon proof must be {} bytes, got {}", BEACON_PROOF_LEN, bytes.len());
+        }
+        let w = |at: usize| -> [u8; 32] { bytes[at..at + 32].try_into().expect("32 bytes") };
+        Ok(Self {
+            chain_hash: w(0),
+            round: u64::from_be_bytes(bytes[32..40].try_into().expect("8 bytes")),
+            timestamp: u64::from_be_bytes(bytes[40..48].try_into().expect("8 bytes")),
+            randomness: w(48),
+            prev_link: w(80),
+            transcript_hash: w(112),
+            signature: frost::Signature::deserialize(&bytes[SIGNED_LEN..])?,
+        })
+    }
+
+    /// The proof is for `chain` and carries a valid group signature
+    pub fn verify(&self, chain: &ChainInfo) -> Result<()> {
+        if self.chain_hash != chain.hash() {
+            bail!("beacon proof is for another chain");
+        }
+        let vk = frost::VerifyingKey::deserialize(&chain.group_key)?;
+        if !frost_verify_with_key(&self.message(), &self.signature, &vk)? {
+            bail!("invalid group signature on beacon proof for round {}", self.round);
+        }
+        Ok(())
+    }
+
+    /// `verify` plus the chain schedule: no rounds from the future, timestamp on its slot
+    pub fn verify_at(&self, chain: &ChainInfo, now: u64, timing: &TimingPolicy) -> Result<()> {
+        chain.check_timing(self.round, self.timestamp, now, timing)?;
+        self.verify(chain)
+    }
+
+    /// `verify` plus: the round's certificate is valid and is the one this proof describes
+    pub fn verify_with_round(&self, chain: &ChainInfo, round: &BeaconRound, public_key_package: &PublicKeyPackage) -> Result<()> {
+        round.verify(public_key_package, chain.min_signers)?;
+        if round.round != self.round || round.randomness() != self.randomness || round_transcript_hash(&round.cert) != self.transcript_hash {
+            bail!("beacon proof does not describe round {}", round.round);
+        }
+        self.verify(chain)
+    }
+}
+
+/// Follows one chain from a trusted head, accepting only the next round linked onto it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaconLightClient {
+    pub chain: ChainInfo,
+    pub timing: TimingPolicy,
+    pub head: [u8; 32],
+    /// last accepted round, 0 before the first
+    pub round: u64,
+}
+
+impl BeaconLightClient {
+    pub fn new(chain: ChainInfo) -> Self {
+        let head = chain.genesis_link();
+        Self { chain, timing: TimingPolicy::default(), head, round: 0 }
+    }
+
+    pub fn with_timing(mut self, timing: TimingPolicy) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Resume from a checkpoint (e.g. the head a rollup contract stores)
+    pub fn from_checkpoint(chain: ChainInfo, head: [u8; 32], round: u64) -> Self {
+        Self { chain, timing: TimingPolicy::default(), head, round }
+    }
+
+    /// `now` is the verifier's unix time (a rollup uses its block timestamp)
+    pub fn accept(&mut self, proof: &BeaconInclusionProof, now: u64) -> Result<[u8; 32]> {
+        if proof.round != self.round + 1 {
+            bail!("expected beacon round {}, got {}", self.round + 1, proof.round);
+        }
+        if proof.prev_link != self.head {
+            bail!("beacon round {} does not extend the current head", proof.round);
+        }
+        proof.verify_at(&self.chain, now, &self.timing)?;
+        self.head = proof.link();
+        self.round = proof.round;
+        Ok(proof.randomness)
+    }
+}
+
+/// Verify `round`, then have `signers` sign its inclusion after `prev_link`, stamped `timestamp`
+#[cfg(feature = "protocol")]
+pub fn prove_round<R: RngCore + CryptoRng>(
+    chain: &ChainInfo,
+    round: &BeaconRound,
+    prev_link: [u8; 32],
+    timestamp: u64,
+    out: &DkgOutput,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> Result<BeaconInclusionProof> {
+    round.verify(&out.public_key_package, chain.min_signers)?;
+    let chain_hash = chain.hash();
+    let randomness = round.randomness();
+    let transcript_hash = round_transcript_hash(&round.cert);
+    let msg = AttestationMessage::tagged(BEACON_PROOF_TAG, signed_bytes(&chain_hash, round.round, timestamp, &randomness, &prev_link, &transcript_hash));
+    let signature = frost_sign(&msg, out, signers, rng)?;
+    Ok(BeaconInclusionProof { chain_hash, round: round.round, timestamp, randomness, prev_link, transcript_hash, signature })
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::beacon::produce_round;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_light_client_follows_linked_rounds() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let genesis = 1_700_000_000;
+        let chain = ChainInfo::from_committee(&out.committee_info()?, genesis, 30)?;
+        let now = genesis + 2 * 30 + 1;
+
+        let mut client = BeaconLightClient::new(chain.clone());
+        let mut head = chain.genesis_link();
+        let mut proofs = Vec::new();
+        for r in 1..=3 {
+            let round = produce_round(r, &out.key_packages, &out.public_key_package, &ids[..3]);
+            let proof = prove_round(&chain, &round, head, chain.round_time(r).unwrap() + 1, &out, &ids[1..], &mut OsRng)?;
+            proof.verify_with_round(&chain, &round, &out.public_key_package)?;
+
+            let bytes = proof.to_bytes()?;
+            assert_eq!(bytes.len(), BEACON_PROOF_LEN);
+            assert_eq!(client.accept(&BeaconInclusionProof::from_bytes(&bytes)?, now)?, round.randomness());
+            head = proof.link();
+            proofs.push(proof);
+        }
+        assert_eq!((client.head, client.round), (head, 3));
+
+        // replayed, out of order, forged
+        let mut fresh = BeaconLightClient::new(chain.clone());
+        assert!(fresh.accept(&proofs[1], now).is_err());
+        fresh.accept(&proofs[0], now)?;
+        assert!(fresh.accept(&proofs[0], now).is_err());
+        let mut forged = proofs[1];
+        forged.randomness[0] ^= 1;
+        assert!(fresh.accept(&forged, now).is_err());
+        // the same 144 bytes signed as an application payload are not an inclusion proof
+        let mut relabeled = proofs[1];
+        relabeled.signature = frost_sign(&AttestationMessage::new(proofs[1].signed_bytes()), &out, &ids[1..], &mut OsRng)?;
+        assert!(fresh.accept(&relabeled, now).is_err());
+
+        // another committee's chain
+        let other = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let other_chain = ChainInfo::from_committee(&other.committee_info()?, genesis, 30)?;
+        assert!(proofs[0].verify(&other_chain).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_round_timing() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let ids = out.all_ids();
+        let genesis = 1_000;
+        let chain = ChainInfo::from_committee(&out.committee_info()?, genesis, 10)?;
+        let timing = TimingPolicy { max_skew: 2 };
+        assert_eq!(chain.round_time(3), Some(1_020));
+        assert_eq!((chain.current_round(999), chain.current_round(1_000), chain.current_round(1_019)), (0, 1, 2));
+        assert!(ChainInfo::from_committee(&out.committee_info()?, genesis, 0).is_err());
+
+        // every pinned field is in the chain hash
+        let pinned = [
+            ChainInfo { group_key: [2; 33], ..chain.clone() },
+            ChainInfo { min_signers: chain.min_signers + 1, ..chain.clone() },
+            ChainInfo { genesis_time: genesis + 1, ..chain.clone() },
+            ChainInfo { period: 11, ..chain.clone() },
+        ];
+        assert!(pinned.iter().all(|c| c.hash() != chain.hash()));
+
+        chain.check_timing(3, 1_021, 1_025, &timing)?;
+        // slot within the skew of the verifier's clock
+        chain.check_timing(3, 1_020, 1_018, &timing)?;
+        // produced early: round 3 handed out before its slot
+        assert!(chain.check_timing(3, 1_020, 1_015, &timing).is_err());
+        // timestamp off its slot, round 0
+        assert!(chain.check_timing(3, 1_030, 1_040, &timing).is_err());
+        assert!(chain.check_timing(0, 1_000, 1_040, &timing).is_err());
+
+        let round = produce_round(3, &out.key_packages, &out.public_key_package, &ids[..2]);
+        let proof = prove_round(&chain, &round, [7; 32], 1_020, &out, &ids[..2], &mut OsRng)?;
+        proof.verify_at(&chain, 1_020, &timing)?;
+        assert!(proof.verify_at(&chain, 1_010, &timing).is_err());
+
+        // a relayer that is ahead of the verifier cannot feed round 3 through the client
+        let mut client = BeaconLightClient::from_checkpoint(chain.clone(), [7; 32], 2).with_timing(timing);
+        assert!(client.accept(&proof, 1_010).is_err());
+        assert_eq!(client.accept(&proof, 1_021)?, round.randomness());
+        Ok(())
+    }
+}
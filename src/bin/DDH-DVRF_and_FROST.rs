@@ -1,71 +1,42 @@
-use std::u16;
-
 use anyhow::Result;
-use frost_secp256k1_evm::rand_core::OsRng;
-use k256::elliptic_curve::group::GroupEncoding;
-
-use frostlab::dkg::{DkgConfig, run_dealerless_dkg, DkgOutput};
-use frostlab::ddh_dvrf::{run_ddh_dvrf_once, id_as_u64};
-use frostlab::utils::hash_to_curve_point_keccak;
-use frostlab::frost_ext::{frost_sign, frost_verify};
-
-fn run_single_ddh_dvrf(msg: &[u8], out: &DkgOutput, signer_count: usize) -> Result<()> {
-    let all_ids = out.all_ids();
-
-    if signer_count == 0 || signer_count > all_ids.len() {
-        anyhow::bail!("invalid signer_count: {}", signer_count);
-    }
 
-    let signers = &all_ids[..signer_count];
+use frostlab::ddh_dvrf::id_as_u64;
+use frostlab::dkg::DkgConfig;
+use frostlab::encoding::hex0x;
+use frostlab::messages::DvrfInput;
+use frostlab::scenarios::{full_demo, DEMO_INPUT};
+use frostlab::utils::{dvrf_base_point, point_to_hex};
 
-    // DDH-DVRF run
-    let (v, points) = run_ddh_dvrf_once(msg, &out.key_packages, &out.public_key_package, signers);
+fn ddh_and_frost_main(max: u16, min: u16) -> Result<()> {
+    let demo = full_demo(DkgConfig::new(max, min)?)?;
+    println!("─── DKG completed: {} of {} threshold ───", min, max);
 
     println!("\n─── DDH-DVRF Execution ───");
     println!(
-        "PH(msg) compressed: 0x{}",
-        hex::encode(k256::AffinePoint::from(hash_to_curve_point_keccak(msg)).to_bytes())
+        "PH(msg) compressed: {}",
+        point_to_hex(&dvrf_base_point(&DvrfInput::new(DEMO_INPUT.to_vec())))
     );
     println!(
-        "v (combined) compressed: 0x{}",
-        hex::encode(k256::AffinePoint::from(v).to_bytes())
+        "v (combined) compressed: {}",
+        point_to_hex(&demo.cert.output)
     );
-
-    for (id, vi) in points {
+    for (id, vi) in demo.cert.partials_compressed() {
         println!(
-            "id={}  v_{}: 0x{}",
+            "id={}  v_{}: {}",
             id_as_u64(id),
             id_as_u64(id),
-            hex::encode(k256::AffinePoint::from(vi).to_bytes())
+            hex0x(&vi)
         );
     }
 
-    Ok(())
-}
-
-fn ddh_and_frost_main(max: u16, min: u16) -> Result<()> {
-    // DKG 
-    let mut rng = OsRng;
-    let cfg = DkgConfig::new(max, min)?;
-    let out = run_dealerless_dkg(cfg, &mut rng)?;
-    println!("─── DKG completed: {} of {} threshold ───", min, max);
-
-    // DVRF
-    let msg_dvrf = b"dvrfddhhello";
-    run_single_ddh_dvrf(msg_dvrf, &out, cfg.min_signers as usize)?;
-
-    // FROST Signing (attestation)
-    let msg_frost = b"attestation";
-    let all_ids = out.all_ids();
-    let signers = &all_ids[..cfg.min_signers as usize];
-
-    println!("\n─── FROST signing on message: \"{}\" ───", String::from_utf8_lossy(msg_frost));
-    let sig = frost_sign(msg_frost, &out, signers, &mut rng)?;
+    let dual = &demo.dual;
+    println!("\n─── Round attestation (epoch {}, round {}) ───", dual.attestation.epoch, dual.attestation.round);
+    println!("vrf_output: {}", hex0x(&dual.attestation.vrf_output));
+    println!("contributors_bitmap: {}", hex0x(&dual.attestation.contributors_bitmap));
+    println!("abi: {}", hex0x(&dual.attestation.to_abi()));
 
-    // Verify FROST signature
-    let ok = frost_verify(msg_frost, &sig, &out)?;
-    println!("FROST signature valid: {}", ok);
-    assert!(ok);
+    // full_demo only returns once the signature verified
+    println!("FROST signature valid: true");
 
     Ok(())
 }
@@ -0,0 +1,387 @@
+//! Blind threshold signing (`blind` feature): the committee signs a message it never sees,
+//! and the result is an ordinary FROST signature under the blind key X_b = X + t·G with
+//! t = H("blind" || X). Signers cannot see what they sign, so blind signatures must never
+//! verify under the attestation key X: every share is taken over x_i + t (Σ λ_i = 1, so the
+//! shares combine to x + t), and a requester cannot obtain an attestation, beacon proof or
+//! randomness certificate this way.
+//!
+//! 1. each signer commits to nonces (D_i, E_i);
+//! 2. the requester derives R = Σ (D_i + ρ_i·E_i), picks α, β and computes
+//!    R' = R + α·G + β·X_b, c' = H2(R', X_b, m), and sends only the blinded c = c' + β;
+//! 3. each signer answers z_i = d_i + ρ_i·e_i + λ_i·(x_i + t)·c;
+//! 4. the requester checks every z_i, and (R', Σ z_i + α) is a signature on m under X_b.
+//!
+//! Binding factors ρ_i cover the session id and the commitment list but not the message,
+//! which the signers cannot know. Signers only take part under an explicit
+//! `BlindSigningPolicy::Allow`, which also caps concurrently open blind sessions: many
+//! parallel sessions are what ROS-style forgeries against blind Schnorr need. A session the
+//! requester never completes expires after `session_ttl` seconds (or is dropped with
+//! `Participant::abort_blind_session`), so abandoned sessions do not hold the cap forever.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use frost_core::Ciphersuite;
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::ddh_dvrf::{id_as_u64, validated_vk_share};
+use crate::dkg::{Identifier, PublicKeyPackage};
+use crate::lagrange::lagrange_coefficients;
+use crate::messages::AttestationMessage;
+use crate::participant::SessionId;
+use crate::utils::{hash_to_scalar_keccak, point_bytes_compressed};
+
+const BINDING_DOMAIN: &[u8] = b"frostlab/blind-frost/rho";
+const BLIND_KEY_DOMAIN: &[u8] = b"frostlab/blind-frost/key";
+
+/// Whether a participant takes part in blind signing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlindSigningPolicy {
+    #[default]
+    Refuse,
+    /// `session_ttl`: seconds an unanswered session keeps its nonces
+    Allow { max_open_sessions: usize, session_ttl: u64 },
+}
+
+/// A signer's round-1 commitments (D_i, E_i)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindCommitment {
+    pub id: Identifier,
+    pub hiding: ProjectivePoint,
+    pub binding: ProjectivePoint,
+}
+
+/// What the requester sends to every signer: the commitment list and the blinded challenge
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindChallenge {
+    pub session_id: SessionId,
+    /// ascending by identifier
+    pub commitments: Vec<BlindCommitment>,
+    pub c: Scalar,
+}
+
+impl BlindChallenge {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.session_id.to_vec();
+        out.extend_from_slice(&self.c.to_bytes());
+        for cm in &self.commitments {
+            out.extend_from_slice(&cm.id.serialize());
+            out.extend_from_slice(&point_bytes_compressed(&cm.hiding));
+            out.extend_from_slice(&point_bytes_compressed(&cm.binding));
+        }
+        out
+    }
+
+    fn signers(&self) -> Vec<Identifier> {
+        self.commitments.iter().map(|c| c.id).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindShare {
+    pub id: Identifier,
+    pub z: Scalar,
+}
+
+/// ρ_i = H(domain || X || session id || commitment list || i)
+fn binding_factor(group_key: &ProjectivePoint, session_id: &SessionId, commitments: &[BlindCommitment], id: Identifier) -> Scalar {
+    let mut buf = BINDING_DOMAIN.to_vec();
+    buf.extend_from_slice(&point_bytes_compressed(group_key));
+    buf.extend_from_slice(session_id);
+    for cm in commitments {
+        buf.extend_from_slice(&cm.id.serialize());
+        buf.extend_from_slice(&point_bytes_compressed(&cm.hiding));
+        buf.extend_from_slice(&point_bytes_compressed(&cm.binding));
+    }
+    buf.extend_from_slice(&id.serialize());
+    hash_to_scalar_keccak(&buf)
+}
+
+/// Commitment list sorted, distinct, committee members only, at least `threshold` long
+fn check_commitments(commitments: &[BlindCommitment], pkpkg: &PublicKeyPackage, threshold: u16) -> Result<()> {
+    if commitments.len() < threshold as usize {
+        bail!("need {} signers, got {}", threshold, commitments.len());
+    }
+    if commitments.windows(2).any(|w| w[0].id >= w[1].id) {
+        bail!("commitments must be sorted by identifier without duplicates");
+    }
+    if let Some(cm) = commitments.iter().find(|cm| !pkpkg.verifying_shares().contains_key(&cm.id)) {
+        bail!("commitment from non-member id={}", id_as_u64(cm.id));
+    }
+    Ok(())
+}
+
+/// t = H(domain || X)
+fn key_tweak(pkpkg: &PublicKeyPackage) -> Scalar {
+    let x = pkpkg.verifying_key().to_element();
+    hash_to_scalar_keccak(&[BLIND_KEY_DOMAIN, &point_bytes_compressed(&x)[..]].concat())
+}
+
+/// X_b = X + t·G
+fn blind_key_point(pkpkg: &PublicKeyPackage) -> ProjectivePoint {
+    pkpkg.verifying_key().to_element() + ProjectivePoint::GENERATOR * key_tweak(pkpkg)
+}
+
+/// The key blind signatures verify under; distinct from the group's attestation key
+pub fn blind_verifying_key(pkpkg: &PublicKeyPackage) -> Result<frost::VerifyingKey> {
+    Ok(frost::VerifyingKey::deserialize(&point_bytes_compressed(&blind_key_point(pkpkg)))?)
+}
+
+struct BlindSession {
+    /// (d_i, e_i)
+    nonces: (Scalar, Scalar),
+    commitment: BlindCommitment,
+    /// unix seconds of round 1
+    opened_at: u64,
+}
+
+/// Participant-side blind-signing state; a session is dropped as soon as it has signed
+#[derive(Default)]
+pub(crate) struct BlindSessions {
+    pub(crate) policy: BlindSigningPolicy,
+    sessions: BTreeMap<SessionId, BlindSession>,
+}
+
+impl BlindSessions {
+    /// Drop sessions older than the policy's ttl
+    fn expire(&mut self, now: u64) {
+        if let BlindSigningPolicy::Allow { session_ttl, .. } = self.policy {
+            self.sessions.retain(|_, s| now.saturating_sub(s.opened_at) <= session_ttl);
+        }
+    }
+
+    pub(crate) fn abort(&mut self, session_id: &SessionId) {
+        self.sessions.remove(session_id);
+    }
+
+    fn check_policy(&self, new_session: bool) -> Result<()> {
+        match self.policy {
+            BlindSigningPolicy::Refuse => bail!("blind signing refused by policy"),
+            BlindSigningPolicy::Allow { max_open_sessions, .. } => {
+                let open = self.sessions.len();
+                if new_session && open >= max_open_sessions {
+                    bail!("{} blind sessions already open", open);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn commit<R: RngCore + CryptoRng>(&mut self, id: Identifier, session_id: SessionId, now: u64, rng: &mut R) -> Result<BlindCommitment> {
+        self.expire(now);
+        let new_session = !self.sessions.contains_key(&session_id);
+        self.check_policy(new_session)?;
+        if new_session {
+            let (d, e) = (Scalar::random(&mut *rng), Scalar::random(&mut *rng));
+            let commitment = BlindCommitment {
+                id,
+                hiding: ProjectivePoint::GENERATOR * d,
+                binding: ProjectivePoint::GENERATOR * e,
+            };
+            self.sessions.insert(session_id, BlindSession { nonces: (d, e), commitment, opened_at: now });
+        }
+        Ok(self.sessions[&session_id].commitment)
+    }
+
+    /// z_i for `challenge`; the session's nonces answer one challenge only
+    pub(crate) fn sign(
+        &mut self,
+        x_i: Scalar,
+        pkpkg: &PublicKeyPackage,
+        threshold: u16,
+        challenge: &BlindChallenge,
+        now: u64,
+    ) -> Result<BlindShare> {
+        self.expire(now);
+        self.check_policy(false)?;
+        let session = self.sessions.get(&challenge.session_id)
+            .ok_or_else(|| anyhow!("no blind nonces for session"))?;
+        check_commitments(&challenge.commitments, pkpkg, threshold)?;
+        if !challenge.commitments.contains(&session.commitment) {
+            bail!("challenge does not carry this signer's commitment");
+        }
+
+        let id = session.commitment.id;
+        let rho = binding_factor(&blind_key_point(pkpkg), &challenge.session_id, &challenge.commitments, id);
+        let lambda = lagrange_coefficients(&challenge.signers())?[&id];
+        let (d, e) = self.sessions.remove(&challenge.session_id).expect("checked above").nonces;
+        Ok(BlindShare { id, z: d + rho * e + lambda * (x_i + key_tweak(pkpkg)) * challenge.c })
+    }
+}
+
+/// Requester side: holds the blinding factors between challenge and unblinding
+pub struct BlindRequest {
+    msg: AttestationMessage,
+    challenge: BlindChallenge,
+    alpha: Scalar,
+    r_prime: ProjectivePoint,
+}
+
+impl BlindRequest {
+    /// Blind `msg` against the signers' commitments; send the returned challenge to all of them
+    pub fn new<R: RngCore + CryptoRng>(
+        msg: &AttestationMessage,
+        pkpkg: &PublicKeyPackage,
+        threshold: u16,
+        session_id: SessionId,
+        mut commitments: Vec<BlindCommitment>,
+        rng: &mut R,
+    ) -> Result<(Self, BlindChallenge)> {
+        commitments.sort_by_key(|c| c.id);
+        check_commitments(&commitments, pkpkg, threshold)?;
+        let x = blind_key_point(pkpkg);
+        let r = commitments.iter().fold(ProjectivePoint::IDENTITY, |acc, cm| {
+            acc + cm.hiding + cm.binding * binding_factor(&x, &session_id, &commitments, cm.id)
+        });
+        let (alpha, beta) = (Scalar::random(&mut *rng), Scalar::random(&mut *rng));
+        let r_prime = r + ProjectivePoint::GENERATOR * alpha + x * beta;
+        let c_prime = frost::Secp256K1Keccak256::challenge(&r_prime, &blind_verifying_key(pkpkg)?, &msg.to_signing_bytes())?.to_scalar();
+        let challenge = BlindChallenge { session_id, commitments, c: c_prime + beta };
+        Ok((Self { msg: msg.clone(), challenge: challenge.clone(), alpha, r_prime }, challenge))
+    }
+
+    /// Check every share, then unblind into a FROST signature on the message under
+    /// `blind_verifying_key`
+    pub fn unblind(self, pkpkg: &PublicKeyPackage, shares: &[BlindShare]) -> Result<frost::Signature> {
+        let x = blind_key_point(pkpkg);
+        let t_g = ProjectivePoint::GENERATOR * key_tweak(pkpkg);
+        let signers = self.challenge.signers();
+        let lambdas = lagrange_coefficients(&signers)?;
+        let by_id: BTreeMap<Identifier, Scalar> = shares.iter().map(|s| (s.id, s.z)).collect();
+        if by_id.len() != shares.len() || !by_id.keys().eq(signers.iter()) {
+            bail!("expected exactly one share from each of the {} signers", signers.len());
+        }
+        let mut z = Scalar::ZERO;
+        for cm in &self.challenge.commitments {
+            let z_i = by_id[&cm.id];
+            let rho = binding_factor(&x, &self.challenge.session_id, &self.challenge.commitments, cm.id);
+            let vk_i = *validated_vk_share(pkpkg, cm.id)? + t_g;
+            let expected = cm.hiding + cm.binding * rho + vk_i * (lambdas[&cm.id] * self.challenge.c);
+            if ProjectivePoint::GENERATOR * z_i != expected {
+                bail!("invalid blind signature share from id={}", id_as_u64(cm.id));
+            }
+            z += z_i;
+        }
+
+        let mut bytes = point_bytes_compressed(&self.r_prime).to_vec();
+        bytes.extend_from_slice(&(z + self.alpha).to_bytes());
+        let sig = frost::Signature::deserialize(&bytes)?;
+        if blind_verifying_key(pkpkg)?.verify(&self.msg.to_signing_bytes(), &sig).is_err() {
+            bail!("unblinded signature does not verify");
+        }
+        Ok(sig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::frost_ext::frost_verify_with_key;
+    use crate::participant::participants_from_dkg;
+
+    #[test]
+    fn test_blind_signature_verifies_as_frost() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let sid = [5u8; 32];
+
+        // refused until the policy allows it
+        assert!(parts.get_mut(&ids[0]).unwrap().blind_commit(sid, &mut rng).is_err());
+        for p in parts.values_mut() {
+            p.set_blind_policy(BlindSigningPolicy::Allow { max_open_sessions: 1, session_ttl: 60 });
+        }
+
+        let signers = &ids[1..];
+        let mut commitments = Vec::new();
+        for id in signers {
+            commitments.push(parts.get_mut(id).unwrap().blind_commit(sid, &mut rng)?);
+        }
+        // one open session per signer
+        assert!(parts.get_mut(&ids[1]).unwrap().blind_commit([6u8; 32], &mut rng).is_err());
+
+        let msg = AttestationMessage::new(b"credential".to_vec());
+        let (req, challenge) = BlindRequest::new(&msg, &out.public_key_package, cfg.min_signers, sid, commitments, &mut rng)?;
+        let mut shares = Vec::new();
+        for id in signers {
+            shares.push(parts.get_mut(id).unwrap().blind_sign(&challenge)?);
+        }
+        // nonces answer one challenge only, and the signed session is gone
+        let other = BlindChallenge { c: challenge.c + Scalar::ONE, ..challenge.clone() };
+        assert!(parts.get_mut(&ids[1]).unwrap().blind_sign(&other).is_err());
+        assert!(parts.get_mut(&ids[1]).unwrap().blind_sign(&challenge).is_err());
+        parts.get_mut(&ids[1]).unwrap().blind_commit([6u8; 32], &mut rng)?;
+
+        let sig = req.unblind(&out.public_key_package, &shares)?;
+        assert!(frost_verify_with_key(&msg, &sig, &blind_verifying_key(&out.public_key_package)?)?);
+        assert_eq!(parts[&ids[1]].audit_log().entries().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_blind_signature_is_not_an_attestation() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        for p in parts.values_mut() {
+            p.set_blind_policy(BlindSigningPolicy::Allow { max_open_sessions: 1, session_ttl: 60 });
+        }
+        let sid = [7u8; 32];
+        let mut commitments = Vec::new();
+        for id in &ids[..2] {
+            commitments.push(parts.get_mut(id).unwrap().blind_commit(sid, &mut rng)?);
+        }
+
+        // a commitment under an id outside the committee is refused on both sides
+        let outsider = BlindCommitment { id: Identifier::try_from(9u16)?, ..commitments[0] };
+        let with_outsider = vec![commitments[0], commitments[1], outsider];
+        assert!(BlindRequest::new(&AttestationMessage::new(b"x".to_vec()), &out.public_key_package, 2, sid, with_outsider.clone(), &mut rng).is_err());
+        let forged = BlindChallenge { session_id: sid, commitments: with_outsider, c: Scalar::ONE };
+        assert!(parts.get_mut(&ids[0]).unwrap().blind_sign(&forged).is_err());
+
+        // whatever the requester blinds, the result never verifies under the attestation key
+        let msg = AttestationMessage::new(b"round attestation bytes".to_vec());
+        let (req, challenge) = BlindRequest::new(&msg, &out.public_key_package, cfg.min_signers, sid, commitments, &mut rng)?;
+        let shares = ids[..2].iter().map(|id| parts.get_mut(id).unwrap().blind_sign(&challenge)).collect::<Result<Vec<_>>>()?;
+        let sig = req.unblind(&out.public_key_package, &shares)?;
+        assert!(!frost_verify_with_key(&msg, &sig, out.public_key_package.verifying_key())?);
+        assert_ne!(blind_verifying_key(&out.public_key_package)?, *out.public_key_package.verifying_key());
+        Ok(())
+    }
+
+    #[test]
+    fn test_abandoned_blind_sessions_free_their_slot() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let p = parts.values_mut().next().unwrap();
+        let now = Arc::new(AtomicU64::new(1_000));
+        let clock = now.clone();
+        p.set_clock(move || clock.load(Ordering::SeqCst));
+        p.set_blind_policy(BlindSigningPolicy::Allow { max_open_sessions: 1, session_ttl: 60 });
+
+        // the requester walks away after round 1: the slot is held until the ttl runs out
+        let first = p.blind_commit([1u8; 32], &mut rng)?;
+        now.store(1_060, Ordering::SeqCst);
+        assert!(p.blind_commit([2u8; 32], &mut rng).is_err());
+        // then its nonces are gone and the same id opens a fresh session
+        now.store(1_061, Ordering::SeqCst);
+        assert_ne!(p.blind_commit([1u8; 32], &mut rng)?, first);
+        assert!(p.blind_commit([2u8; 32], &mut rng).is_err());
+
+        // or the session is dropped explicitly
+        p.abort_blind_session(&[1u8; 32]);
+        p.blind_commit([2u8; 32], &mut rng)?;
+        Ok(())
+    }
+}
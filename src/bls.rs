@@ -0,0 +1,593 @@
+//! Threshold BLS over BLS12-381 (`bls` feature): an alternative scheme for signing round
+//! attestations, for verifiers that prefer one pairing check over a Schnorr verification.
+//!
+//! Same committee as the FROST path — shares are indexed by the FROST `Identifier`s, signer
+//! sets come from `SignerSelection`, finished sessions go to the coordinator's `SessionStore`.
+//! Keys are in G1 (48 bytes), signatures in G2 (96 bytes), and the signed bytes are
+//! `AttestationMessage::to_signing_bytes`, so the domain separation matches FROST.
+//!
+//! Signatures are deterministic: any t valid partials combine to the same group signature.
+//! BLS shares come from their own Feldman DKG among the same members (`bls_dkg_part1..3`, or
+//! `run_bls_dkg` locally); each `Participant` holds its share and signs for itself.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, bail, Result};
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{pairing, G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use frost_secp256k1_evm::rand_core::{CryptoRng, RngCore};
+use sha2_09::Digest;
+
+use crate::cert::{CertConfig, QuorumCert};
+use crate::ddh_dvrf::{id_as_u64, run_ddh_dvrf_cert};
+use crate::dkg::{DkgOutput, Identifier, PublicKeyPackage};
+use crate::messages::{AttestationMessage, DvrfInput};
+use crate::round_attestation::RoundAttestation;
+use crate::selection::SignerSelection;
+
+/// Ciphersuite of the IETF BLS draft, proof-of-possession scheme, signatures in G2
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+fn hash_to_g2(msg: &AttestationMessage) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2_09::Sha256>>>::hash_to_curve(msg.to_signing_bytes(), BLS_DST)
+}
+
+/// The identifier's full 32-byte scalar, reduced into BLS12-381's scalar field
+fn id_scalar(id: Identifier) -> Scalar {
+    let mut wide = [0u8; 64];
+    for (k, b) in id.serialize().iter().rev().enumerate() {
+        wide[k] = *b;
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Scalar {
+    let mut wide = [0u8; 64];
+    rng.fill_bytes(&mut wide);
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// λ_i at x = 0 over BLS12-381's scalar field; fails when two identifiers reduce to the same x
+fn lagrange_at_zero(id: Identifier, signers: &[Identifier]) -> Result<Scalar> {
+    let x_i = id_scalar(id);
+    let (mut num, mut den) = (Scalar::one(), Scalar::one());
+    for other in signers.iter().filter(|j| **j != id) {
+        let x_j = id_scalar(*other);
+        num *= x_j;
+        den *= x_j - x_i;
+    }
+    Option::from(den.invert())
+        .map(|inv| num * inv)
+        .ok_or_else(|| anyhow!("identifier id={} collides in the BLS scalar field", id_as_u64(id)))
+}
+
+/// One member's secret share
+#[derive(Clone)]
+pub struct BlsKeyShare {
+    pub id: Identifier,
+    secret: Scalar,
+}
+
+/// Group key and every member's verifying share
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlsPublicKeyPackage {
+    pub group_key: G1Affine,
+    pub verifying_shares: BTreeMap<Identifier, G1Affine>,
+    pub min_signers: u16,
+}
+
+/// Every member's share of a local run (tests, demos); deployments hold one share per node
+pub struct BlsKeys {
+    pub shares: BTreeMap<Identifier, BlsKeyShare>,
+    pub public_key_package: BlsPublicKeyPackage,
+}
+
+fn eval_poly(coeffs: &[Scalar], x: Scalar) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::zero(), |acc, a| acc * x + a)
+}
+
+/// Σ_k C_k x^k, the Feldman commitment to f(x)
+fn eval_commitments(commitments: &[G1Affine], x: Scalar) -> G1Projective {
+    commitments.iter().rev().fold(G1Projective::identity(), |acc, c| acc * x + c)
+}
+
+/// Deal t-of-n BLS shares to `ids`; a trusted dealer, kept to cross-check the DKG
+#[cfg(test)]
+fn bls_keygen<R: RngCore + CryptoRng>(ids: &[Identifier], min_signers: u16, rng: &mut R) -> Result<BlsKeys> {
+    let distinct: BTreeSet<Identifier> = ids.iter().copied().collect();
+    if distinct.len() != ids.len() {
+        bail!("duplicate identifier");
+    }
+    if min_signers < 2 || min_signers as usize > ids.len() {
+        bail!("threshold {} out of range for {} members", min_signers, ids.len());
+    }
+    let coeffs: Vec<Scalar> = (0..min_signers).map(|_| random_scalar(rng)).collect();
+    let mut shares = BTreeMap::new();
+    let mut verifying_shares = BTreeMap::new();
+    for id in distinct {
+        let secret = eval_poly(&coeffs, id_scalar(id));
+        verifying_shares.insert(id, G1Affine::from(G1Projective::generator() * secret));
+        shares.insert(id, BlsKeyShare { id, secret });
+    }
+    let group_key = G1Affine::from(G1Projective::generator() * coeffs[0]);
+    Ok(BlsKeys { shares, public_key_package: BlsPublicKeyPackage { group_key, verifying_shares, min_signers } })
+}
+
+/// Domain of the round-1 proof of knowledge
+pub const BLS_DKG_POK_DST: &[u8] = b"frostlab/bls-dkg/pok";
+
+/// Round-1 broadcast: Feldman commitments to the member's polynomial and a Schnorr proof of
+/// knowledge of its constant term (no rogue-key contributions)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlsRound1Package {
+    pub commitments: Vec<G1Affine>,
+    pub pok_r: G1Affine,
+    pub pok_z: Scalar,
+}
+
+/// Round-2 message: f_i(x_j) for one recipient; must travel over a private channel
+#[derive(Clone)]
+pub struct BlsRound2Package {
+    share: Scalar,
+}
+
+impl BlsRound2Package {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.share.to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        Option::from(Scalar::from_bytes(bytes))
+            .map(|share| Self { share })
+            .ok_or_else(|| anyhow!("non-canonical BLS DKG share"))
+    }
+}
+
+/// A member's secret polynomial, held between the DKG rounds
+pub struct BlsDkgSecret {
+    id: Identifier,
+    coeffs: Vec<Scalar>,
+    max_signers: u16,
+}
+
+impl BlsDkgSecret {
+    fn commitments(&self) -> Vec<G1Affine> {
+        self.coeffs.iter().map(|a| G1Affine::from(G1Projective::generator() * a)).collect()
+    }
+}
+
+fn pok_challenge(id: Identifier, c0: &G1Affine, r: &G1Affine) -> Scalar {
+    let mut buf = BLS_DKG_POK_DST.to_vec();
+    buf.extend_from_slice(&id.serialize());
+    buf.extend_from_slice(&c0.to_compressed());
+    buf.extend_from_slice(&r.to_compressed());
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(&sha2_09::Sha512::digest(&buf));
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Round 1: sample a degree t-1 polynomial, commit to it, prove knowledge of f(0)
+pub fn bls_dkg_part1<R: RngCore + CryptoRng>(
+    id: Identifier,
+    max_signers: u16,
+    min_signers: u16,
+    rng: &mut R,
+) -> Result<(BlsDkgSecret, BlsRound1Package)> {
+    if min_signers < 2 || min_signers > max_signers {
+        bail!("threshold {} out of range for {} members", min_signers, max_signers);
+    }
+    if id_scalar(id) == Scalar::zero() {
+        bail!("identifier id={} is zero in the BLS scalar field", id_as_u64(id));
+    }
+    let secret = BlsDkgSecret { id, coeffs: (0..min_signers).map(|_| random_scalar(rng)).collect(), max_signers };
+    let commitments = secret.commitments();
+    let k = random_scalar(rng);
+    let pok_r = G1Affine::from(G1Projective::generator() * k);
+    let pok_z = k + secret.coeffs[0] * pok_challenge(id, &commitments[0], &pok_r);
+    Ok((secret, BlsRound1Package { commitments, pok_r, pok_z }))
+}
+
+/// Round 2: check everyone else's round-1 package, then one share per other member
+pub fn bls_dkg_part2(
+    secret: &BlsDkgSecret,
+    round1: &BTreeMap<Identifier, BlsRound1Package>,
+) -> Result<BTreeMap<Identifier, BlsRound2Package>> {
+    if round1.len() + 1 != secret.max_signers as usize || round1.contains_key(&secret.id) {
+        bail!("expected round-1 packages from the {} other members", secret.max_signers - 1);
+    }
+    let mut xs = BTreeSet::from([id_scalar(secret.id).to_bytes()]);
+    let mut out = BTreeMap::new();
+    for (j, pkg) in round1 {
+        let x_j = id_scalar(*j);
+        if x_j == Scalar::zero() || !xs.insert(x_j.to_bytes()) {
+            bail!("identifier id={} collides in the BLS scalar field", id_as_u64(*j));
+        }
+        if pkg.commitments.len() != secret.coeffs.len() {
+            bail!("round-1 package from id={} has the wrong degree", id_as_u64(*j));
+        }
+        let c = pok_challenge(*j, &pkg.commitments[0], &pkg.pok_r);
+        let lhs = G1Projective::generator() * pkg.pok_z;
+        if bool::from(pkg.commitments[0].is_identity()) || lhs != G1Projective::from(pkg.pok_r) + pkg.commitments[0] * c {
+            bail!("invalid proof of knowledge from id={}", id_as_u64(*j));
+        }
+        out.insert(*j, BlsRound2Package { share: eval_poly(&secret.coeffs, x_j) });
+    }
+    Ok(out)
+}
+
+/// Round 3: check every received share against its sender's commitments and sum them
+pub fn bls_dkg_part3(
+    secret: &BlsDkgSecret,
+    round1: &BTreeMap<Identifier, BlsRound1Package>,
+    round2: &BTreeMap<Identifier, BlsRound2Package>,
+) -> Result<(BlsKeyShare, BlsPublicKeyPackage)> {
+    if !round1.keys().eq(round2.keys()) {
+        bail!("round-2 shares do not match the round-1 senders");
+    }
+    let x_i = id_scalar(secret.id);
+    let mut share = eval_poly(&secret.coeffs, x_i);
+    let mut summed: Vec<G1Projective> = secret.commitments().iter().map(G1Projective::from).collect();
+    for (j, pkg) in round1 {
+        let s = round2[j].share;
+        if G1Projective::generator() * s != eval_commitments(&pkg.commitments, x_i) {
+            bail!("invalid DKG share from id={}", id_as_u64(*j));
+        }
+        share += s;
+        for (acc, c) in summed.iter_mut().zip(&pkg.commitments) {
+            *acc += c;
+        }
+    }
+    let summed: Vec<G1Affine> = summed.iter().map(G1Affine::from).collect();
+    let verifying_shares = round1
+        .keys()
+        .chain([&secret.id])
+        .map(|j| (*j, G1Affine::from(eval_commitments(&summed, id_scalar(*j)))))
+        .collect();
+    let public_key_package =
+        BlsPublicKeyPackage { group_key: summed[0], verifying_shares, min_signers: secret.coeffs.len() as u16 };
+    Ok((BlsKeyShare { id: secret.id, secret: share }, public_key_package))
+}
+
+/// All three DKG rounds for `ids`, run locally
+pub fn run_bls_dkg<R: RngCore + CryptoRng>(ids: &[Identifier], min_signers: u16, rng: &mut R) -> Result<BlsKeys> {
+    let distinct: BTreeSet<Identifier> = ids.iter().copied().collect();
+    if distinct.len() != ids.len() {
+        bail!("duplicate identifier");
+    }
+    let n = u16::try_from(ids.len())?;
+    let mut secrets = BTreeMap::new();
+    let mut round1 = BTreeMap::new();
+    for id in &distinct {
+        let (secret, pkg) = bls_dkg_part1(*id, n, min_signers, rng)?;
+        secrets.insert(*id, secret);
+        round1.insert(*id, pkg);
+    }
+    let others = |me: &Identifier| -> BTreeMap<Identifier, BlsRound1Package> {
+        round1.iter().filter(|(j, _)| *j != me).map(|(j, p)| (*j, p.clone())).collect()
+    };
+    let mut inbox: BTreeMap<Identifier, BTreeMap<Identifier, BlsRound2Package>> = BTreeMap::new();
+    for (id, secret) in &secrets {
+        for (to, pkg) in bls_dkg_part2(secret, &others(id))? {
+            inbox.entry(to).or_default().insert(*id, pkg);
+        }
+    }
+    let mut shares = BTreeMap::new();
+    let mut public_key_package = None;
+    for (id, secret) in &secrets {
+        let (share, pkg) = bls_dkg_part3(secret, &others(id), &inbox[id])?;
+        if public_key_package.as_ref().is_some_and(|p| *p != pkg) {
+            bail!("members derived different BLS public key packages");
+        }
+        public_key_package = Some(pkg);
+        shares.insert(*id, share);
+    }
+    Ok(BlsKeys { shares, public_key_package: public_key_package.expect("at least one member") })
+}
+
+/// BLS shares for the committee of a FROST DKG output (same identifiers and threshold), from
+/// a BLS DKG among its members
+pub fn bls_keygen_for<R: RngCore + CryptoRng>(out: &DkgOutput, rng: &mut R) -> Result<BlsKeys> {
+    run_bls_dkg(&out.all_ids(), out.min_signers(), rng)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlsPartialSignature {
+    pub id: Identifier,
+    pub sig: G2Affine,
+}
+
+/// Aggregate signature, 96-byte compressed G2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlsSignature(pub G2Affine);
+
+impl BlsSignature {
+    pub fn to_bytes(&self) -> [u8; 96] {
+        self.0.to_compressed()
+    }
+
+    /// Rejects non-canonical encodings and points outside the prime-order subgroup
+    pub fn from_bytes(bytes: &[u8; 96]) -> Result<Self> {
+        Option::from(G2Affine::from_compressed(bytes))
+            .map(Self)
+            .ok_or_else(|| anyhow!("invalid BLS signature encoding"))
+    }
+}
+
+impl BlsKeyShare {
+    pub fn sign(&self, msg: &AttestationMessage) -> BlsPartialSignature {
+        BlsPartialSignature { id: self.id, sig: G2Affine::from(hash_to_g2(msg) * self.secret) }
+    }
+}
+
+fn pairing_check(pk: &G1Affine, msg: &AttestationMessage, sig: &G2Affine) -> bool {
+    bool::from(!pk.is_identity()) && pairing(pk, &G2Affine::from(hash_to_g2(msg))) == pairing(&G1Affine::generator(), sig)
+}
+
+impl BlsPublicKeyPackage {
+    pub fn verify_partial(&self, msg: &AttestationMessage, partial: &BlsPartialSignature) -> bool {
+        self.verifying_shares.get(&partial.id).is_some_and(|vk| pairing_check(vk, msg, &partial.sig))
+    }
+
+    /// Check every partial, then Lagrange-combine; needs `min_signers` distinct members
+    pub fn combine(&self, msg: &AttestationMessage, partials: &[BlsPartialSignature]) -> Result<BlsSignature> {
+        if partials.len() < self.min_signers as usize {
+            bail!("need {} partial signatures, got {}", self.min_signers, partials.len());
+        }
+        let ids: Vec<Identifier> = partials.iter().map(|p| p.id).collect();
+        if ids.iter().collect::<BTreeSet<_>>().len() != ids.len() {
+            bail!("duplicate partial signature");
+        }
+        let mut acc = G2Projective::identity();
+        for p in partials {
+            if !self.verify_partial(msg, p) {
+                bail!("invalid partial signature from id={}", id_as_u64(p.id));
+            }
+            acc += G2Projective::from(p.sig) * lagrange_at_zero(p.id, &ids)?;
+        }
+        Ok(BlsSignature(G2Affine::from(acc)))
+    }
+
+    pub fn verify(&self, msg: &AttestationMessage, sig: &BlsSignature) -> bool {
+        pairing_check(&self.group_key, msg, &sig.0)
+    }
+}
+
+/// Which scheme signs a round attestation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttestationScheme {
+    Frost,
+    Bls,
+}
+
+/// Who signs a round attestation: the FROST group, or the same committee's BLS shares
+#[derive(Clone, Copy)]
+pub enum AttestationSigner<'a> {
+    Frost,
+    Bls(&'a BlsKeys),
+}
+
+/// A round attestation's group signature under either scheme
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttestationSignature {
+    Frost(frost_secp256k1_evm::Signature),
+    Bls(BlsSignature),
+}
+
+impl AttestationSignature {
+    pub fn scheme(&self) -> AttestationScheme {
+        match self {
+            AttestationSignature::Frost(_) => AttestationScheme::Frost,
+            AttestationSignature::Bls(_) => AttestationScheme::Bls,
+        }
+    }
+}
+
+/// `DualOutput` with the attestation signed under a chosen scheme
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemeDualOutput {
+    pub cert: QuorumCert,
+    pub attestation: RoundAttestation,
+    pub signature: AttestationSignature,
+}
+
+impl SchemeDualOutput {
+    /// As `DualOutput::verify`; a BLS signature needs the BLS package
+    pub fn verify<'a>(
+        &self,
+        public_key_package: &PublicKeyPackage,
+        bls: Option<&BlsPublicKeyPackage>,
+        config: impl Into<CertConfig<'a>>,
+    ) -> Result<()> {
+        self.cert.verify(public_key_package, config)?;
+        let expected = RoundAttestation::from_cert(self.attestation.epoch, self.attestation.round, &self.cert)?;
+        if expected != self.attestation {
+            bail!("attestation does not match the certificate");
+        }
+        let msg = self.attestation.message();
+        let ok = match &self.signature {
+            AttestationSignature::Frost(sig) => {
+                crate::frost_ext::frost_verify_with_key(&msg, sig, public_key_package.verifying_key())?
+            }
+            AttestationSignature::Bls(sig) => {
+                let Some(bls) = bls else { bail!("BLS-signed attestation needs the BLS public key package") };
+                bls.verify(&msg, sig)
+            }
+        };
+        if !ok {
+            bail!("invalid group signature on round attestation");
+        }
+        Ok(())
+    }
+}
+
+/// Dual-output round whose attestation is signed by `signer`, over the selected signers
+pub fn run_dual_round_with_scheme<R: RngCore + CryptoRng>(
+    epoch: u64,
+    round: u64,
+    msg: &DvrfInput,
+    out: &DkgOutput,
+    signer: AttestationSigner,
+    selection: SignerSelection,
+    rng: &mut R,
+) -> Result<SchemeDualOutput> {
+    let signers = selection.select(&out.all_ids(), out.min_signers())?;
+    let cert = run_ddh_dvrf_cert(msg, &out.key_packages, &out.public_key_package, &signers);
+    let attestation = RoundAttestation::from_cert(epoch, round, &cert)?;
+    let signature = match signer {
+        AttestationSigner::Frost => {
+            AttestationSignature::Frost(crate::frost_ext::frost_sign(&attestation.message(), out, &signers, rng)?)
+        }
+        AttestationSigner::Bls(keys) => AttestationSignature::Bls(bls_sign(&attestation.message(), keys, &signers)?),
+    };
+    Ok(SchemeDualOutput { cert, attestation, signature })
+}
+
+/// Local BLS round: every signer signs, the partials are checked and combined
+pub fn bls_sign(msg: &AttestationMessage, keys: &BlsKeys, signers: &[Identifier]) -> Result<BlsSignature> {
+    let mut partials = Vec::with_capacity(signers.len());
+    for id in signers {
+        let Some(share) = keys.shares.get(id) else { bail!("no BLS share for id={}", id_as_u64(*id)) };
+        partials.push(share.sign(msg));
+    }
+    keys.public_key_package.combine(msg, &partials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_bls_threshold_signature_is_deterministic() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(5, 3)?, &mut OsRng)?;
+        let keys = bls_keygen_for(&out, &mut OsRng)?;
+        let ids = out.all_ids();
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+
+        let a = bls_sign(&msg, &keys, &ids[..3])?;
+        let b = bls_sign(&msg, &keys, &[ids[1], ids[3], ids[4]])?;
+        assert_eq!(a, b);
+        assert!(keys.public_key_package.verify(&msg, &a));
+        assert!(!keys.public_key_package.verify(&AttestationMessage::new(b"other".to_vec()), &a));
+        assert_eq!(BlsSignature::from_bytes(&a.to_bytes())?, a);
+
+        assert!(bls_sign(&msg, &keys, &ids[..2]).is_err());
+        // a partial from the wrong share is caught before combining
+        let mut forged = keys.shares[&ids[0]].sign(&msg);
+        forged.id = ids[1];
+        assert!(keys.public_key_package.combine(&msg, &[forged, keys.shares[&ids[2]].sign(&msg), keys.shares[&ids[3]].sign(&msg)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scheme_selectable_per_attestation() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let keys = bls_keygen_for(&out, &mut OsRng)?;
+        let msg = DvrfInput::new(b"round".to_vec());
+        let bls_pk = Some(&keys.public_key_package);
+
+        for (signer, scheme) in [(AttestationSigner::Frost, AttestationScheme::Frost), (AttestationSigner::Bls(&keys), AttestationScheme::Bls)] {
+            let dual = run_dual_round_with_scheme(1, 1, &msg, &out, signer, SignerSelection::FirstT, &mut OsRng)?;
+            assert_eq!(dual.signature.scheme(), scheme);
+            dual.verify(&out.public_key_package, bls_pk, cfg.min_signers)?;
+        }
+        let dual = run_dual_round_with_scheme(1, 2, &msg, &out, AttestationSigner::Bls(&keys), SignerSelection::FirstT, &mut OsRng)?;
+        assert!(dual.verify(&out.public_key_package, None, cfg.min_signers).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bls_sessions_go_through_the_store() -> Result<()> {
+        use std::sync::Arc;
+        use crate::coordinator::{derive_session_id, Coordinator, MemorySessionStore};
+
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let keys = bls_keygen_for(&out, &mut OsRng)?;
+        let ids = out.all_ids();
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let mut parts = crate::participant::participants_from_dkg(&out)?;
+        for (id, share) in &keys.shares {
+            parts.get_mut(id).expect("member").set_bls_share(share.clone())?;
+        }
+        assert!(parts.get_mut(&ids[0]).expect("member").set_bls_share(keys.shares[&ids[1]].clone()).is_err());
+
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"bls", 1, msg.raw());
+        let sig = coord.run_bls_signing(sid, &msg, &keys.public_key_package, &mut parts, &ids[..3])?;
+        assert_eq!(coord.run_bls_signing(sid, &msg, &keys.public_key_package, &mut parts, &ids[1..])?, sig);
+        assert!(keys.public_key_package.verify(&msg, &sig));
+        assert_eq!(parts[&ids[0]].audit_log().entries().len(), 1);
+        // a FROST session id is not a BLS session
+        let dvrf = DvrfInput::new(b"m".to_vec());
+        assert!(coord.run_dvrf(sid, &dvrf, &mut parts, &ids[..3]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bls_dkg_matches_dealt_scheme() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(4, 3)?, &mut OsRng)?;
+        let ids = out.all_ids();
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        for keys in [run_bls_dkg(&ids, 3, &mut OsRng)?, bls_keygen(&ids, 3, &mut OsRng)?] {
+            let pk = &keys.public_key_package;
+            for (id, share) in &keys.shares {
+                assert_eq!(pk.verifying_shares[id], G1Affine::from(G1Projective::generator() * share.secret));
+            }
+            let sig = bls_sign(&msg, &keys, &ids[1..])?;
+            assert!(pk.verify(&msg, &sig));
+            assert_eq!(bls_sign(&msg, &keys, &ids[..3])?, sig);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bls_dkg_blames_bad_share() -> Result<()> {
+        let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().expect("nonzero")).collect();
+        let mut secrets = BTreeMap::new();
+        let mut round1 = BTreeMap::new();
+        for id in &ids {
+            let (s, p) = bls_dkg_part1(*id, 3, 2, &mut OsRng)?;
+            secrets.insert(*id, s);
+            round1.insert(*id, p);
+        }
+        let others = |me: Identifier| -> BTreeMap<_, _> { round1.iter().filter(|(j, _)| **j != me).map(|(j, p)| (*j, p.clone())).collect() };
+
+        // forged proof of knowledge
+        let mut forged = others(ids[0]);
+        forged.get_mut(&ids[1]).expect("member").pok_z += Scalar::one();
+        let err = bls_dkg_part2(&secrets[&ids[0]], &forged).err().expect("forged proof");
+        assert!(err.to_string().contains("proof of knowledge"), "{err}");
+
+        // a share that does not match its sender's commitments
+        let mut inbox = BTreeMap::new();
+        for id in &ids[1..] {
+            let mut pkgs = bls_dkg_part2(&secrets[id], &others(*id))?;
+            inbox.insert(*id, pkgs.remove(&ids[0]).expect("share for member 1"));
+        }
+        inbox.get_mut(&ids[2]).expect("member").share += Scalar::one();
+        let err = bls_dkg_part3(&secrets[&ids[0]], &others(ids[0]), &inbox).err().expect("bad share");
+        assert!(err.to_string().contains(&format!("id={}", id_as_u64(ids[2]))), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_colliding_identifiers_rejected_not_panicking() -> Result<()> {
+        // 1 and 1 + r are distinct secp256k1 identifiers but the same BLS12-381 scalar
+        let r_plus_one = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000002")?;
+        let one: Identifier = 1u16.try_into().expect("nonzero");
+        let other = Identifier::deserialize(&r_plus_one)?;
+        let two: Identifier = 2u16.try_into().expect("nonzero");
+        assert!(lagrange_at_zero(one, &[one, other]).is_err());
+        assert!(run_bls_dkg(&[one, other, two], 2, &mut OsRng).is_err());
+        // identifiers that only differ above the low 64 bits no longer collide
+        let mut high = [0u8; 32];
+        high[0] = 1;
+        high[31] = 1;
+        let high = Identifier::deserialize(&high)?;
+        assert!(lagrange_at_zero(one, &[one, high]).is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+//! Broadcast channels for the DKG round-1 packages.
+//!
+//! - `MemoryChannel`: in-process, for tests and local runs
+//! - `DirChannel`: one file per sender in a shared directory (air-gapped setups, sneakernet)
+//! - `ContractChannel`: packages are posted to a contract and read back from its event log,
+//!   so the DKG transcript is anchored on-chain. A log only counts for the member whose
+//!   registered account signed the transaction; the sender word in the event is not trusted.
+//!
+//! Every channel refuses a second, different package from the same sender.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::dkg::Identifier;
+use crate::encoding::{from_hex0x, hex0x};
+use crate::utils::keccak256;
+
+pub trait BroadcastChannel {
+    fn publish(&mut self, sender: Identifier, payload: &[u8]) -> Result<()>;
+
+    /// Everything published so far, by sender
+    fn collect(&self) -> Result<BTreeMap<Identifier, Vec<u8>>>;
+}
+
+pub(crate) fn insert_once(map: &mut BTreeMap<Identifier, Vec<u8>>, sender: Identifier, payload: &[u8]) -> Result<()> {
+    match map.get(&sender) {
+        Some(prev) if prev != payload => bail!("sender {} published two different packages", hex0x(&sender.serialize())),
+        Some(_) => Ok(()),
+        None => {
+            map.insert(sender, payload.to_vec());
+            Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryChannel {
+    messages: BTreeMap<Identifier, Vec<u8>>,
+}
+
+impl BroadcastChannel for MemoryChannel {
+    fn publish(&mut self, sender: Identifier, payload: &[u8]) -> Result<()> {
+        insert_once(&mut self.messages, sender, payload)
+    }
+
+    fn collect(&self) -> Result<BTreeMap<Identifier, Vec<u8>>> {
+        Ok(self.messages.clone())
+    }
+}
+
+/// `<dir>/round1-0x<hex id>.bin`, written via a temp file + rename
+#[derive(Clone, Debug)]
+pub struct DirChannel {
+    dir: PathBuf,
+}
+
+impl DirChannel {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, sender: Identifier) -> PathBuf {
+        self.dir.join(format!("round1-{}.bin", hex0x(&sender.serialize())))
+    }
+
+    /// Scratch file no other publisher (thread or process) uses
+    fn scratch_path(&self, sender: Identifier) -> PathBuf {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        self.dir.join(format!("round1-{}.{}-{}.tmp", hex0x(&sender.serialize()), std::process::id(), n))
+    }
+}
+
+impl BroadcastChannel for DirChannel {
+    /// Written aside, then hard-linked into place: the link fails atomically if the package
+    /// already exists, so concurrent publishers can neither overwrite nor half-write it
+    fn publish(&mut self, sender: Identifier, payload: &[u8]) -> Result<()> {
+        let path = self.path(sender);
+        let tmp = self.scratch_path(sender);
+        fs::OpenOptions::new().write(true).create_new(true).open(&tmp).and_then(|mut f| io::Write::write_all(&mut f, payload))?;
+        let linked = fs::hard_link(&tmp, &path);
+        fs::remove_file(&tmp)?;
+        match linked {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if fs::read(&path)? != payload {
+                    bail!("{} already holds a different package", path.display());
+                }
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn collect(&self) -> Result<BTreeMap<Identifier, Vec<u8>>> {
+        let mut out = BTreeMap::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            let Some(hex_id) = name.strip_prefix("round1-").and_then(|s| s.strip_suffix(".bin")) else { continue };
+            let id = Identifier::deserialize(&from_hex0x(hex_id)?)?;
+            out.insert(id, fs::read(self.dir.join(&name))?);
+        }
+        Ok(out)
+    }
+}
+
+/// `publish(bytes32 dkgId, bytes32 sender, bytes payload)`
+pub const PUBLISH_SIGNATURE: &str = "publish(bytes32,bytes32,bytes)";
+/// `event Round1Published(bytes32 indexed dkgId, bytes32 sender, bytes payload)`
+pub const PUBLISHED_EVENT_SIGNATURE: &str = "Round1Published(bytes32,bytes32,bytes)";
+
+/// One event log together with the signer of the transaction that emitted it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventLog {
+    pub from: [u8; 20],
+    pub data: Vec<u8>,
+}
+
+/// Minimal view of an Ethereum node: send a transaction, read event logs
+pub trait ContractTransport {
+    /// Send `calldata` to `to`, signed by the local account `from` (as `eth_sendTransaction`)
+    fn send_transaction(&mut self, from: [u8; 20], to: [u8; 20], calldata: Vec<u8>) -> Result<()>;
+
+    /// Every log emitted by `address` matching `topics`, in chain order
+    fn logs(&self, address: [u8; 20], topics: &[[u8; 32]]) -> Result<Vec<EventLog>>;
+}
+
+fn word(n: usize) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[24..].copy_from_slice(&(n as u64).to_be_bytes());
+    w
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<[u8; 32]> {
+    let end = at.checked_add(32).ok_or_else(|| anyhow!("ABI offset overflow"))?;
+    Ok(data.get(at..end).ok_or_else(|| anyhow!("ABI data too short"))?.try_into().expect("32 bytes"))
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize> {
+    let w = read_word(data, at)?;
+    if w[..24].iter().any(|b| *b != 0) {
+        bail!("ABI word out of range");
+    }
+    usize::try_from(u64::from_be_bytes(w[24..].try_into().expect("8 bytes"))).map_err(|_| anyhow!("ABI word out of range"))
+}
+
+/// Static head words followed by dynamic `bytes` arguments
+pub(crate) fn abi_encode_words_bytes(words: &[[u8; 32]], bytes: &[&[u8]]) -> Vec<u8> {
+    let head = 32 * (words.len() + bytes.len());
+    let mut out = Vec::with_capacity(head + bytes.iter().map(|b| 32 + b.len().div_ceil(32) * 32).sum::<usize>());
+    for w in words {
+        out.extend_from_slice(w);
+    }
+    let mut off = head;
+    for b in bytes {
+        out.extend_from_slice(&word(off));
+        off += 32 + b.len().div_ceil(32) * 32;
+    }
+    for b in bytes {
+        out.extend_from_slice(&word(b.len()));
+        out.extend_from_slice(b);
+        out.resize(out.len() + (32 - b.len() % 32) % 32, 0);
+    }
+    out
+}
+
+/// Inverse of `abi_encode_words_bytes` for `n_words` head words and `n_bytes` `bytes` arguments
+pub(crate) fn abi_decode_words_bytes(data: &[u8], n_words: usize, n_bytes: usize) -> Result<(Vec<[u8; 32]>, Vec<Vec<u8>>)> {
+    let words = (0..n_words).map(|k| read_word(data, 32 * k)).collect::<Result<Vec<_>>>()?;
+    let mut bytes = Vec::with_capacity(n_bytes);
+    for k in 0..n_bytes {
+        // offsets and lengths come from untrusted calldata / logs
+        let off = read_usize(data, 32 * (n_words + k))?;
+        let len = read_usize(data, off)?;
+        let start = off.checked_add(32).ok_or_else(|| anyhow!("ABI length overflow"))?;
+        let end = start.checked_add(len).ok_or_else(|| anyhow!("ABI length overflow"))?;
+        bytes.push(data.get(start..end).ok_or_else(|| anyhow!("ABI data too short"))?.to_vec());
+    }
+    Ok((words, bytes))
+}
+
+pub(crate) fn selector(signature: &str) -> [u8; 4] {
+    keccak256(signature.as_bytes())[..4].try_into().expect("4 bytes")
+}
+
+pub(crate) fn id_word(id: Identifier) -> [u8; 32] {
+    id.serialize().try_into().expect("32-byte identifier")
+}
+
+/// The member posting from `account`, if any
+pub(crate) fn member_for_account(members: &BTreeMap<Identifier, [u8; 20]>, account: &[u8; 20]) -> Option<Identifier> {
+    members.iter().find(|(_, a)| *a == account).map(|(id, _)| *id)
+}
+
+pub fn publish_calldata(dkg_id: &[u8; 32], sender: Identifier, payload: &[u8]) -> Vec<u8> {
+    let mut out = selector(PUBLISH_SIGNATURE).to_vec();
+    out.extend_from_slice(&abi_encode_words_bytes(&[*dkg_id, id_word(sender)], &[payload]));
+    out
+}
+
+/// (dkgId, sender, payload) of a `publish` call, e.g. for an indexer
+pub fn decode_publish_calldata(calldata: &[u8]) -> Result<([u8; 32], Identifier, Vec<u8>)> {
+    if calldata.get(..4) != Some(&selector(PUBLISH_SIGNATURE)[..]) {
+        bail!("not a publish call");
+    }
+    let (words, mut bytes) = abi_decode_words_bytes(&calldata[4..], 2, 1)?;
+    Ok((words[0], Identifier::deserialize(&words[1])?, bytes.remove(0)))
+}
+
+/// Event data of `Round1Published`: (sender, payload)
+pub fn published_log_data(sender: Identifier, payload: &[u8]) -> Vec<u8> {
+    abi_encode_words_bytes(&[id_word(sender)], &[payload])
+}
+
+/// Round-1 packages exchanged through a contract; `dkg_id` separates DKG runs on one contract.
+/// `members` maps every member to the account it posts from.
+pub struct ContractChannel<T: ContractTransport> {
+    pub address: [u8; 20],
+    pub dkg_id: [u8; 32],
+    members: BTreeMap<Identifier, [u8; 20]>,
+    transport: T,
+}
+
+impl<T: ContractTransport> ContractChannel<T> {
+    pub fn new(address: [u8; 20], dkg_id: [u8; 32], members: BTreeMap<Identifier, [u8; 20]>, transport: T) -> Self {
+        Self { address, dkg_id, members, transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T: ContractTransport> BroadcastChannel for ContractChannel<T> {
+    fn publish(&mut self, sender: Identifier, payload: &[u8]) -> Result<()> {
+        let from = *self.members.get(&sender).ok_or_else(|| anyhow!("sender {} is not a member", hex0x(&sender.serialize())))?;
+        self.transport.send_transaction(from, self.address, publish_calldata(&self.dkg_id, sender, payload))
+    }
+
+    /// Logs not signed by a member's account are skipped; a member's log claiming another
+    /// sender, or not decoding, is an error naming that member
+    fn collect(&self) -> Result<BTreeMap<Identifier, Vec<u8>>> {
+        let topics = [keccak256(PUBLISHED_EVENT_SIGNATURE.as_bytes()), self.dkg_id];
+        let mut out = BTreeMap::new();
+        for log in self.transport.logs(self.address, &topics)? {
+            let Some(member) = member_for_account(&self.members, &log.from) else { continue };
+            let payload = abi_decode_words_bytes(&log.data, 1, 1)
+                .ok()
+                .filter(|(words, _)| words[0] == id_word(member))
+                .map(|(_, mut bytes)| bytes.remove(0))
+                .ok_or_else(|| anyhow!("member {} posted a malformed package", hex0x(&member.serialize())))?;
+            insert_once(&mut out, member, &payload)?;
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg_with_channel, DkgConfig};
+
+    /// Stand-in for the contract: every publish call emits its event
+    struct Log {
+        address: [u8; 20],
+        topics: [[u8; 32]; 2],
+        from: [u8; 20],
+        data: Vec<u8>,
+    }
+
+    #[derive(Default)]
+    struct MockChain {
+        logs: Vec<Log>,
+    }
+
+    impl ContractTransport for MockChain {
+        fn send_transaction(&mut self, from: [u8; 20], to: [u8; 20], calldata: Vec<u8>) -> Result<()> {
+            let (dkg_id, sender, payload) = decode_publish_calldata(&calldata)?;
+            let topics = [keccak256(PUBLISHED_EVENT_SIGNATURE.as_bytes()), dkg_id];
+            self.logs.push(Log { address: to, topics, from, data: published_log_data(sender, &payload) });
+            Ok(())
+        }
+
+        fn logs(&self, address: [u8; 20], topics: &[[u8; 32]]) -> Result<Vec<EventLog>> {
+            Ok(self.logs.iter()
+                .filter(|l| l.address == address && l.topics[..] == *topics)
+                .map(|l| EventLog { from: l.from, data: l.data.clone() })
+                .collect())
+        }
+    }
+
+    fn accounts(n: u16) -> BTreeMap<Identifier, [u8; 20]> {
+        (1..=n).map(|i| (i.try_into().unwrap(), [i as u8; 20])).collect()
+    }
+
+    #[test]
+    fn test_dkg_over_each_channel() -> Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+
+        let mem = run_dealerless_dkg_with_channel(cfg, &mut MemoryChannel::default(), &mut OsRng)?;
+        assert_eq!(mem.key_packages.len(), 3);
+
+        let dir = std::env::temp_dir().join(format!("frostlab-bcast-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut files = DirChannel::new(&dir)?;
+        let out = run_dealerless_dkg_with_channel(cfg, &mut files, &mut OsRng)?;
+        let mut transcript = Vec::new();
+        for (id, bytes) in files.collect()? {
+            transcript.extend_from_slice(&id.serialize());
+            transcript.extend_from_slice(&bytes);
+        }
+        assert_eq!(keccak256(&transcript), out.transcript_hash);
+        fs::remove_dir_all(&dir)?;
+
+        let mut chain = ContractChannel::new([0x11; 20], [0x22; 32], accounts(3), MockChain::default());
+        run_dealerless_dkg_with_channel(cfg, &mut chain, &mut OsRng)?;
+        assert_eq!(chain.transport().logs.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivocation_rejected() -> Result<()> {
+        let id: Identifier = 1u16.try_into()?;
+        let mut ch = MemoryChannel::default();
+        ch.publish(id, b"a")?;
+        ch.publish(id, b"a")?;
+        assert!(ch.publish(id, b"b").is_err());
+
+        // racing publishers on one directory: exactly one package wins, nothing is left behind
+        let dir = std::env::temp_dir().join(format!("frostlab-race-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        DirChannel::new(&dir)?;
+        let results: Vec<bool> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8u8)
+                .map(|k| {
+                    let dir = &dir;
+                    s.spawn(move || DirChannel::new(dir).and_then(|mut c| c.publish(id, &[k; 64])).is_ok())
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().expect("publisher thread")).collect()
+        });
+        assert_eq!(results.iter().filter(|ok| **ok).count(), 1);
+        let files = DirChannel::new(&dir)?.collect()?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+        fs::remove_dir_all(&dir)?;
+
+        // on-chain the second event is there, collecting refuses it
+        let mut chain = ContractChannel::new([0x11; 20], [0x22; 32], accounts(3), MockChain::default());
+        chain.publish(id, b"a")?;
+        chain.publish(id, b"b")?;
+        assert!(chain.collect().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_logs_bound_to_member_accounts() -> Result<()> {
+        let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().unwrap()).collect();
+        let mut chain = ContractChannel::new([0x11; 20], [0x22; 32], accounts(3), MockChain::default());
+        chain.publish(ids[0], b"a")?;
+        assert!(chain.publish(7u16.try_into()?, b"x").is_err());
+
+        // an outsider posting under a member's id is ignored
+        let topics = [keccak256(PUBLISHED_EVENT_SIGNATURE.as_bytes()), [0x22; 32]];
+        let spoof = Log { address: [0x11; 20], topics, from: [0xee; 20], data: published_log_data(ids[1], b"junk") };
+        chain.transport.logs.push(spoof);
+        assert_eq!(chain.collect()?, BTreeMap::from([(ids[0], b"a".to_vec())]));
+
+        // a member posting under another id is blamed itself
+        let forged = Log { address: [0x11; 20], topics, from: [2; 20], data: published_log_data(ids[2], b"junk") };
+        chain.transport.logs.push(forged);
+        assert!(chain.collect().unwrap_err().to_string().contains(&hex0x(&ids[1].serialize())));
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_decode_rejects_huge_offsets() {
+        // offset word pointing at the end of the address space
+        let data = word(usize::MAX).to_vec();
+        assert!(abi_decode_words_bytes(&data, 0, 1).is_err());
+
+        // offset fine, length word at the top of the range
+        let mut data = word(32).to_vec();
+        data.extend_from_slice(&word(usize::MAX - 16));
+        assert!(abi_decode_words_bytes(&data, 0, 1).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Coordinator-side cache of finished rounds keyed by (kind, epoch, message hash), the same
+//! inputs the session id is derived from. Repeat requests (common for randomness APIs) get the
+//! earlier certificate / signature back without a store lookup. The signer set is part of
+//! neither: the first quorum to complete a session fixes its result.
+//!
+//! Only this cache expires: entries go after `ttl`, and beyond `max_entries` the oldest go first.
+//! The `SessionStore` keeps completed sessions for good, so a repeat after expiry still gets the
+//! same result back, from the store instead of a new round.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+use crate::coordinator::{session_id_from_hash, SessionResult};
+use crate::participant::SessionId;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RoundKind {
+    Dvrf,
+    Signature,
+}
+
+impl RoundKind {
+    /// session-id label of the round kind
+    fn label(&self) -> &'static [u8] {
+        match self {
+            RoundKind::Dvrf => b"dvrf",
+            RoundKind::Signature => b"frost",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CacheKey {
+    pub kind: RoundKind,
+    pub epoch: u64,
+    pub msg_hash: [u8; 32],
+}
+
+impl CacheKey {
+    pub fn new(kind: RoundKind, epoch: u64, msg_hash: [u8; 32]) -> Self {
+        Self { kind, epoch, msg_hash }
+    }
+
+    /// Session whose result this entry caches
+    pub fn session_id(&self) -> SessionId {
+        session_id_from_hash(self.kind.label(), self.epoch, &self.msg_hash)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CacheConfig {
+    /// lifetime of a cache entry; completed sessions in the store do not expire
+    pub ttl: Duration,
+    pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { ttl: Duration::from_secs(300), max_entries: 10_000 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+pub struct ResultCache {
+    cfg: CacheConfig,
+    entries: BTreeMap<CacheKey, (Instant, SessionResult)>,
+    /// (insertion time, key), oldest first
+    by_age: BTreeSet<(Instant, CacheKey)>,
+    stats: CacheStats,
+}
+
+impl ResultCache {
+    pub fn new(cfg: CacheConfig) -> Self {
+        Self { cfg, entries: BTreeMap::new(), by_age: BTreeSet::new(), stats: CacheStats::default() }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some((at, _)) = self.entries.remove(key) {
+            self.by_age.remove(&(at, *key));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn get(&mut self, key: &CacheKey, now: Instant) -> Option<SessionResult> {
+        match self.entries.get(key) {
+            Some((at, r)) if now.saturating_duration_since(*at) < self.cfg.ttl => {
+                self.stats.hits += 1;
+                Some(r.clone())
+            }
+            Some(_) => {
+                self.remove(key);
+                self.stats.evictions += 1;
+                self.stats.misses += 1;
+                None
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: CacheKey, result: SessionResult, now: Instant) {
+        if self.cfg.max_entries == 0 {
+            return;
+        }
+        // expired entries are the oldest ones
+        while let Some(&(at, oldest)) = self.by_age.first() {
+            if now.saturating_duration_since(at) < self.cfg.ttl {
+                break;
+            }
+            self.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        // a re-inserted key replaces its entry
+        self.remove(&key);
+        while self.entries.len() >= self.cfg.max_entries {
+            let (_, oldest) = self.by_age.pop_first().expect("non-empty");
+            self.entries.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        self.by_age.insert((now, key));
+        self.entries.insert(key, (now, result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use std::sync::Arc;
+    use crate::coordinator::{Coordinator, MemorySessionStore};
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::coordinator::derive_session_id;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::messages::DvrfInput;
+    use crate::participant::participants_from_dkg;
+    use crate::utils::keccak256;
+
+    fn key(epoch: u64) -> CacheKey {
+        CacheKey::new(RoundKind::Dvrf, epoch, [0u8; 32])
+    }
+
+    #[test]
+    fn test_ttl_and_size_limits() -> anyhow::Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let cert = run_ddh_dvrf_cert(&DvrfInput::new(b"m".to_vec()), &out.key_packages, &out.public_key_package, &out.all_ids()[..2]);
+        let r = SessionResult::Dvrf(cert);
+
+        let mut cache = ResultCache::new(CacheConfig { ttl: Duration::from_secs(10), max_entries: 2 });
+        let t0 = Instant::now();
+        cache.insert(key(1), r.clone(), t0);
+        cache.insert(key(2), r.clone(), t0 + Duration::from_secs(1));
+        assert!(cache.get(&key(1), t0 + Duration::from_secs(2)).is_some());
+
+        // full: the oldest entry goes
+        cache.insert(key(3), r.clone(), t0 + Duration::from_secs(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key(1), t0 + Duration::from_secs(3)).is_none());
+
+        // expired
+        assert!(cache.get(&key(2), t0 + Duration::from_secs(11)).is_none());
+        assert!(cache.get(&key(3), t0 + Duration::from_secs(11)).is_some());
+        assert_eq!(cache.stats().hits, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_coordinator_serves_repeats_from_cache() -> anyhow::Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers)
+            .with_cache(CacheConfig::default());
+
+        let input = DvrfInput::new(b"randomness please".to_vec());
+        let a = coord.run_dvrf_cached(5, &input, &mut parts, &ids[..3])?;
+        let b = coord.run_dvrf_cached(5, &input, &mut parts, &ids[..3])?;
+        assert_eq!(a, b);
+        assert_eq!(coord.cache_stats(), Some(CacheStats { hits: 1, misses: 1, evictions: 0 }));
+        // one partial eval per participant, the repeat never reached them
+        assert_eq!(parts[&ids[0]].audit_log().entries().len(), 1);
+
+        // another quorum asks for the same session: same key, same answer
+        assert_eq!(coord.run_dvrf_cached(5, &input, &mut parts, &ids[1..])?, a);
+        let key = CacheKey::new(RoundKind::Dvrf, 5, keccak256(&input.to_hash_input()));
+        assert_eq!(key.session_id(), derive_session_id(b"dvrf", 5, &input.to_hash_input()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_expired_entry_served_from_store() -> anyhow::Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers)
+            .with_cache(CacheConfig { ttl: Duration::ZERO, max_entries: 10 });
+
+        let input = DvrfInput::new(b"m".to_vec());
+        let a = coord.run_dvrf_cached(1, &input, &mut parts, &ids[..2])?;
+        // the cache entry is gone at once, the store still answers and no participant is asked again
+        assert_eq!(coord.run_dvrf_cached(1, &input, &mut parts, &ids[1..])?, a);
+        assert_eq!(parts[&ids[2]].audit_log().entries().len(), 0);
+        Ok(())
+    }
+}
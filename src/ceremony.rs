@@ -0,0 +1,184 @@
+//! Setup ceremony report: one artifact describing a finished DKG (parameters, members, group
+//! key, verifying shares, transcript hash), signed by every participant's long-term ECDSA key.
+//! Each entry carries the member's proof of possession over the transcript hash, so a report
+//! only verifies if every enrolled verifying share is backed by a share its owner holds.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use k256::ecdsa::{signature::{Signer, Verifier}, Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::committee::CIPHERSUITE;
+use crate::dkg::{DkgOutput, Identifier};
+use crate::encoding::{from_hex0x, hex0x};
+use crate::possession::{verify_possession, PossessionProof};
+use crate::utils::keccak256;
+use crate::validated::ValidatedPoint;
+
+pub const REPORT_VERSION: u32 = 2;
+
+/// Who a participant is outside the protocol
+#[derive(Clone, Debug)]
+pub struct ParticipantInfo {
+    pub label: String,
+    pub long_term_key: VerifyingKey,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipantEntry {
+    pub identifier: String,
+    pub label: String,
+    /// compressed SEC1 vk_i
+    pub verifying_share: String,
+    /// compressed SEC1 ECDSA key that signs this report
+    pub long_term_key: String,
+    /// `PossessionProof::to_bytes` over the transcript hash
+    pub proof_of_possession: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetupReport {
+    pub version: u32,
+    pub ciphersuite: String,
+    pub max_signers: u16,
+    pub min_signers: u16,
+    pub group_key: String,
+    pub transcript_hash: String,
+    pub participants: Vec<ParticipantEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSignature {
+    pub identifier: String,
+    /// 64-byte r || s
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedSetupReport {
+    pub report: SetupReport,
+    pub signatures: Vec<ReportSignature>,
+}
+
+impl SetupReport {
+    /// Build the report; every DKG member must have an entry in `info`
+    pub fn from_dkg(out: &DkgOutput, info: &BTreeMap<Identifier, ParticipantInfo>) -> Result<Self> {
+        let committee = out.committee_info()?;
+        let mut participants = Vec::with_capacity(committee.identifiers.len());
+        for (&id, share) in &committee.verifying_shares {
+            let pi = info.get(&id).ok_or_else(|| anyhow!("no participant info for {}", hex0x(&id.serialize())))?;
+            let pop = out.possession_proofs.get(&id).ok_or_else(|| anyhow!("no proof of possession for {}", hex0x(&id.serialize())))?;
+            participants.push(ParticipantEntry {
+                identifier: hex0x(&id.serialize()),
+                label: pi.label.clone(),
+                verifying_share: hex0x(share),
+                long_term_key: hex0x(pi.long_term_key.to_encoded_point(true).as_bytes()),
+                proof_of_possession: hex0x(&pop.to_bytes()),
+            });
+        }
+        Ok(Self {
+            version: REPORT_VERSION,
+            ciphersuite: CIPHERSUITE.to_string(),
+            max_signers: committee.max_signers,
+            min_signers: committee.min_signers,
+            group_key: hex0x(&committee.group_key),
+            transcript_hash: hex0x(&out.transcript_hash),
+            participants,
+        })
+    }
+
+    /// keccak of the JSON encoding; this is what participants sign
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        Ok(keccak256(serde_json::to_string(self)?.as_bytes()))
+    }
+
+    /// One participant's signature over the digest
+    pub fn sign(&self, id: Identifier, long_term_key: &SigningKey) -> Result<ReportSignature> {
+        let sig: Signature = long_term_key.sign(&self.digest()?);
+        Ok(ReportSignature { identifier: hex0x(&id.serialize()), signature: hex0x(&sig.to_bytes()) })
+    }
+}
+
+impl SignedSetupReport {
+    /// Every listed participant signed and proved possession of its share, and nobody else signed
+    pub fn verify(&self) -> Result<()> {
+        let digest = self.report.digest()?;
+        let transcript_hash: [u8; 32] = from_hex0x(&self.report.transcript_hash)?
+            .try_into()
+            .map_err(|_| anyhow!("transcript hash must be 32 bytes"))?;
+        if self.signatures.len() != self.report.participants.len() {
+            bail!("expected {} signatures, got {}", self.report.participants.len(), self.signatures.len());
+        }
+        for entry in &self.report.participants {
+            let s = self.signatures.iter()
+                .find(|s| s.identifier == entry.identifier)
+                .ok_or_else(|| anyhow!("missing signature from {}", entry.label))?;
+            let vk = VerifyingKey::from_sec1_bytes(&from_hex0x(&entry.long_term_key)?)?;
+            let sig = Signature::from_slice(&from_hex0x(&s.signature)?)?;
+            vk.verify(&digest, &sig).map_err(|_| anyhow!("bad signature from {}", entry.label))?;
+
+            let id = Identifier::deserialize(&from_hex0x(&entry.identifier)?)?;
+            let vk_i = ValidatedPoint::from_compressed(&from_hex0x(&entry.verifying_share)?)?;
+            let pop = PossessionProof::from_bytes(id, &from_hex0x(&entry.proof_of_possession)?)?;
+            verify_possession(&transcript_hash, &vk_i, &pop).map_err(|_| anyhow!("bad proof of possession from {}", entry.label))?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_setup_report_signed_by_all() -> Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+
+        let mut keys = BTreeMap::new();
+        let mut info = BTreeMap::new();
+        for (k, id) in out.all_ids().into_iter().enumerate() {
+            let sk = SigningKey::random(&mut OsRng);
+            info.insert(id, ParticipantInfo { label: format!("node-{k}"), long_term_key: *sk.verifying_key() });
+            keys.insert(id, sk);
+        }
+
+        let report = SetupReport::from_dkg(&out, &info)?;
+        assert_eq!((report.max_signers, report.min_signers), (cfg.max_signers, cfg.min_signers));
+        let signatures = keys.iter().map(|(id, sk)| report.sign(*id, sk)).collect::<Result<Vec<_>>>()?;
+        let signed = SignedSetupReport { report, signatures };
+        signed.verify()?;
+
+        let back = SignedSetupReport::from_json(&signed.to_json()?)?;
+        back.verify()?;
+        assert_eq!(back.report.transcript_hash, hex0x(&out.transcript_hash));
+
+        // editing the report invalidates everyone's signature
+        let mut edited = back.clone();
+        edited.report.min_signers = 1;
+        assert!(edited.verify().is_err());
+
+        // a proof of possession moved to another member, even with everyone re-signing
+        let mut swapped = back.report.clone();
+        swapped.participants[0].proof_of_possession = swapped.participants[1].proof_of_possession.clone();
+        let signatures = keys.iter().map(|(id, sk)| swapped.sign(*id, sk)).collect::<Result<Vec<_>>>()?;
+        assert!(SignedSetupReport { report: swapped, signatures }.verify().is_err());
+
+        // missing signer
+        let mut partial = back;
+        partial.signatures.pop();
+        assert!(partial.verify().is_err());
+        Ok(())
+    }
+}
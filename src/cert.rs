@@ -0,0 +1,245 @@
+//! DDH-DVRF quorum certificate: the combined output together with the partials it was built from.
+//! Verification only needs public data (verifying shares), no key material.
+
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Result};
+use k256::ProjectivePoint;
+
+use crate::ddh_dvrf::{id_as_u64, validated_vk_share, Identifier, PublicKeyPackage};
+use crate::messages::DvrfInput;
+use crate::utils::{keccak256, lagrange_combine_points, point_bytes_compressed, points_bytes_compressed, verify_eq, Proof};
+use crate::validated::ValidatedPoint;
+use crate::vdf::{vdf_seed, Vdf, VdfOutput};
+use crate::version::ProtocolVersion;
+
+/// One signer's contribution (i, v_i, π_i); π_i is bound to `version`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartialEval {
+    pub version: ProtocolVersion,
+    pub id: Identifier,
+    pub v_i: ValidatedPoint,
+    pub proof: Proof,
+}
+
+impl PartialEval {
+    /// π_i against vk_i, under the partial's own version
+    pub fn verify(&self, msg: &DvrfInput, vk_i: &ValidatedPoint) -> bool {
+        verify_eq(self.version, msg, vk_i, &self.v_i, &self.proof)
+    }
+}
+
+/// What a verifier requires of a certificate. A plain threshold converts into a config
+/// without a VDF stage.
+#[derive(Clone, Copy)]
+pub struct CertConfig<'a> {
+    pub threshold: u16,
+    /// backend of the round's VDF stage; `None` for rounds without one
+    pub vdf: Option<&'a dyn Vdf>,
+}
+
+impl<'a> CertConfig<'a> {
+    pub fn new(threshold: u16) -> Self {
+        Self { threshold, vdf: None }
+    }
+
+    pub fn with_vdf(mut self, vdf: &'a dyn Vdf) -> Self {
+        self.vdf = Some(vdf);
+        self
+    }
+}
+
+impl From<u16> for CertConfig<'_> {
+    fn from(threshold: u16) -> Self {
+        Self::new(threshold)
+    }
+}
+
+/// Combined DVRF output for `msg` plus its t partials, optionally post-processed by a VDF.
+/// Every partial runs under the certificate's `version`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuorumCert {
+    pub version: ProtocolVersion,
+    pub msg: DvrfInput,
+    pub output: ProjectivePoint,
+    pub partials: Vec<PartialEval>,
+    pub vdf: Option<VdfOutput>,
+}
+
+/// Check every π_i against vk_i and Lagrange-combine the partials.
+/// Needs at least `threshold` distinct, known signers, all under one protocol version.
+pub fn verify_combined(
+    msg: &DvrfInput,
+    public_key_package: &PublicKeyPackage,
+    partials: &[PartialEval],
+    threshold: u16,
+) -> Result<ProjectivePoint> {
+    if partials.len() < threshold as usize {
+        bail!("need {} partials, got {}", threshold, partials.len());
+    }
+    let mut seen = BTreeSet::new();
+    let mut points = Vec::with_capacity(partials.len());
+    for p in partials {
+        if !seen.insert(p.id) {
+            bail!("duplicate partial for id={}", id_as_u64(p.id));
+        }
+        if p.version != partials[0].version {
+            bail!("partials under different protocol versions ({} and {})", partials[0].version.0, p.version.0);
+        }
+        let vk_i = validated_vk_share(public_key_package, p.id)?;
+        if !p.verify(msg, &vk_i) {
+            bail!("invalid proof for id={}", id_as_u64(p.id));
+        }
+        points.push((id_as_u64(p.id), *p.v_i));
+    }
+    Ok(lagrange_combine_points(&points))
+}
+
+impl QuorumCert {
+    /// Verify all partials, that they combine to `output`, and the VDF proof on top of it.
+    /// The certificate has a VDF stage exactly when `config` names a backend.
+    pub fn verify<'a>(&self, public_key_package: &PublicKeyPackage, config: impl Into<CertConfig<'a>>) -> Result<()> {
+        let config = config.into();
+        self.verify_combined_output(public_key_package, config.threshold)?;
+        match (&self.vdf, config.vdf) {
+            (None, None) => Ok(()),
+            (Some(out), Some(vdf)) if vdf.verify(&vdf_seed(&self.output), out) => Ok(()),
+            (Some(_), Some(_)) => bail!("invalid VDF proof"),
+            (Some(_), None) => bail!("certificate has a VDF stage but no VDF backend is configured"),
+            (None, Some(_)) => bail!("certificate has no VDF stage"),
+        }
+    }
+
+    /// Run the VDF on the combined output and attach the result
+    pub fn with_vdf(mut self, vdf: &dyn Vdf) -> Self {
+        self.vdf = Some(vdf.eval(&vdf_seed(&self.output)));
+        self
+    }
+
+    /// Compressed v_i of every partial, in certificate order (one batched inversion)
+    pub fn partials_compressed(&self) -> Vec<(Identifier, [u8; 33])> {
+        let points: Vec<ProjectivePoint> = self.partials.iter().map(|p| *p.v_i).collect();
+        self.partials.iter().map(|p| p.id).zip(points_bytes_compressed(&points)).collect()
+    }
+
+    /// Round randomness: keccak of the VDF output if present, else of compressed v
+    pub fn randomness(&self) -> [u8; 32] {
+        match &self.vdf {
+            Some(out) => keccak256(&out.output),
+            None => keccak256(&point_bytes_compressed(&self.output)),
+        }
+    }
+
+    fn verify_combined_output(&self, public_key_package: &PublicKeyPackage, threshold: u16) -> Result<()> {
+        if let Some(p) = self.partials.iter().find(|p| p.version != self.version) {
+            bail!("partial of id={} is under protocol version {}, certificate under {}", id_as_u64(p.id), p.version.0, self.version.0);
+        }
+        let v = verify_combined(&self.msg, public_key_package, &self.partials, threshold)?;
+        if v != self.output {
+            bail!("combined output does not match certificate");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::utils::hash_to_scalar_keccak;
+
+    #[test]
+    fn test_quorum_cert_verify() -> Result<()> {
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let cert = run_ddh_dvrf_cert(&DvrfInput::new(b"beacon".to_vec()), &out.key_packages, &out.public_key_package, &ids[1..4]);
+        cert.verify(&out.public_key_package, cfg.min_signers)?;
+
+        // wrong message
+        let mut bad = cert.clone();
+        bad.msg = DvrfInput::new(b"other".to_vec());
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+
+        // below threshold
+        let mut bad = cert.clone();
+        bad.partials.pop();
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+
+        // duplicated signer
+        let mut bad = cert.clone();
+        bad.partials[2] = bad.partials[0];
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+
+        // forged output
+        let mut bad = cert.clone();
+        bad.output += ProjectivePoint::GENERATOR;
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+
+        // relabelled version: the proofs are bound to the one they were made under
+        let mut bad = cert.clone();
+        bad.version = ProtocolVersion(3);
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+        bad.partials.iter_mut().for_each(|p| p.version = ProtocolVersion(3));
+        assert!(bad.verify(&out.public_key_package, cfg.min_signers).is_err());
+        Ok(())
+    }
+
+    /// iterated keccak; "proof" is empty and verification recomputes (test-only backend)
+    struct HashChain(usize);
+
+    impl Vdf for HashChain {
+        fn eval(&self, seed: &[u8]) -> VdfOutput {
+            let mut h = keccak256(seed);
+            for _ in 0..self.0 { h = keccak256(&h); }
+            VdfOutput { output: h.to_vec(), proof: Vec::new() }
+        }
+        fn verify(&self, seed: &[u8], out: &VdfOutput) -> bool {
+            self.eval(seed) == *out
+        }
+    }
+
+    #[test]
+    fn test_quorum_cert_with_vdf() -> Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let plain = run_ddh_dvrf_cert(&DvrfInput::new(b"beacon".to_vec()), &out.key_packages, &out.public_key_package, &ids[..2]);
+        let cert = plain.clone().with_vdf(&HashChain(100));
+
+        let config = CertConfig::new(cfg.min_signers).with_vdf(&HashChain(100));
+        cert.verify(&out.public_key_package, config)?;
+        assert!(cert.verify(&out.public_key_package, CertConfig::new(cfg.min_signers).with_vdf(&HashChain(99))).is_err());
+        // a VDF cert is never accepted without checking the VDF
+        assert!(cert.verify(&out.public_key_package, cfg.min_signers).is_err());
+        assert!(plain.verify(&out.public_key_package, config).is_err());
+        let mut forged = cert.clone();
+        forged.vdf.as_mut().unwrap().output[0] ^= 1;
+        assert!(forged.verify(&out.public_key_package, config).is_err());
+        assert_ne!(cert.randomness(), plain.randomness());
+
+        // the seed needs a quorum: it is not vdf_seed of anything the group key and m give away,
+        // so nobody can start the delay before the partials are out
+        let group_key = out.public_key_package.verifying_key().to_element();
+        let h = hash_to_scalar_keccak(&cert.msg.to_hash_input());
+        assert_ne!(vdf_seed(&cert.output), vdf_seed(&(group_key * h)));
+        assert_ne!(vdf_seed(&cert.output), vdf_seed(&group_key));
+        Ok(())
+    }
+
+    #[cfg(feature = "vdf-wesolowski")]
+    #[test]
+    fn test_quorum_cert_with_wesolowski() -> Result<()> {
+        use crate::vdf::wesolowski::Wesolowski;
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let vdf = Wesolowski::new(1_000);
+        let cert = run_ddh_dvrf_cert(&DvrfInput::new(b"beacon".to_vec()), &out.key_packages, &out.public_key_package, &ids[..2])
+            .with_vdf(&vdf);
+        cert.verify(&out.public_key_package, CertConfig::new(cfg.min_signers).with_vdf(&vdf))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Incremental DVRF combining for rounds where partials trickle in.
+//!
+//! The certificate is built from the first t valid partials as soon as they are in; partials
+//! arriving afterwards are still verified and recorded, so the round ends with a report of
+//! who contributed on time, late, with a bad proof, or not at all.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use k256::ProjectivePoint;
+
+use crate::cert::{PartialEval, QuorumCert};
+use crate::ddh_dvrf::{id_as_u64, validated_vk_share, Identifier, PublicKeyPackage};
+use crate::messages::DvrfInput;
+use crate::utils::lagrange_combine_points;
+use crate::version::ProtocolVersion;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Contribution {
+    /// among the first t valid partials
+    OnTime,
+    /// valid, but arrived after the output was fixed
+    Late,
+    /// proof did not verify, or was made under another protocol version
+    Invalid,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParticipationReport {
+    pub on_time: Vec<Identifier>,
+    pub late: Vec<Identifier>,
+    pub invalid: Vec<Identifier>,
+    pub missing: Vec<Identifier>,
+}
+
+pub struct IncrementalCombiner {
+    version: ProtocolVersion,
+    msg: DvrfInput,
+    public_key_package: PublicKeyPackage,
+    threshold: u16,
+    contributions: BTreeMap<Identifier, Contribution>,
+    valid: Vec<PartialEval>,
+    cert: Option<QuorumCert>,
+}
+
+impl IncrementalCombiner {
+    /// Refuses a threshold below 2: a single partial would be the output
+    pub fn new(version: ProtocolVersion, msg: DvrfInput, public_key_package: PublicKeyPackage, threshold: u16) -> Result<Self> {
+        if threshold < 2 {
+            bail!("threshold must be >= 2");
+        }
+        Ok(Self { version, msg, public_key_package, threshold, contributions: BTreeMap::new(), valid: Vec::new(), cert: None })
+    }
+
+    /// Verify and record one partial. Returns the certificate on the call that completes the quorum.
+    /// A signer that already delivered a valid partial is ignored; one with a bad proof may retry.
+    pub fn add(&mut self, partial: PartialEval) -> Result<Option<&QuorumCert>> {
+        let vk_i = validated_vk_share(&self.public_key_package, partial.id)?;
+        if matches!(self.contributions.get(&partial.id), Some(Contribution::OnTime | Contribution::Late)) {
+            return Ok(None);
+        }
+        if partial.version != self.version || !partial.verify(&self.msg, &vk_i) {
+            self.contributions.insert(partial.id, Contribution::Invalid);
+            return Ok(None);
+        }
+
+        if self.cert.is_some() {
+            self.contributions.insert(partial.id, Contribution::Late);
+            return Ok(None);
+        }
+        self.contributions.insert(partial.id, Contribution::OnTime);
+        self.valid.push(partial);
+        if self.valid.len() < self.threshold as usize {
+            return Ok(None);
+        }
+
+        let points: Vec<(u64, ProjectivePoint)> = self.valid.iter().map(|p| (id_as_u64(p.id), *p.v_i)).collect();
+        self.cert = Some(QuorumCert {
+            version: self.version,
+            msg: self.msg.clone(),
+            output: lagrange_combine_points(&points),
+            partials: self.valid.clone(),
+            vdf: None,
+        });
+        Ok(self.cert.as_ref())
+    }
+
+    pub fn output(&self) -> Option<&QuorumCert> {
+        self.cert.as_ref()
+    }
+
+    pub fn report(&self) -> ParticipationReport {
+        let mut r = ParticipationReport::default();
+        for id in self.public_key_package.verifying_shares().keys() {
+            match self.contributions.get(id) {
+                Some(Contribution::OnTime) => r.on_time.push(*id),
+                Some(Contribution::Late) => r.late.push(*id),
+                Some(Contribution::Invalid) => r.invalid.push(*id),
+                None => r.missing.push(*id),
+            }
+        }
+        r
+    }
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::validated::ValidatedPoint;
+
+    #[test]
+    fn test_early_combine_and_report() -> Result<()> {
+        let cfg = DkgConfig::new(6, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let msg = DvrfInput::new(b"round 9".to_vec());
+        let all = run_ddh_dvrf_cert(&msg, &out.key_packages, &out.public_key_package, &ids[..5]).partials;
+
+        assert!(IncrementalCombiner::new(ProtocolVersion::CURRENT, msg.clone(), out.public_key_package.clone(), 1).is_err());
+        assert!(IncrementalCombiner::new(ProtocolVersion::CURRENT, msg.clone(), out.public_key_package.clone(), 0).is_err());
+        let mut c = IncrementalCombiner::new(ProtocolVersion::CURRENT, msg.clone(), out.public_key_package.clone(), cfg.min_signers)?;
+        let mut bad = all[0];
+        bad.v_i = ValidatedPoint::new(*bad.v_i + ProjectivePoint::GENERATOR)?;
+        assert!(c.add(bad)?.is_none());
+        assert!(c.add(all[1])?.is_none());
+        assert!(c.add(all[1])?.is_none());
+        assert!(c.add(all[2])?.is_none());
+        let cert = c.add(all[3])?.cloned().expect("quorum reached");
+        cert.verify(&out.public_key_package, cfg.min_signers)?;
+
+        assert!(c.add(all[4])?.is_none());
+        assert_eq!(c.output(), Some(&cert));
+        let r = c.report();
+        assert_eq!(r.on_time, ids[1..4].to_vec());
+        assert_eq!(r.late, vec![ids[4]]);
+        assert_eq!(r.invalid, vec![ids[0]]);
+        assert_eq!(r.missing, vec![ids[5]]);
+        Ok(())
+    }
+}
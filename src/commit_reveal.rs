@@ -0,0 +1,133 @@
+//! Commit-then-reveal output mode.
+//!
+//! Phase 1: the committee evaluates the DVRF, keeps the certificate back and publishes only
+//!          keccak(v) for the epoch, FROST-signed.
+//! Phase 2: the certificate (v, partials, proofs) is revealed; anyone checks it against the
+//!          signed commitment.
+//!
+//! Applications can close dependent bets between the two phases knowing v is already fixed.
+//! Nobody outside a quorum can compute v early: the DVRF base point is a hash-to-curve point
+//! with unknown discrete log, so v is not a public function of the group key.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use k256::ProjectivePoint;
+
+use crate::cert::QuorumCert;
+use crate::ddh_dvrf::PublicKeyPackage;
+use crate::frost_ext::frost_verify_with_key;
+use crate::messages::{AttestationMessage, DvrfInput, OUTPUT_COMMITMENT_TAG};
+use crate::utils::{keccak256, point_bytes_compressed};
+#[cfg(feature = "protocol")]
+use frost::rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "protocol")]
+use crate::{ddh_dvrf::run_ddh_dvrf_cert_with_rng, dkg::{DkgOutput, Identifier}, frost_ext::frost_sign};
+
+/// keccak(compressed v)
+pub fn output_commitment_hash(v: &ProjectivePoint) -> [u8; 32] {
+    keccak256(&point_bytes_compressed(v))
+}
+
+/// Signed commitment to the output of `input` in `epoch`
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputCommitment {
+    pub epoch: u64,
+    pub input: DvrfInput,
+    pub commitment: [u8; 32],
+    pub signature: frost::Signature,
+}
+
+impl OutputCommitment {
+    /// epoch || keccak(input) || commitment, tagged `OUTPUT_COMMITMENT_TAG`
+    pub fn attestation(epoch: u64, input: &DvrfInput, commitment: &[u8; 32]) -> AttestationMessage {
+        let mut raw = Vec::with_capacity(8 + 32 + 32);
+        raw.extend_from_slice(&epoch.to_be_bytes());
+        raw.extend_from_slice(&keccak256(&input.to_hash_input()));
+        raw.extend_from_slice(commitment);
+        AttestationMessage::tagged(OUTPUT_COMMITMENT_TAG, raw)
+    }
+
+    /// Check the group signature on the commitment
+    pub fn verify(&self, group_key: &frost::VerifyingKey) -> Result<()> {
+        let msg = Self::attestation(self.epoch, &self.input, &self.commitment);
+        if !frost_verify_with_key(&msg, &self.signature, group_key)? {
+            bail!("invalid group signature on output commitment");
+        }
+        Ok(())
+    }
+
+    /// Check a revealed certificate: signed commitment, same input, valid proofs, matching hash
+    pub fn verify_reveal(&self, reveal: &QuorumCert, public_key_package: &PublicKeyPackage, threshold: u16) -> Result<()> {
+        self.verify(public_key_package.verifying_key())?;
+        if reveal.msg != self.input {
+            bail!("reveal is for a different input");
+        }
+        reveal.verify(public_key_package, threshold)?;
+        if output_commitment_hash(&reveal.output) != self.commitment {
+            bail!("revealed output does not match commitment");
+        }
+        Ok(())
+    }
+}
+
+/// Phase 1: evaluate, sign the commitment. The returned certificate is the reveal and must be
+/// held back until the reveal phase.
+#[cfg(feature = "protocol")]
+pub fn commit_phase<R: RngCore + CryptoRng>(
+    epoch: u64,
+    input: &DvrfInput,
+    out: &DkgOutput,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> Result<(OutputCommitment, QuorumCert)> {
+    let cert = run_ddh_dvrf_cert_with_rng(input, &out.key_packages, &out.public_key_package, signers, rng);
+    let commitment = output_commitment_hash(&cert.output);
+    let msg = OutputCommitment::attestation(epoch, input, &commitment);
+    let signature = frost_sign(&msg, out, signers, rng)?;
+    Ok((OutputCommitment { epoch, input: input.clone(), commitment, signature }, cert))
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::utils::hash_to_scalar_keccak;
+
+    #[test]
+    fn test_commit_then_reveal() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let ids = out.all_ids();
+        let input = DvrfInput::new(b"lottery-42".to_vec());
+
+        let (commitment, reveal) = commit_phase(42, &input, &out, &ids[..3], &mut rng)?;
+        commitment.verify(out.public_key_package.verifying_key())?;
+        commitment.verify_reveal(&reveal, &out.public_key_package, cfg.min_signers)?;
+
+        // the held-back output is not H(m)·Y, which anyone holding the group key could compute
+        let group_key = out.public_key_package.verifying_key().to_element();
+        assert_ne!(reveal.output, group_key * hash_to_scalar_keccak(&input.to_hash_input()));
+
+        // the output is fixed: another quorum reveals the same v
+        let other = run_ddh_dvrf_cert(&input, &out.key_packages, &out.public_key_package, &ids[2..]);
+        commitment.verify_reveal(&other, &out.public_key_package, cfg.min_signers)?;
+
+        // reveal for another input
+        let wrong = run_ddh_dvrf_cert(&DvrfInput::new(b"lottery-43".to_vec()), &out.key_packages, &out.public_key_package, &ids[..3]);
+        assert!(commitment.verify_reveal(&wrong, &out.public_key_package, cfg.min_signers).is_err());
+
+        // tampered commitment breaks the signature
+        let mut forged = commitment.clone();
+        forged.commitment[0] ^= 1;
+        assert!(forged.verify(out.public_key_package.verifying_key()).is_err());
+
+        // epoch is bound too
+        let mut moved = commitment.clone();
+        moved.epoch = 43;
+        assert!(moved.verify(out.public_key_package.verifying_key()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Read-only view of a committee: who is in it, what the threshold is, which key it signs
+//! under. `group_id` is a stable name for the committee — keccak over the parameters, the
+//! group key and every (i, vk_i) in identifier order — so two parties holding the same
+//! public key package derive the same id.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use frost_secp256k1_evm as frost;
+
+use crate::ddh_dvrf::{vk_share_from_public_pkg, Identifier, PublicKeyPackage};
+use crate::utils::{keccak256, points_bytes_compressed};
+#[cfg(feature = "protocol")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "protocol")]
+use crate::dkg::DkgOutput;
+#[cfg(feature = "protocol")]
+use crate::encoding::hex0x;
+
+const GROUP_ID_DOMAIN: &[u8] = b"frostlab/group-id/v1";
+
+pub const CIPHERSUITE: &str = "FROST-secp256k1-KECCAK256-v1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitteeInfo {
+    pub min_signers: u16,
+    pub max_signers: u16,
+    /// ascending
+    pub identifiers: Vec<Identifier>,
+    /// compressed SEC1 vk_i
+    pub verifying_shares: BTreeMap<Identifier, [u8; 33]>,
+    /// compressed SEC1 group key
+    pub group_key: [u8; 33],
+    pub group_id: [u8; 32],
+}
+
+impl CommitteeInfo {
+    /// The public key package does not carry t, so the caller supplies it
+    pub fn from_public_key_package(pkpkg: &PublicKeyPackage, min_signers: u16) -> Result<Self> {
+        let identifiers: Vec<Identifier> = pkpkg.verifying_shares().keys().copied().collect();
+        let max_signers = identifiers.len() as u16;
+        if min_signers < 2 || min_signers > max_signers {
+            bail!("threshold {} out of range for {} members", min_signers, max_signers);
+        }
+        let points: Vec<_> = identifiers.iter().map(|id| vk_share_from_public_pkg(pkpkg, *id)).collect();
+        let verifying_shares: BTreeMap<_, _> = identifiers.iter().copied().zip(points_bytes_compressed(&points)).collect();
+        let group_key: [u8; 33] = pkpkg.verifying_key().serialize()?.try_into()
+            .map_err(|_| anyhow!("group key is not 33 bytes"))?;
+
+        let mut pre = GROUP_ID_DOMAIN.to_vec();
+        pre.extend_from_slice(&min_signers.to_be_bytes());
+        pre.extend_from_slice(&max_signers.to_be_bytes());
+        pre.extend_from_slice(&group_key);
+        for (id, vk) in &verifying_shares {
+            pre.extend_from_slice(&id.serialize());
+            pre.extend_from_slice(vk);
+        }
+        let group_id = keccak256(&pre);
+        Ok(Self { min_signers, max_signers, identifiers, verifying_shares, group_key, group_id })
+    }
+
+    /// First 8 bytes of keccak(group key), for logs and UIs
+    pub fn group_key_fingerprint(&self) -> [u8; 8] {
+        keccak256(&self.group_key)[..8].try_into().expect("8 bytes")
+    }
+
+    pub fn verifying_key(&self) -> Result<frost::VerifyingKey> {
+        Ok(frost::VerifyingKey::deserialize(&self.group_key)?)
+    }
+
+    #[cfg(feature = "protocol")]
+    pub fn to_report(&self) -> CommitteeReport {
+        CommitteeReport {
+            ciphersuite: CIPHERSUITE.to_string(),
+            min_signers: self.min_signers,
+            max_signers: self.max_signers,
+            group_id: hex0x(&self.group_id),
+            group_key: hex0x(&self.group_key),
+            group_key_fingerprint: hex0x(&self.group_key_fingerprint()),
+            members: self.verifying_shares.iter()
+                .map(|(id, vk)| CommitteeMember { identifier: hex0x(&id.serialize()), verifying_share: hex0x(vk) })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "protocol")]
+impl DkgOutput {
+    pub fn committee_info(&self) -> Result<CommitteeInfo> {
+        CommitteeInfo::from_public_key_package(&self.public_key_package, self.min_signers())
+    }
+}
+
+#[cfg(feature = "protocol")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    pub identifier: String,
+    pub verifying_share: String,
+}
+
+/// JSON form of `CommitteeInfo`, 0x-hex throughout
+#[cfg(feature = "protocol")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeReport {
+    pub ciphersuite: String,
+    pub min_signers: u16,
+    pub max_signers: u16,
+    pub group_id: String,
+    pub group_key: String,
+    pub group_key_fingerprint: String,
+    pub members: Vec<CommitteeMember>,
+}
+
+#[cfg(feature = "protocol")]
+impl CommitteeReport {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_committee_info() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(4, 3)?, &mut OsRng)?;
+        let info = out.committee_info()?;
+        assert_eq!((info.min_signers, info.max_signers), (3, 4));
+        assert_eq!(info.identifiers, out.all_ids());
+        assert_eq!(&info.verifying_key()?, out.public_key_package.verifying_key());
+
+        // same package, same id; a different threshold names a different committee
+        assert_eq!(CommitteeInfo::from_public_key_package(&out.public_key_package, 3)?.group_id, info.group_id);
+        assert_ne!(CommitteeInfo::from_public_key_package(&out.public_key_package, 2)?.group_id, info.group_id);
+        assert!(CommitteeInfo::from_public_key_package(&out.public_key_package, 5).is_err());
+
+        let other = run_dealerless_dkg(DkgConfig::new(4, 3)?, &mut OsRng)?;
+        assert_ne!(other.committee_info()?.group_id, info.group_id);
+
+        let report = info.to_report();
+        assert_eq!(report.members.len(), 4);
+        let back: CommitteeReport = serde_json::from_str(&report.to_json()?)?;
+        assert_eq!(back, report);
+        Ok(())
+    }
+}
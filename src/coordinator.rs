@@ -0,0 +1,1027 @@
+//! Coordinator side of DVRF and FROST sessions.
+//!
+//! Several coordinators may race on the same session id. The first result written to the
+//! shared `SessionStore` wins and later coordinators fetch it instead of running a new round;
+//! participants deduplicate by session id, so even coordinators that do not share a store
+//! end up with the same output while their rounds overlap. Once a result is stored the
+//! participants forget the session (`Participant::forget_session`), so their state does not
+//! grow with every round.
+//!
+//! `begin_signing` runs a FROST session that survives signers dropping out of round 2: after
+//! the share timeout the missing signers are swapped for spares and only the spares run
+//! round 1. Nonces are bound to one signing package, so every signer preprocesses one nonce
+//! pair per allowed recovery up front and each attempt signs under its own sub-session id.
+//!
+//! Round-2 shares may be delivered more than once or ahead of their attempt
+//! (`deliver_share`): repeats are ignored and shares for a later attempt (up to
+//! `max_recoveries`) wait for it. Every share is checked against the sender's verifying share
+//! before it is kept, so a forged share under someone else's id cannot displace the real one;
+//! a signer that never gets past an invalid share is reported for it.
+//!
+//! Every session runs under the highest protocol version the coordinator and all of its
+//! signers support; participants are told the version before they contribute.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use frost::{round1, round2};
+use k256::ProjectivePoint;
+
+use crate::cache::{CacheConfig, CacheKey, CacheStats, ResultCache, RoundKind};
+use crate::cert::{verify_combined, PartialEval, QuorumCert};
+use crate::ddh_dvrf::validated_vk_share;
+use crate::dkg::{Identifier, PublicKeyPackage};
+use crate::inbox::{Delivery, Inbox};
+use crate::liveness::{aggregate_liveness, LivenessReport};
+use crate::messages::{AttestationMessage, DvrfInput};
+use crate::participant::{Participant, SessionId};
+use crate::participation::{Misbehavior, ParticipationHook, RoundParticipation};
+use crate::utils::keccak256;
+use crate::version::{negotiate, ProtocolVersion, VersionRange};
+
+/// Deterministic session id, so independent coordinators agree on it for the same request
+pub fn derive_session_id(label: &[u8], epoch: u64, msg: &[u8]) -> SessionId {
+    session_id_from_hash(label, epoch, &keccak256(msg))
+}
+
+/// `derive_session_id` of an already hashed message
+pub fn session_id_from_hash(label: &[u8], epoch: u64, msg_hash: &[u8; 32]) -> SessionId {
+    let mut buf = Vec::with_capacity(label.len() + 8 + 32);
+    buf.extend_from_slice(label);
+    buf.extend_from_slice(&epoch.to_be_bytes());
+    buf.extend_from_slice(msg_hash);
+    keccak256(&buf)
+}
+
+/// Sub-session of `session_id` used by recovery attempt `attempt` (0 = initial signer set)
+pub fn attempt_session_id(session_id: &SessionId, attempt: u32) -> SessionId {
+    let mut buf = session_id.to_vec();
+    buf.extend_from_slice(&attempt.to_be_bytes());
+    keccak256(&buf)
+}
+
+/// Output of a completed session
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionResult {
+    Dvrf(QuorumCert),
+    Signature(frost::Signature),
+    #[cfg(feature = "bls")]
+    BlsSignature(crate::bls::BlsSignature),
+}
+
+/// Completed-session registry shared by all coordinators. Entries never expire: once a
+/// session completes its result is fixed, however long later it is asked for again.
+pub trait SessionStore {
+    fn get(&self, id: &SessionId) -> Option<SessionResult>;
+    /// Store `result` unless the session already completed; returns whatever is stored afterwards
+    fn put_if_absent(&self, id: SessionId, result: SessionResult) -> SessionResult;
+}
+
+/// In-process store (tests, single host with several coordinator tasks)
+#[derive(Default)]
+pub struct MemorySessionStore {
+    inner: Mutex<BTreeMap<SessionId, SessionResult>>,
+}
+
+impl SessionStore for MemorySessionStore {
+    fn get(&self, id: &SessionId) -> Option<SessionResult> {
+        self.inner.lock().expect("store lock").get(id).cloned()
+    }
+
+    fn put_if_absent(&self, id: SessionId, result: SessionResult) -> SessionResult {
+        self.inner.lock().expect("store lock").entry(id).or_insert(result).clone()
+    }
+}
+
+pub struct Coordinator<S: SessionStore> {
+    store: Arc<S>,
+    public_key_package: PublicKeyPackage,
+    threshold: u16,
+    cache: Option<Mutex<ResultCache>>,
+    hooks: Vec<Arc<dyn ParticipationHook>>,
+    versions: VersionRange,
+}
+
+impl<S: SessionStore> Coordinator<S> {
+    pub fn new(store: Arc<S>, public_key_package: PublicKeyPackage, threshold: u16) -> Self {
+        Self { store, public_key_package, threshold, cache: None, hooks: Vec::new(), versions: VersionRange::supported() }
+    }
+
+    /// Only run sessions under versions in `versions`
+    pub fn with_versions(mut self, versions: VersionRange) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /// Highest version this coordinator and every one of `signers` support
+    pub fn negotiate_version(
+        &self,
+        participants: &BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+    ) -> Result<ProtocolVersion> {
+        let mut ranges = vec![self.versions];
+        for id in signers {
+            let Some(p) = participants.get(id) else { bail!("unknown participant") };
+            ranges.push(p.supported_versions());
+        }
+        negotiate(&ranges)
+    }
+
+    /// Serve repeated (epoch, message) requests from a local cache; see `cache` for what expires
+    pub fn with_cache(mut self, cfg: CacheConfig) -> Self {
+        self.cache = Some(Mutex::new(ResultCache::new(cfg)));
+        self
+    }
+
+    /// Report the participation of every round this coordinator runs to `hook`
+    pub fn with_hook(mut self, hook: Arc<dyn ParticipationHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.lock().expect("cache lock").stats())
+    }
+
+    /// `run_dvrf` for `epoch`, answered from the cache when possible
+    pub fn run_dvrf_cached(
+        &self,
+        epoch: u64,
+        msg: &DvrfInput,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+    ) -> Result<QuorumCert> {
+        let key = CacheKey::new(RoundKind::Dvrf, epoch, keccak256(&msg.to_hash_input()));
+        if let Some(hit) = self.cache_get(&key) {
+            return expect_dvrf(hit, msg);
+        }
+        let cert = self.run_dvrf(key.session_id(), msg, participants, signers)?;
+        self.cache_put(key, SessionResult::Dvrf(cert.clone()));
+        Ok(cert)
+    }
+
+    /// `run_signing` for `epoch`, answered from the cache when possible
+    pub fn run_signing_cached<R: RngCore + CryptoRng>(
+        &self,
+        epoch: u64,
+        msg: &AttestationMessage,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+        rng: &mut R,
+    ) -> Result<frost::Signature> {
+        let key = CacheKey::new(RoundKind::Signature, epoch, keccak256(&msg.to_signing_bytes()));
+        if let Some(hit) = self.cache_get(&key) {
+            return expect_signature(hit);
+        }
+        let sig = self.run_signing(key.session_id(), msg, participants, signers, rng)?;
+        self.cache_put(key, SessionResult::Signature(sig));
+        Ok(sig)
+    }
+
+    /// Challenge every reachable participant with a fresh nonce and report whether a t-quorum answers
+    pub fn check_liveness<R: RngCore + CryptoRng>(
+        &self,
+        participants: &BTreeMap<Identifier, Participant>,
+        rng: &mut R,
+    ) -> LivenessReport {
+        let mut nonce = [0u8; 32];
+        rng.fill_bytes(&mut nonce);
+        let responses: Vec<_> = participants.values().map(|p| p.liveness(&nonce, rng)).collect();
+        aggregate_liveness(nonce, &self.public_key_package, self.threshold, &responses)
+    }
+
+    fn notify(&self, round: RoundParticipation) {
+        for h in &self.hooks {
+            h.on_round(&round);
+        }
+    }
+
+    /// Partials under another version than the session's, or whose proof does not verify
+    /// against the signer's verifying share
+    fn invalid_partials(&self, version: ProtocolVersion, msg: &DvrfInput, partials: &[PartialEval]) -> Vec<Identifier> {
+        partials
+            .iter()
+            .filter(|p| match validated_vk_share(&self.public_key_package, p.id) {
+                Ok(vk_i) => p.version != version || !p.verify(msg, &vk_i),
+                Err(_) => true,
+            })
+            .map(|p| p.id)
+            .collect()
+    }
+
+    fn cache_get(&self, key: &CacheKey) -> Option<SessionResult> {
+        self.cache.as_ref()?.lock().expect("cache lock").get(key, Instant::now())
+    }
+
+    fn cache_put(&self, key: CacheKey, result: SessionResult) {
+        if let Some(c) = &self.cache {
+            c.lock().expect("cache lock").insert(key, result, Instant::now());
+        }
+    }
+
+    /// Collect partials from `signers`, verify and combine; or return the already completed result
+    pub fn run_dvrf(
+        &self,
+        session_id: SessionId,
+        msg: &DvrfInput,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+    ) -> Result<QuorumCert> {
+        if let Some(done) = self.store.get(&session_id) {
+            return expect_dvrf(done, msg);
+        }
+
+        let version = self.negotiate_version(participants, signers)?;
+        let mut partials = Vec::with_capacity(signers.len());
+        for id in signers {
+            let p = participants.get_mut(id).expect("checked by negotiate_version");
+            p.open_session(session_id, version)?;
+            partials.push(p.partial_eval(session_id, msg)?);
+        }
+        let verified = if partials.iter().any(|p| p.version != version) {
+            Err(anyhow!("partial evaluation under another protocol version than the session's"))
+        } else {
+            verify_combined(msg, &self.public_key_package, &partials, self.threshold)
+        };
+        let output: ProjectivePoint = match verified {
+            Ok(v) => v,
+            Err(e) => {
+                let bad = self.invalid_partials(version, msg, &partials);
+                self.notify(RoundParticipation {
+                    kind: RoundKind::Dvrf,
+                    session_id,
+                    contributors: partials.iter().map(|p| p.id).filter(|id| !bad.contains(id)).collect(),
+                    misbehaving: bad.into_iter().map(|id| (id, Misbehavior::InvalidProof)).collect(),
+                    completed: false,
+                });
+                return Err(e);
+            }
+        };
+        self.notify(RoundParticipation {
+            kind: RoundKind::Dvrf,
+            session_id,
+            contributors: partials.iter().map(|p| p.id).collect(),
+            misbehaving: Vec::new(),
+            completed: true,
+        });
+        let cert = QuorumCert { version, msg: msg.clone(), output, partials, vdf: None };
+
+        let done = expect_dvrf(self.store.put_if_absent(session_id, SessionResult::Dvrf(cert)), msg);
+        forget(participants, signers, &session_id);
+        done
+    }
+
+    /// FROST round 1 + 2 + aggregate; or return the already completed signature
+    pub fn run_signing<R: RngCore + CryptoRng>(
+        &self,
+        session_id: SessionId,
+        msg: &AttestationMessage,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+        rng: &mut R,
+    ) -> Result<frost::Signature> {
+        if let Some(done) = self.store.get(&session_id) {
+            return expect_signature(done);
+        }
+        if signers.len() < self.threshold as usize {
+            bail!("need {} signers, got {}", self.threshold, signers.len());
+        }
+
+        let version = self.negotiate_version(participants, signers)?;
+        let mut commitments = BTreeMap::new();
+        for id in signers {
+            let p = participants.get_mut(id).expect("checked by negotiate_version");
+            p.open_session(session_id, version)?;
+            commitments.insert(*id, p.commit(session_id, rng)?);
+        }
+        let signing_pkg = frost::SigningPackage::new(commitments, &msg.to_signing_bytes());
+
+        let mut shares = BTreeMap::new();
+        for id in signers {
+            let p = participants.get_mut(id).expect("checked above");
+            shares.insert(*id, p.sign(session_id, &signing_pkg)?);
+        }
+        let sig = self.aggregate_and_notify(session_id, &signing_pkg, &shares, Vec::new())?;
+
+        let done = expect_signature(self.store.put_if_absent(session_id, SessionResult::Signature(sig)));
+        forget(participants, signers, &session_id);
+        done
+    }
+}
+
+/// The result is stored: participants drop their per-session state
+fn forget(participants: &mut BTreeMap<Identifier, Participant>, signers: &[Identifier], session_id: &SessionId) {
+    for id in signers {
+        if let Some(p) = participants.get_mut(id) {
+            p.forget_session(session_id);
+        }
+    }
+}
+
+/// Spare pool, timeout and budget of a recoverable signing session
+#[derive(Clone, Debug)]
+pub struct RecoveryConfig {
+    /// replacements, taken in order; must not overlap the signer set
+    pub spares: Vec<Identifier>,
+    /// how long round 2 waits before missing signers may be replaced
+    pub share_timeout: Duration,
+    /// replacement rounds allowed; each costs every signer one preprocessed nonce pair
+    pub max_recoveries: u32,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self { spares: Vec::new(), share_timeout: Duration::from_secs(30), max_recoveries: 2 }
+    }
+}
+
+/// FROST session in round 2 that can replace dropped signers from a spare pool
+pub struct RecoverableSigning {
+    session_id: SessionId,
+    version: ProtocolVersion,
+    msg: AttestationMessage,
+    cfg: RecoveryConfig,
+    attempt: u32,
+    signers: Vec<Identifier>,
+    spares: Vec<Identifier>,
+    replaced: Vec<Identifier>,
+    /// (attempt, signer) → round-1 commitments
+    commitments: BTreeMap<(u32, Identifier), round1::SigningCommitments>,
+    package: frost::SigningPackage,
+    shares: BTreeMap<Identifier, round2::SignatureShare>,
+    /// round-2 shares by (attempt, signer); shares of the current attempt are verified first
+    inbox: Inbox,
+    /// signers that delivered a share failing verification
+    invalid: BTreeSet<Identifier>,
+    public_key_package: PublicKeyPackage,
+    deadline: Instant,
+}
+
+impl<S: SessionStore> Coordinator<S> {
+    /// Aggregate and report the round; a share rejected by aggregation is reported with its signer
+    fn aggregate_and_notify(
+        &self,
+        session_id: SessionId,
+        package: &frost::SigningPackage,
+        shares: &BTreeMap<Identifier, round2::SignatureShare>,
+        mut misbehaving: Vec<(Identifier, Misbehavior)>,
+    ) -> Result<frost::Signature> {
+        let result = frost::aggregate(package, shares, &self.public_key_package);
+        let mut contributors: Vec<Identifier> = shares.keys().copied().collect();
+        if result.is_err() {
+            // aggregation stops at the first bad share; check them all for the report
+            let pk = &self.public_key_package;
+            let bad: Vec<Identifier> = shares
+                .iter()
+                .filter(|(id, share)| match pk.verifying_shares().get(id) {
+                    Some(vs) => frost_core::verify_signature_share(**id, vs, share, package, pk.verifying_key()).is_err(),
+                    None => true,
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            contributors.retain(|id| !bad.contains(id));
+            misbehaving.extend(bad.into_iter().map(|id| (id, Misbehavior::InvalidSignatureShare)));
+        }
+        self.notify(RoundParticipation {
+            kind: RoundKind::Signature,
+            session_id,
+            contributors,
+            misbehaving,
+            completed: result.is_ok(),
+        });
+        Ok(result?)
+    }
+}
+
+fn attempt_package(
+    commitments: &BTreeMap<(u32, Identifier), round1::SigningCommitments>,
+    attempt: u32,
+    signers: &[Identifier],
+    msg: &AttestationMessage,
+) -> frost::SigningPackage {
+    let selected = signers.iter().map(|id| (*id, commitments[&(attempt, *id)])).collect();
+    frost::SigningPackage::new(selected, &msg.to_signing_bytes())
+}
+
+impl RecoverableSigning {
+    pub fn signers(&self) -> &[Identifier] {
+        &self.signers
+    }
+
+    /// Signers dropped so far
+    pub fn replaced(&self) -> &[Identifier] {
+        &self.replaced
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    pub fn signing_package(&self) -> &frost::SigningPackage {
+        &self.package
+    }
+
+    /// Current signers that have not delivered a share yet
+    pub fn missing(&self) -> Vec<Identifier> {
+        self.signers.iter().filter(|id| !self.shares.contains_key(id)).copied().collect()
+    }
+
+    /// Ask every signer still missing for its round-2 share. Unreachable or refusing signers
+    /// simply stay missing.
+    pub fn collect_shares(&mut self, participants: &mut BTreeMap<Identifier, Participant>) {
+        let sid = attempt_session_id(&self.session_id, self.attempt);
+        for id in self.missing() {
+            if let Some(share) = participants.get_mut(&id).and_then(|p| p.sign(sid, &self.package).ok()) {
+                // an invalid share is recorded and the signer stays missing
+                let _ = self.deliver_share(id, self.attempt, &share);
+            }
+        }
+    }
+
+    /// Take a round-2 share that arrived over the network for `attempt`. A share for the
+    /// current attempt is verified before it is kept; shares for a later attempt wait, and are
+    /// verified once that attempt starts.
+    pub fn deliver_share(&mut self, signer: Identifier, attempt: u32, share: &round2::SignatureShare) -> Result<Delivery> {
+        if attempt > self.cfg.max_recoveries {
+            bail!("attempt {} beyond the {} allowed recoveries", attempt, self.cfg.max_recoveries);
+        }
+        if attempt > self.attempt {
+            if !self.signers.contains(&signer) && !self.spares.contains(&signer) {
+                bail!("share from outside the signer set and spare pool");
+            }
+            return Ok(self.inbox.deliver(self.session_id, signer, attempt, &share.serialize()));
+        }
+        if attempt < self.attempt {
+            return Ok(Delivery::Stale);
+        }
+        if !self.signers.contains(&signer) {
+            bail!("share from a non-signer of attempt {}", attempt);
+        }
+        if !self.share_is_valid(signer, share) {
+            self.invalid.insert(signer);
+            bail!("invalid signature share");
+        }
+        let delivery = self.inbox.deliver(self.session_id, signer, attempt, &share.serialize());
+        if delivery == Delivery::Accepted {
+            self.shares.insert(signer, *share);
+        }
+        Ok(delivery)
+    }
+
+    fn share_is_valid(&self, signer: Identifier, share: &round2::SignatureShare) -> bool {
+        let pk = &self.public_key_package;
+        pk.verifying_shares()
+            .get(&signer)
+            .is_some_and(|vs| frost_core::verify_signature_share(signer, vs, share, &self.package, pk.verifying_key()).is_ok())
+    }
+
+    /// Signers that delivered an invalid share and never a valid one
+    pub fn invalid_signers(&self) -> Vec<Identifier> {
+        self.invalid.iter().filter(|id| !self.shares.contains_key(id)).copied().collect()
+    }
+
+    /// After the share timeout, replace every missing signer with a reachable spare and move
+    /// to the next attempt. Survivors sign again with their preprocessed nonces; only the
+    /// replacements run round 1. Returns the replacements.
+    pub fn recover<R: RngCore + CryptoRng>(
+        &mut self,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        now: Instant,
+        rng: &mut R,
+    ) -> Result<Vec<Identifier>> {
+        let missing = self.missing();
+        if missing.is_empty() {
+            bail!("no shares missing");
+        }
+        if now < self.deadline {
+            bail!("round 2 still open");
+        }
+        if self.attempt >= self.cfg.max_recoveries {
+            bail!("all {} recoveries used", self.cfg.max_recoveries);
+        }
+        let replacements: Vec<Identifier> = self
+            .spares
+            .iter()
+            .filter(|id| participants.get(id).is_some_and(|p| p.supported_versions().contains(self.version)))
+            .take(missing.len())
+            .copied()
+            .collect();
+        if replacements.len() < missing.len() {
+            bail!("spare pool exhausted: {} missing, {} spares reachable", missing.len(), replacements.len());
+        }
+
+        let next = self.attempt + 1;
+        for id in &replacements {
+            let p = participants.get_mut(id).expect("filtered above");
+            for a in next..=self.cfg.max_recoveries {
+                let sid = attempt_session_id(&self.session_id, a);
+                p.open_session(sid, self.version)?;
+                self.commitments.insert((a, *id), p.commit(sid, rng)?);
+            }
+        }
+        self.spares.retain(|id| !replacements.contains(id));
+        self.signers.retain(|id| !missing.contains(id));
+        self.signers.extend(&replacements);
+        self.signers.sort();
+        self.replaced.extend(missing);
+
+        self.attempt = next;
+        self.package = attempt_package(&self.commitments, next, &self.signers, &self.msg);
+        self.shares.clear();
+        for (id, bytes) in self.inbox.advance(self.session_id, next) {
+            if !self.signers.contains(&id) {
+                continue;
+            }
+            let share = round2::SignatureShare::deserialize(&bytes)?;
+            if self.share_is_valid(id, &share) {
+                self.shares.insert(id, share);
+            } else {
+                // make room for the real share
+                self.inbox.retract(&self.session_id, id, next);
+                self.invalid.insert(id);
+            }
+        }
+        self.deadline = now + self.cfg.share_timeout;
+        Ok(replacements)
+    }
+
+    /// Drop the preprocessed nonces nobody used
+    fn release(&self, participants: &mut BTreeMap<Identifier, Participant>) {
+        for (a, id) in self.commitments.keys() {
+            if let Some(p) = participants.get_mut(id) {
+                p.abort_signing(&attempt_session_id(&self.session_id, *a));
+            }
+        }
+    }
+
+    /// The result is stored: drop every attempt's state, signed or not
+    fn forget(&self, participants: &mut BTreeMap<Identifier, Participant>) {
+        for (a, id) in self.commitments.keys() {
+            if let Some(p) = participants.get_mut(id) {
+                p.forget_session(&attempt_session_id(&self.session_id, *a));
+            }
+        }
+    }
+}
+
+/// Round 1 of every signer for every attempt. If one fails, the sessions opened here are
+/// dropped again; sessions that already existed (another coordinator's) are left alone.
+fn preprocess<R: RngCore + CryptoRng>(
+    participants: &mut BTreeMap<Identifier, Participant>,
+    signers: &[Identifier],
+    session_id: SessionId,
+    version: ProtocolVersion,
+    max_recoveries: u32,
+    rng: &mut R,
+) -> Result<BTreeMap<(u32, Identifier), round1::SigningCommitments>> {
+    let mut commitments = BTreeMap::new();
+    let mut opened = Vec::new();
+    for id in signers {
+        let p = participants.get_mut(id).expect("checked by negotiate_version");
+        for a in 0..=max_recoveries {
+            let sid = attempt_session_id(&session_id, a);
+            if let Err(e) = p.open_session(sid, version) {
+                abort_opened(participants, &opened);
+                return Err(e);
+            }
+            if !p.has_signing_session(&sid) {
+                opened.push((*id, sid));
+            }
+            match p.commit(sid, rng) {
+                Ok(c) => commitments.insert((a, *id), c),
+                Err(e) => {
+                    abort_opened(participants, &opened);
+                    return Err(e);
+                }
+            };
+        }
+    }
+    Ok(commitments)
+}
+
+fn abort_opened(participants: &mut BTreeMap<Identifier, Participant>, opened: &[(Identifier, SessionId)]) {
+    for (id, sid) in opened {
+        participants.get_mut(id).expect("preprocessed above").abort_signing(sid);
+    }
+}
+
+impl<S: SessionStore> Coordinator<S> {
+    /// FROST round 1 for `signers` (one nonce pair per allowed attempt); round 2 is driven
+    /// through the returned session
+    pub fn begin_signing<R: RngCore + CryptoRng>(
+        &self,
+        session_id: SessionId,
+        msg: &AttestationMessage,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+        cfg: RecoveryConfig,
+        rng: &mut R,
+    ) -> Result<RecoverableSigning> {
+        let mut signers = signers.to_vec();
+        signers.sort();
+        signers.dedup();
+        if signers.len() < self.threshold as usize {
+            bail!("need {} distinct signers, got {}", self.threshold, signers.len());
+        }
+        if cfg.spares.iter().any(|id| signers.contains(id)) {
+            bail!("spare pool overlaps the signer set");
+        }
+        let version = self.negotiate_version(participants, &signers)?;
+        let commitments = preprocess(participants, &signers, session_id, version, cfg.max_recoveries, rng)?;
+        let package = attempt_package(&commitments, 0, &signers, msg);
+        let mut inbox = Inbox::new();
+        inbox.advance(session_id, 0);
+        let deadline = Instant::now() + cfg.share_timeout;
+        Ok(RecoverableSigning {
+            session_id,
+            version,
+            msg: msg.clone(),
+            attempt: 0,
+            signers,
+            spares: cfg.spares.clone(),
+            cfg,
+            replaced: Vec::new(),
+            commitments,
+            package,
+            shares: BTreeMap::new(),
+            inbox,
+            invalid: BTreeSet::new(),
+            public_key_package: self.public_key_package.clone(),
+            deadline,
+        })
+    }
+
+    /// Aggregate once every current signer delivered its share; or return the already
+    /// completed signature
+    pub fn finish_signing(
+        &self,
+        session: RecoverableSigning,
+        participants: &mut BTreeMap<Identifier, Participant>,
+    ) -> Result<frost::Signature> {
+        if let Some(done) = self.store.get(&session.session_id) {
+            session.forget(participants);
+            return expect_signature(done);
+        }
+        let missing = session.missing();
+        if !missing.is_empty() {
+            bail!("{} signature shares missing", missing.len());
+        }
+        let invalid = session.invalid_signers();
+        let mut misbehaving: Vec<_> = session
+            .replaced
+            .iter()
+            .map(|id| (*id, if invalid.contains(id) { Misbehavior::InvalidSignatureShare } else { Misbehavior::DroppedOut }))
+            .collect();
+        // once per signer, however many attempts it equivocated in
+        for e in session.inbox.equivocations() {
+            if !misbehaving.contains(&(e.sender, Misbehavior::Equivocation)) {
+                misbehaving.push((e.sender, Misbehavior::Equivocation));
+            }
+        }
+        let sig = match self.aggregate_and_notify(session.session_id, &session.package, &session.shares, misbehaving) {
+            Ok(sig) => sig,
+            Err(e) => {
+                session.release(participants);
+                return Err(e);
+            }
+        };
+        let done = expect_signature(self.store.put_if_absent(session.session_id, SessionResult::Signature(sig)));
+        session.forget(participants);
+        done
+    }
+}
+
+fn expect_dvrf(r: SessionResult, msg: &DvrfInput) -> Result<QuorumCert> {
+    match r {
+        SessionResult::Dvrf(cert) if cert.msg == *msg => Ok(cert),
+        SessionResult::Dvrf(_) => bail!("session completed for a different message"),
+        SessionResult::Signature(_) => bail!("session id belongs to a signing session"),
+        #[cfg(feature = "bls")]
+        SessionResult::BlsSignature(_) => bail!("session id belongs to a BLS signing session"),
+    }
+}
+
+fn expect_signature(r: SessionResult) -> Result<frost::Signature> {
+    match r {
+        SessionResult::Signature(sig) => Ok(sig),
+        SessionResult::Dvrf(_) => bail!("session id belongs to a DVRF session"),
+        #[cfg(feature = "bls")]
+        SessionResult::BlsSignature(_) => bail!("session id belongs to a BLS signing session"),
+    }
+}
+
+#[cfg(feature = "bls")]
+fn expect_bls(r: SessionResult) -> Result<crate::bls::BlsSignature> {
+    match r {
+        SessionResult::BlsSignature(sig) => Ok(sig),
+        SessionResult::Dvrf(_) => bail!("session id belongs to a DVRF session"),
+        SessionResult::Signature(_) => bail!("session id belongs to a FROST signing session"),
+    }
+}
+
+#[cfg(feature = "bls")]
+impl<S: SessionStore> Coordinator<S> {
+    /// Threshold BLS signature of `signers` on `msg`, each partial produced by its participant;
+    /// or return the already completed one
+    pub fn run_bls_signing(
+        &self,
+        session_id: SessionId,
+        msg: &AttestationMessage,
+        bls: &crate::bls::BlsPublicKeyPackage,
+        participants: &mut BTreeMap<Identifier, Participant>,
+        signers: &[Identifier],
+    ) -> Result<crate::bls::BlsSignature> {
+        if let Some(done) = self.store.get(&session_id) {
+            return expect_bls(done);
+        }
+        let partials: Result<Vec<_>> = signers
+            .iter()
+            .map(|id| match participants.get_mut(id) {
+                Some(p) => p.bls_sign(session_id, msg),
+                None => Err(anyhow!("unknown participant")),
+            })
+            .collect();
+        let sig = partials.and_then(|partials| bls.combine(msg, &partials));
+        let mut contributors = signers.to_vec();
+        contributors.sort();
+        contributors.dedup();
+        self.notify(RoundParticipation {
+            kind: RoundKind::Signature,
+            session_id,
+            contributors,
+            misbehaving: Vec::new(),
+            completed: sig.is_ok(),
+        });
+        expect_bls(self.store.put_if_absent(session_id, SessionResult::BlsSignature(sig?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::participant::participants_from_dkg;
+
+    #[test]
+    fn test_racing_coordinators_share_result() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+
+        let store = Arc::new(MemorySessionStore::default());
+        let a = Coordinator::new(store.clone(), out.public_key_package.clone(), cfg.min_signers);
+        let b = Coordinator::new(store.clone(), out.public_key_package.clone(), cfg.min_signers);
+
+        let beacon = DvrfInput::new(b"beacon".to_vec());
+        let sid = derive_session_id(b"dvrf", 1, beacon.raw());
+        let cert_a = a.run_dvrf(sid, &beacon, &mut parts, &ids[..3])?;
+        // b picks other signers but the session is already done: same cert, nobody is asked again
+        let cert_b = b.run_dvrf(sid, &beacon, &mut parts, &ids[2..])?;
+        assert_eq!(cert_a, cert_b);
+        assert_eq!(parts[&ids[4]].audit_log().entries().len(), 0);
+        assert!(b.run_dvrf(sid, &DvrfInput::new(b"other".to_vec()), &mut parts, &ids[..3]).is_err());
+
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 1, msg.raw());
+        let sig_a = a.run_signing(sid, &msg, &mut parts, &ids[..3], &mut rng)?;
+        let sig_b = b.run_signing(sid, &msg, &mut parts, &ids[..3], &mut rng)?;
+        assert_eq!(sig_a, sig_b);
+        Ok(())
+    }
+
+    #[test]
+    fn test_coordinators_without_shared_store_agree() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+
+        let a = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let b = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+
+        // overlapping rounds: participants answer both coordinators consistently, same
+        // commitments and cached shares
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 7, msg.raw());
+        let rc = RecoveryConfig { max_recoveries: 0, ..RecoveryConfig::default() };
+        let mut session_a = a.begin_signing(sid, &msg, &mut parts, &ids[..3], rc.clone(), &mut rng)?;
+        let mut session_b = b.begin_signing(sid, &msg, &mut parts, &ids[..3], rc.clone(), &mut rng)?;
+        session_a.collect_shares(&mut parts);
+        session_b.collect_shares(&mut parts);
+
+        // a different signer subset under the same session would need the nonces twice: refused
+        let c = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let mut session_c = c.begin_signing(sid, &msg, &mut parts, &ids[1..], rc, &mut rng)?;
+        session_c.collect_shares(&mut parts);
+        assert_eq!(session_c.missing(), vec![ids[1], ids[2]]);
+
+        let sig_a = a.finish_signing(session_a, &mut parts)?;
+        let sig_b = b.finish_signing(session_b, &mut parts)?;
+        assert_eq!(sig_a, sig_b);
+        assert!(out.public_key_package.verifying_key().verify(&msg.to_signing_bytes(), &sig_a).is_ok());
+
+        // the stored result released the session on every signer
+        let sid0 = attempt_session_id(&sid, 0);
+        assert!(ids[..3].iter().all(|id| !parts[id].has_signing_session(&sid0) && parts[id].session_version(&sid0).is_none()));
+        let dvrf_msg = DvrfInput::new(b"beacon".to_vec());
+        let dvrf_sid = derive_session_id(b"dvrf", 7, dvrf_msg.raw());
+        a.run_dvrf(dvrf_sid, &dvrf_msg, &mut parts, &ids[..3])?;
+        assert!(ids[..3].iter().all(|id| parts[id].session_version(&dvrf_sid).is_none()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_participation_hook_sees_contributors_and_misbehavior() -> Result<()> {
+        use crate::participation::{ParticipationTally, Tally};
+
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let tally = Arc::new(ParticipationTally::default());
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers)
+            .with_hook(tally.clone());
+
+        let msg = DvrfInput::new(b"beacon".to_vec());
+        let sid = derive_session_id(b"dvrf", 1, msg.raw());
+        coord.run_dvrf(sid, &msg, &mut parts, &ids[..3])?;
+        // answered from the store: not a new round
+        coord.run_dvrf(sid, &msg, &mut parts, &ids[..3])?;
+        assert_eq!(tally.get(&ids[0]).contributed, 1);
+
+        // ids[4] holds a share that does not match its verifying share
+        let (kp3, kp4) = (out.key_packages[&ids[3]].clone(), out.key_packages[&ids[4]].clone());
+        let forged = crate::dkg::KeyPackage::new(ids[4], *kp3.signing_share(), *kp4.verifying_share(), *kp4.verifying_key(), *kp4.min_signers());
+        parts.insert(ids[4], Participant::try_new(forged, out.public_key_package.clone())?);
+
+        let sid = derive_session_id(b"dvrf", 2, msg.raw());
+        assert!(coord.run_dvrf(sid, &msg, &mut parts, &ids[2..]).is_err());
+        let amsg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 2, amsg.raw());
+        assert!(coord.run_signing(sid, &amsg, &mut parts, &ids[2..], &mut rng).is_err());
+
+        assert_eq!(tally.get(&ids[4]), Tally { contributed: 0, misbehaved: 2 });
+        assert_eq!(tally.get(&ids[2]), Tally { contributed: 3, misbehaved: 0 });
+        assert_eq!(tally.get(&ids[1]).contributed, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_signing_recovers_from_dropped_signer() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(6, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 3, msg.raw());
+        let rc = RecoveryConfig { spares: ids[3..5].to_vec(), share_timeout: Duration::from_secs(5), max_recoveries: 1 };
+        let mut s = coord.begin_signing(sid, &msg, &mut parts, &ids[..3], rc.clone(), &mut rng)?;
+
+        // ids[1] goes away after round 1
+        let dropped = parts.remove(&ids[1]).unwrap();
+        s.collect_shares(&mut parts);
+        assert_eq!(s.missing(), vec![ids[1]]);
+        assert!(s.recover(&mut parts, Instant::now(), &mut rng).is_err());
+
+        let replacements = s.recover(&mut parts, Instant::now() + rc.share_timeout, &mut rng)?;
+        assert_eq!(replacements, vec![ids[3]]);
+        assert_eq!(s.signers(), &[ids[0], ids[2], ids[3]]);
+        // survivors sign the new package with their preprocessed nonces
+        assert!(parts[&ids[0]].has_signing_session(&attempt_session_id(&sid, 1)));
+        s.collect_shares(&mut parts);
+
+        let sig = coord.finish_signing(s, &mut parts)?;
+        assert!(out.public_key_package.verifying_key().verify(&msg.to_signing_bytes(), &sig).is_ok());
+        for id in [ids[0], ids[2], ids[3]] {
+            assert_eq!(parts[&id].open_signing_sessions(), 0);
+        }
+        parts.insert(ids[1], dropped);
+
+        // recovery budget spent
+        let sid = derive_session_id(b"frost", 4, msg.raw());
+        let rc = RecoveryConfig { spares: ids[3..4].to_vec(), ..rc };
+        let mut s = coord.begin_signing(sid, &msg, &mut parts, &ids[..3], rc.clone(), &mut rng)?;
+        parts.remove(&ids[0]);
+        s.collect_shares(&mut parts);
+        let later = Instant::now() + rc.share_timeout;
+        s.recover(&mut parts, later, &mut rng)?;
+        parts.remove(&ids[2]);
+        s.collect_shares(&mut parts);
+        assert!(s.recover(&mut parts, later + rc.share_timeout, &mut rng).is_err());
+        assert!(coord.finish_signing(s, &mut parts).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_begin_signing_dedups_and_cleans_up() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 9, msg.raw());
+        let rc = RecoveryConfig { max_recoveries: 1, ..RecoveryConfig::default() };
+
+        // a repeated id does not count twice towards the threshold
+        assert!(coord.begin_signing(sid, &msg, &mut parts, &[ids[0], ids[1], ids[1]], rc.clone(), &mut rng).is_err());
+
+        // ids[2]'s second attempt is already bound to another version: round 1 fails there,
+        // and nobody is left holding nonces for this session
+        let wide = VersionRange::new(ProtocolVersion::V2, ProtocolVersion(3))?;
+        let p2 = parts.get_mut(&ids[2]).unwrap();
+        p2.set_supported_versions(wide);
+        p2.open_session(attempt_session_id(&sid, 1), ProtocolVersion(3))?;
+        assert!(coord.begin_signing(sid, &msg, &mut parts, &ids[..3], rc, &mut rng).is_err());
+        for id in &ids[..3] {
+            assert_eq!(parts[id].open_signing_sessions(), 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_and_conflicting_shares() -> Result<()> {
+        use crate::participation::{ParticipationTally, Tally};
+
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let tally = Arc::new(ParticipationTally::default());
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers)
+            .with_hook(tally.clone());
+
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sid = derive_session_id(b"frost", 5, msg.raw());
+        let rc = RecoveryConfig { spares: ids[3..].to_vec(), share_timeout: Duration::from_secs(5), max_recoveries: 1 };
+        let mut s = coord.begin_signing(sid, &msg, &mut parts, &ids[..3], rc, &mut rng)?;
+        let pkg = s.signing_package().clone();
+        let share_of = |parts: &mut BTreeMap<Identifier, Participant>, id: Identifier| parts.get_mut(&id).unwrap().sign(attempt_session_id(&sid, 0), &pkg);
+
+        let s0 = share_of(&mut parts, ids[0])?;
+        let s1 = share_of(&mut parts, ids[1])?;
+        // someone else's share injected under ids[0] is refused and does not lock out the real one
+        assert!(s.deliver_share(ids[0], 0, &s1).is_err());
+        assert_eq!(s.deliver_share(ids[0], 0, &s0)?, Delivery::Accepted);
+        assert_eq!(s.deliver_share(ids[0], 0, &s0)?, Delivery::Duplicate);
+        // a share for the next attempt waits; nothing is kept beyond the recovery budget or
+        // from outside the session
+        assert_eq!(s.deliver_share(ids[1], 1, &s1)?, Delivery::Buffered);
+        // a second, different share for that attempt is equivocation, reported with the round
+        assert!(matches!(s.deliver_share(ids[1], 1, &s0)?, Delivery::Equivocation(ref e) if e.sender == ids[1]));
+        assert!(s.deliver_share(ids[1], 2, &s1).is_err());
+        assert!(s.deliver_share(ids[3], 0, &s1).is_err());
+        assert_eq!(s.missing(), vec![ids[1], ids[2]]);
+
+        s.collect_shares(&mut parts);
+        let sig = coord.finish_signing(s, &mut parts)?;
+        assert!(out.public_key_package.verifying_key().verify(&msg.to_signing_bytes(), &sig).is_ok());
+        assert_eq!(tally.get(&ids[0]), Tally { contributed: 1, misbehaved: 0 });
+        assert_eq!(tally.get(&ids[1]), Tally { contributed: 1, misbehaved: 1 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_sessions_run_under_negotiated_version() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let msg = DvrfInput::new(b"beacon".to_vec());
+
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        assert_eq!(coord.negotiate_version(&parts, &ids[..3])?, ProtocolVersion::V2);
+        let sid = derive_session_id(b"dvrf", 1, msg.raw());
+        let cert = coord.run_dvrf(sid, &msg, &mut parts, &ids[..3])?;
+        assert_eq!(cert.version, ProtocolVersion::V2);
+        assert!(cert.partials.iter().all(|p| p.version == ProtocolVersion::V2));
+
+        // a coordinator that only speaks a newer version finds no common one and asks nobody
+        let v3 = ProtocolVersion(3);
+        let newer = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers)
+            .with_versions(VersionRange::new(v3, v3)?);
+        let sid = derive_session_id(b"dvrf", 2, msg.raw());
+        assert!(newer.run_dvrf(sid, &msg, &mut parts, &ids[..3]).is_err());
+        assert_eq!(parts[&ids[0]].audit_log().entries().len(), 1);
+        Ok(())
+    }
+}
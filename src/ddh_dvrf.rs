@@ -1,5 +1,7 @@
+#[cfg(feature = "protocol")]
 use std::collections::BTreeMap;
 
+use anyhow::{anyhow, Result};
 use frost_secp256k1_evm as frost;
 
 use k256::{
@@ -7,7 +9,18 @@ use k256::{
     elliptic_curve::{ops::Reduce, FieldBytes, bigint::U256},
 };
 
-use crate::utils::{prove_eq, verify_eq, lagrange_combine_points};
+#[cfg(feature = "protocol")]
+use frost::rand_core::{CryptoRng, OsRng, RngCore};
+#[cfg(feature = "protocol")]
+use crate::utils::{prove_eq, prove_eq_with_rng, verify_eq, lagrange_combine_points};
+#[cfg(feature = "protocol")]
+use crate::cert::{PartialEval, QuorumCert};
+#[cfg(feature = "protocol")]
+use crate::messages::DvrfInput;
+#[cfg(feature = "protocol")]
+use crate::version::ProtocolVersion;
+
+use crate::validated::ValidatedPoint;
 
 pub type Identifier        = frost::Identifier;
 pub type KeyPackage        = frost::keys::KeyPackage;
@@ -43,6 +56,15 @@ pub fn vk_share_from_public_pkg(pkpkg: &PublicKeyPackage, id: Identifier) -> Pro
 }
 
 
+/// vk_i as a checked point, for verification paths
+pub fn validated_vk_share(pkpkg: &PublicKeyPackage, id: Identifier) -> Result<ValidatedPoint> {
+    let vk_share = pkpkg
+        .verifying_shares()
+        .get(&id)
+        .ok_or_else(|| anyhow!("unknown signer id={}", id_as_u64(id)))?;
+    ValidatedPoint::new(vk_share.to_element())
+}
+
 pub fn id_as_u64(id: Identifier) -> u64 {
     let bytes = id.serialize();
     let mut arr = [0u8; 8];
@@ -55,8 +77,9 @@ pub fn id_as_u64(id: Identifier) -> u64 {
 /// - For the selected signers I (size ≥ t), each signer produces (v_i, π_i)
 /// - Each π_i is verified
 /// - The values are combined using LagrangeCombine({(i, v_i)}) to obtain v
+#[cfg(feature = "protocol")]
 pub fn run_ddh_dvrf_once(
-    msg: &[u8],
+    msg: &DvrfInput,
     key_packages: &BTreeMap<Identifier, KeyPackage>,
     public_key_package: &PublicKeyPackage,
     signers: &[Identifier],   //  (t-of-n)
@@ -69,16 +92,16 @@ pub fn run_ddh_dvrf_once(
     for id in signers {
         let kp = key_packages.get(id).expect("id has KeyPackage");
         let sk_i = scalar_from_keypackage(kp);
-        let vk_i = vk_share_from_public_pkg(public_key_package, *id);
+        let vk_i = validated_vk_share(public_key_package, *id).expect("valid verifying share for id");
 
-        let (v_i, proof) = prove_eq(msg, vk_i, sk_i);
+        let (v_i, proof) = prove_eq(ProtocolVersion::CURRENT, msg, *vk_i, sk_i).expect("DKG shares are non-zero");
 
         // kanıtı kontrol et
-        let ok = verify_eq(msg, &vk_i, &v_i, &proof);
+        let ok = verify_eq(ProtocolVersion::CURRENT, msg, &vk_i, &v_i, &proof);
         assert!(ok, "prove_eq / verify_eq failed for id={}", id_as_u64(*id));
 
-        good_points.push((id_as_u64(*id), v_i));
-        exported_points_for_debug.push((*id, v_i));
+        good_points.push((id_as_u64(*id), *v_i));
+        exported_points_for_debug.push((*id, *v_i));
     }
 
     // 2) Lagrange combine: v = Σ λ_i * v_i   (additive form)
@@ -86,3 +109,39 @@ pub fn run_ddh_dvrf_once(
 
     (v, exported_points_for_debug)
 }
+
+/// Same round as `run_ddh_dvrf_once`, but keeps the proofs so anyone can re-verify the output
+#[cfg(feature = "protocol")]
+pub fn run_ddh_dvrf_cert(
+    msg: &DvrfInput,
+    key_packages: &BTreeMap<Identifier, KeyPackage>,
+    public_key_package: &PublicKeyPackage,
+    signers: &[Identifier],
+) -> QuorumCert {
+    run_ddh_dvrf_cert_with_rng(msg, key_packages, public_key_package, signers, &mut OsRng)
+}
+
+/// `run_ddh_dvrf_cert` with the proof nonces drawn from `rng`, under `ProtocolVersion::CURRENT`
+#[cfg(feature = "protocol")]
+pub fn run_ddh_dvrf_cert_with_rng<R: RngCore + CryptoRng>(
+    msg: &DvrfInput,
+    key_packages: &BTreeMap<Identifier, KeyPackage>,
+    public_key_package: &PublicKeyPackage,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> QuorumCert {
+    let mut points = Vec::with_capacity(signers.len());
+    let mut partials = Vec::with_capacity(signers.len());
+
+    for id in signers {
+        let kp = key_packages.get(id).expect("id has KeyPackage");
+        let vk_i = vk_share_from_public_pkg(public_key_package, *id);
+        let (v_i, proof) = prove_eq_with_rng(ProtocolVersion::CURRENT, msg, vk_i, scalar_from_keypackage(kp), rng)
+            .expect("DKG shares are non-zero");
+
+        points.push((id_as_u64(*id), *v_i));
+        partials.push(PartialEval { version: ProtocolVersion::CURRENT, id: *id, v_i, proof });
+    }
+
+    QuorumCert { version: ProtocolVersion::CURRENT, msg: msg.clone(), output: lagrange_combine_points(&points), partials, vdf: None }
+}
@@ -6,6 +6,10 @@ use frost_secp256k1_evm as frost;
 
 use frost::rand_core::{CryptoRng, RngCore};
 
+use crate::broadcast::{BroadcastChannel, MemoryChannel};
+use crate::possession::{prove_possession, verify_possessions, PossessionProof};
+use crate::utils::keccak256;
+
 pub type Identifier = frost::Identifier;
 pub type KeyPackage = frost::keys::KeyPackage;
 pub type PublicKeyPackage = frost::keys::PublicKeyPackage;
@@ -30,6 +34,10 @@ impl DkgConfig {
 pub struct DkgOutput {
     pub key_packages: BTreeMap<Identifier, KeyPackage>,
     pub public_key_package: PublicKeyPackage,
+    /// keccak over the round-1 broadcast packages, in identifier order
+    pub transcript_hash: [u8; 32],
+    /// every member's signature over `transcript_hash` with its share, see `possession`
+    pub possession_proofs: BTreeMap<Identifier, PossessionProof>,
 }
 
 impl DkgOutput {
@@ -38,29 +46,59 @@ impl DkgOutput {
         v.sort();
         v
     }
+
+    /// t
+    pub fn min_signers(&self) -> u16 {
+        *self.key_packages.values().next().expect("DKG output has key packages").min_signers()
+    }
 }
 
 /// Local DKG
 pub fn run_dealerless_dkg<R: RngCore + CryptoRng>(cfg: DkgConfig, rng: &mut R) -> Result<DkgOutput> {
+    run_dealerless_dkg_with_channel(cfg, &mut MemoryChannel::default(), rng)
+}
+
+/// Local DKG whose round-1 broadcasts go through `channel`; everyone reads the packages back
+/// from the channel, so the transcript is exactly what was published there.
+pub fn run_dealerless_dkg_with_channel<R: RngCore + CryptoRng>(
+    cfg: DkgConfig,
+    channel: &mut dyn BroadcastChannel,
+    rng: &mut R,
+) -> Result<DkgOutput> {
     let n = cfg.max_signers;
     let t = cfg.min_signers;
 
     // --- Round 1: herkes kendi Part1 secret'ını ve broadcast paketini üretir.
     let mut round1_secret = BTreeMap::<Identifier, _>::new();
-    let mut recv_r1_pkgs  = BTreeMap::<Identifier, BTreeMap<Identifier, _>>::new();
-
     for i in 1..=n {
         let id: Identifier = i.try_into().expect("nonzero id");
         let (r1_secret, r1_pkg) = frost::keys::dkg::part1(id, n, t, &mut *rng)?;
         round1_secret.insert(id, r1_secret);
+        channel.publish(id, &r1_pkg.serialize()?)?;
+    }
 
-        for j in 1..=n {
-            if j == i { continue; }
-            let rid: Identifier = j.try_into().unwrap();
-            recv_r1_pkgs.entry(rid)
-                .or_insert_with(BTreeMap::new)
-                .insert(id, r1_pkg.clone());
-        }
+    let published = channel.collect()?;
+    if published.len() != n as usize || !round1_secret.keys().all(|id| published.contains_key(id)) {
+        bail!("broadcast channel returned {} round-1 packages, expected {}", published.len(), n);
+    }
+
+    // transcript: every party sees the same round-1 broadcasts
+    let mut transcript = Vec::new();
+    let mut r1_pkgs_by_sender = BTreeMap::new();
+    for (id, bytes) in &published {
+        transcript.extend_from_slice(&id.serialize());
+        transcript.extend_from_slice(bytes);
+        r1_pkgs_by_sender.insert(*id, frost::keys::dkg::round1::Package::deserialize(bytes)?);
+    }
+    let transcript_hash = keccak256(&transcript);
+
+    let mut recv_r1_pkgs = BTreeMap::<Identifier, BTreeMap<Identifier, _>>::new();
+    for rid in round1_secret.keys() {
+        let others = r1_pkgs_by_sender.iter()
+            .filter(|(id, _)| *id != rid)
+            .map(|(id, pkg)| (*id, pkg.clone()))
+            .collect();
+        recv_r1_pkgs.insert(*rid, others);
     }
 
     // --- Round 2
@@ -102,7 +140,14 @@ pub fn run_dealerless_dkg<R: RngCore + CryptoRng>(cfg: DkgConfig, rng: &mut R) -
     }
 
     let public_key_package = pubkey_pkg_opt.expect("same across participants");
-    Ok(DkgOutput { key_packages, public_key_package })
+
+    // --- Finalization: nobody is enrolled without proving it holds its share
+    let possession_proofs = key_packages
+        .iter()
+        .map(|(id, kp)| (*id, prove_possession(&transcript_hash, kp, &mut *rng)))
+        .collect();
+    verify_possessions(&transcript_hash, &public_key_package, &possession_proofs)?;
+    Ok(DkgOutput { key_packages, public_key_package, transcript_hash, possession_proofs })
 }
 
 
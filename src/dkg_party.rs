@@ -0,0 +1,272 @@
+//! One DKG member as a message-driven state machine, for deployments where round packages
+//! travel over an unreliable network. Packages go through an `Inbox`: re-deliveries are
+//! ignored, round-2 packages that overtake round 1 wait until the party reaches round 2, and a
+//! sender contradicting itself is recorded as an equivocation while its first package stands.
+//!
+//! Round 3 is enrollment: each member broadcasts its proof of possession over the transcript
+//! hash, and the party only finishes once every member's proof verifies.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::keys::dkg::{round1, round2};
+use frost::rand_core::{CryptoRng, RngCore};
+
+use crate::dkg::{DkgConfig, Identifier, KeyPackage, PublicKeyPackage};
+use crate::inbox::{Delivery, Equivocation, Inbox, Round};
+use crate::possession::{prove_possession, verify_possessions, PossessionProof};
+use crate::participant::SessionId;
+use crate::utils::keccak256;
+
+pub const DKG_ROUND1: Round = 1;
+pub const DKG_ROUND2: Round = 2;
+pub const DKG_ROUND3: Round = 3;
+
+/// A serialized round package; rounds 1 and 3 are broadcast (`recipient` None), round 2 is addressed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DkgEnvelope {
+    pub session_id: SessionId,
+    pub sender: Identifier,
+    pub recipient: Option<Identifier>,
+    pub round: Round,
+    pub payload: Vec<u8>,
+}
+
+pub struct DkgPartyOutput {
+    pub key_package: KeyPackage,
+    pub public_key_package: PublicKeyPackage,
+    /// same as `DkgOutput::transcript_hash`
+    pub transcript_hash: [u8; 32],
+    /// same as `DkgOutput::possession_proofs`
+    pub possession_proofs: BTreeMap<Identifier, PossessionProof>,
+}
+
+enum PartyState {
+    Round1(round1::SecretPackage),
+    Round2 { secret: round2::SecretPackage, round1: BTreeMap<Identifier, round1::Package> },
+    Round3 {
+        key_package: KeyPackage,
+        public_key_package: PublicKeyPackage,
+        transcript_hash: [u8; 32],
+        own_proof: PossessionProof,
+    },
+    Done(DkgPartyOutput),
+}
+
+pub struct DkgParty {
+    id: Identifier,
+    session_id: SessionId,
+    others: Vec<Identifier>,
+    own_round1: Vec<u8>,
+    inbox: Inbox,
+    /// None while a round transition runs, and for good once one failed
+    state: Option<PartyState>,
+}
+
+impl DkgParty {
+    /// Members are 1..=n; returns the party and its round-1 broadcast
+    pub fn start<R: RngCore + CryptoRng>(id: Identifier, cfg: DkgConfig, session_id: SessionId, rng: &mut R) -> Result<(Self, DkgEnvelope)> {
+        let others: Vec<Identifier> = (1..=cfg.max_signers)
+            .map(|i| Identifier::try_from(i).expect("nonzero id"))
+            .filter(|other| *other != id)
+            .collect();
+        if others.len() + 1 != cfg.max_signers as usize {
+            bail!("identifier is not a member of this DKG");
+        }
+        let (secret, pkg) = frost::keys::dkg::part1(id, cfg.max_signers, cfg.min_signers, rng)?;
+        let own_round1 = pkg.serialize()?;
+        let mut inbox = Inbox::new();
+        inbox.advance(session_id, DKG_ROUND1);
+        let envelope = DkgEnvelope { session_id, sender: id, recipient: None, round: DKG_ROUND1, payload: own_round1.clone() };
+        Ok((Self { id, session_id, others, own_round1, inbox, state: Some(PartyState::Round1(secret)) }, envelope))
+    }
+
+    pub fn id(&self) -> Identifier {
+        self.id
+    }
+
+    /// Take one envelope; returns what happened to it and the envelopes to send as a result
+    pub fn receive<R: RngCore + CryptoRng>(&mut self, env: &DkgEnvelope, rng: &mut R) -> Result<(Delivery, Vec<DkgEnvelope>)> {
+        if env.session_id != self.session_id {
+            bail!("envelope belongs to another DKG session");
+        }
+        if !self.others.contains(&env.sender) {
+            bail!("envelope from a non-member");
+        }
+        match env.round {
+            DKG_ROUND1 if env.recipient.is_none() => {
+                round1::Package::deserialize(&env.payload)?;
+            }
+            DKG_ROUND2 if env.recipient == Some(self.id) => {
+                round2::Package::deserialize(&env.payload)?;
+            }
+            DKG_ROUND3 if env.recipient.is_none() => {
+                PossessionProof::from_bytes(env.sender, &env.payload)?;
+            }
+            _ => bail!("envelope is not a round-{} package for this party", env.round),
+        }
+        let delivery = self.inbox.deliver(self.session_id, env.sender, env.round, &env.payload);
+        let out = match delivery {
+            Delivery::Accepted | Delivery::Buffered => self.progress(rng)?,
+            _ => Vec::new(),
+        };
+        Ok((delivery, out))
+    }
+
+    /// Move through every round whose packages are all in
+    fn progress<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> Result<Vec<DkgEnvelope>> {
+        let mut out = Vec::new();
+        loop {
+            let round = match &self.state {
+                Some(PartyState::Round1(_)) => DKG_ROUND1,
+                Some(PartyState::Round2 { .. }) => DKG_ROUND2,
+                Some(PartyState::Round3 { .. }) => DKG_ROUND3,
+                _ => return Ok(out),
+            };
+            let received = self.inbox.received(&self.session_id, round);
+            if received.len() < self.others.len() {
+                return Ok(out);
+            }
+            self.state = Some(match self.state.take().expect("checked above") {
+                PartyState::Round1(secret) => {
+                    let round1 = received
+                        .iter()
+                        .map(|(id, bytes)| Ok((*id, round1::Package::deserialize(bytes)?)))
+                        .collect::<Result<BTreeMap<_, _>>>()?;
+                    let (secret, packages) = frost::keys::dkg::part2(secret, &round1)?;
+                    for (recipient, pkg) in packages {
+                        out.push(DkgEnvelope {
+                            session_id: self.session_id,
+                            sender: self.id,
+                            recipient: Some(recipient),
+                            round: DKG_ROUND2,
+                            payload: pkg.serialize()?,
+                        });
+                    }
+                    self.inbox.advance(self.session_id, DKG_ROUND2);
+                    PartyState::Round2 { secret, round1 }
+                }
+                PartyState::Round2 { secret, round1 } => {
+                    let round2 = received
+                        .iter()
+                        .map(|(id, bytes)| Ok((*id, round2::Package::deserialize(bytes)?)))
+                        .collect::<Result<BTreeMap<_, _>>>()?;
+                    let (key_package, public_key_package) = frost::keys::dkg::part3(&secret, &round1, &round2)?;
+                    let transcript_hash = self.transcript_hash(&round1)?;
+                    let own_proof = prove_possession(&transcript_hash, &key_package, rng);
+                    out.push(DkgEnvelope {
+                        session_id: self.session_id,
+                        sender: self.id,
+                        recipient: None,
+                        round: DKG_ROUND3,
+                        payload: own_proof.to_bytes().to_vec(),
+                    });
+                    self.inbox.advance(self.session_id, DKG_ROUND3);
+                    PartyState::Round3 { key_package, public_key_package, transcript_hash, own_proof }
+                }
+                PartyState::Round3 { key_package, public_key_package, transcript_hash, own_proof } => {
+                    let mut possession_proofs = received
+                        .iter()
+                        .map(|(id, bytes)| Ok((*id, PossessionProof::from_bytes(*id, bytes)?)))
+                        .collect::<Result<BTreeMap<_, _>>>()?;
+                    possession_proofs.insert(self.id, own_proof);
+                    verify_possessions(&transcript_hash, &public_key_package, &possession_proofs)?;
+                    PartyState::Done(DkgPartyOutput { key_package, public_key_package, transcript_hash, possession_proofs })
+                }
+                PartyState::Done(_) => unreachable!("returned above"),
+            });
+        }
+    }
+
+    fn transcript_hash(&self, round1: &BTreeMap<Identifier, round1::Package>) -> Result<[u8; 32]> {
+        let mut all: BTreeMap<Identifier, Vec<u8>> = BTreeMap::from([(self.id, self.own_round1.clone())]);
+        for (id, pkg) in round1 {
+            all.insert(*id, pkg.serialize()?);
+        }
+        let mut transcript = Vec::new();
+        for (id, bytes) in &all {
+            transcript.extend_from_slice(&id.serialize());
+            transcript.extend_from_slice(bytes);
+        }
+        Ok(keccak256(&transcript))
+    }
+
+    pub fn output(&self) -> Option<&DkgPartyOutput> {
+        match &self.state {
+            Some(PartyState::Done(out)) => Some(out),
+            _ => None,
+        }
+    }
+
+    pub fn equivocations(&self) -> &[Equivocation] {
+        self.inbox.equivocations()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+
+    #[test]
+    fn test_reordered_and_repeated_delivery() -> Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+        let sid = [5u8; 32];
+        let mut parties = BTreeMap::new();
+        let mut queue = Vec::new();
+        for i in 1..=3u16 {
+            let id: Identifier = i.try_into()?;
+            let (party, env) = DkgParty::start(id, cfg, sid, &mut OsRng)?;
+            parties.insert(id, party);
+            queue.push(env);
+        }
+
+        // newest first, every envelope delivered twice
+        let mut deliveries = 0;
+        while let Some(env) = queue.pop() {
+            let targets: Vec<Identifier> = match env.recipient {
+                Some(r) => vec![r],
+                None => parties.keys().filter(|id| **id != env.sender).copied().collect(),
+            };
+            for to in targets {
+                let party = parties.get_mut(&to).unwrap();
+                let (first, out) = party.receive(&env, &mut OsRng)?;
+                assert!(matches!(first, Delivery::Accepted | Delivery::Buffered));
+                assert_eq!(party.receive(&env, &mut OsRng)?.0, Delivery::Duplicate);
+                queue.extend(out);
+                deliveries += 1;
+            }
+        }
+        // round-1 broadcasts, round-2 shares, round-3 proofs of possession
+        assert_eq!(deliveries, 3 * 2 + 3 * 2 + 3 * 2);
+
+        let outs: Vec<&DkgPartyOutput> = parties.values().map(|p| p.output().expect("finished")).collect();
+        assert!(outs.iter().all(|o| o.public_key_package == outs[0].public_key_package));
+        assert!(outs.iter().all(|o| o.transcript_hash == outs[0].transcript_hash));
+        assert!(outs.iter().all(|o| o.possession_proofs == outs[0].possession_proofs && o.possession_proofs.len() == 3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivocating_sender_reported() -> Result<()> {
+        let cfg = DkgConfig::new(3, 2)?;
+        let sid = [6u8; 32];
+        let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().unwrap()).collect();
+        let (mut alice, _) = DkgParty::start(ids[0], cfg, sid, &mut OsRng)?;
+        let (_, honest) = DkgParty::start(ids[1], cfg, sid, &mut OsRng)?;
+        let (_, second) = DkgParty::start(ids[1], cfg, sid, &mut OsRng)?;
+
+        assert_eq!(alice.receive(&honest, &mut OsRng)?.0, Delivery::Accepted);
+        let (delivery, out) = alice.receive(&second, &mut OsRng)?;
+        assert!(matches!(delivery, Delivery::Equivocation(ref e) if e.sender == ids[1] && e.first == keccak256(&honest.payload)));
+        assert!(out.is_empty());
+        assert_eq!(alice.equivocations().len(), 1);
+
+        // wrong session, non-member, misaddressed
+        assert!(alice.receive(&DkgEnvelope { session_id: [0; 32], ..honest.clone() }, &mut OsRng).is_err());
+        assert!(alice.receive(&DkgEnvelope { sender: ids[0], ..honest.clone() }, &mut OsRng).is_err());
+        assert!(alice.receive(&DkgEnvelope { recipient: Some(ids[2]), ..honest }, &mut OsRng).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Text encodings for keys, outputs and proofs, shared by the CLI, HTTP and export paths.
+//!
+//! - hex with `0x` prefix (decoding also takes bare hex)
+//! - bare hex, for formats that fix it (reference vectors, directory names)
+//! - base64 (standard alphabet, padded)
+//! - bech32 (BIP-173 checksum) with a caller-chosen HRP; the 90-character cap of BIP-173 is
+//!   not enforced so 64-byte proofs fit too
+//!
+//! `EncodingRegistry` picks the encoding per interface.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use base64ct::{Base64, Encoding as _};
+
+pub fn hex0x(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Accepts `0x`-prefixed or bare hex
+pub fn from_hex0x(s: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(s.strip_prefix("0x").unwrap_or(s))?)
+}
+
+pub fn bare_hex(bytes: &[u8]) -> String {
+    hex::encode(bytes)
+}
+
+/// Rejects a `0x` prefix
+pub fn from_bare_hex(s: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(s)?)
+}
+
+pub fn base64(bytes: &[u8]) -> String {
+    Base64::encode_string(bytes)
+}
+
+pub fn from_base64(s: &str) -> Result<Vec<u8>> {
+    Base64::decode_vec(s).map_err(|e| anyhow!("invalid base64: {e}"))
+}
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk = 1u32;
+    for v in values {
+        let b = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ *v as u32;
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|c| c & 31));
+    out
+}
+
+/// Regroup bits (8 → 5 or 5 → 8)
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>> {
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let maxv = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for v in data {
+        acc = (acc << from) | *v as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & maxv) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & maxv) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & maxv) != 0 {
+        bail!("invalid bech32 padding");
+    }
+    Ok(out)
+}
+
+pub fn bech32(hrp: &str, bytes: &[u8]) -> Result<String> {
+    if hrp.is_empty() || !hrp.bytes().all(|c| (33..=126).contains(&c) && !c.is_ascii_uppercase()) {
+        bail!("invalid bech32 HRP {hrp:?}");
+    }
+    let data = convert_bits(bytes, 8, 5, true)?;
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0u8; 6]);
+    let pm = bech32_polymod(&values) ^ 1;
+
+    let mut s = String::with_capacity(hrp.len() + 1 + data.len() + 6);
+    s.push_str(hrp);
+    s.push('1');
+    for d in data {
+        s.push(BECH32_CHARSET[d as usize] as char);
+    }
+    for i in 0..6 {
+        s.push(BECH32_CHARSET[((pm >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+    Ok(s)
+}
+
+/// (hrp, payload)
+pub fn from_bech32(s: &str) -> Result<(String, Vec<u8>)> {
+    if s.bytes().any(|c| c.is_ascii_uppercase()) && s.bytes().any(|c| c.is_ascii_lowercase()) {
+        bail!("mixed-case bech32 string");
+    }
+    let s = s.to_ascii_lowercase();
+    let sep = s.rfind('1').ok_or_else(|| anyhow!("bech32 separator missing"))?;
+    let (hrp, rest) = (&s[..sep], &s[sep + 1..]);
+    if hrp.is_empty() || rest.len() < 6 {
+        bail!("bech32 string too short");
+    }
+    let data = rest
+        .bytes()
+        .map(|c| BECH32_CHARSET.iter().position(|x| *x == c).map(|p| p as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| anyhow!("invalid bech32 character"))?;
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    if bech32_polymod(&values) != 1 {
+        bail!("bech32 checksum mismatch");
+    }
+    Ok((hrp.to_string(), convert_bits(&data[..data.len() - 6], 5, 8, false)?))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Hex0x,
+    Base64,
+    Bech32 { hrp: String },
+}
+
+impl Encoding {
+    pub fn encode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Encoding::Hex0x => Ok(hex0x(bytes)),
+            Encoding::Base64 => Ok(base64(bytes)),
+            Encoding::Bech32 { hrp } => bech32(hrp, bytes),
+        }
+    }
+
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>> {
+        match self {
+            Encoding::Hex0x => from_hex0x(s),
+            Encoding::Base64 => from_base64(s),
+            Encoding::Bech32 { hrp } => {
+                let (got, bytes) = from_bech32(s)?;
+                if got != *hrp {
+                    bail!("expected bech32 HRP {hrp:?}, got {got:?}");
+                }
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Interface {
+    Cli,
+    Http,
+    Export,
+}
+
+/// Encoding per interface; everything defaults to `0x` hex
+#[derive(Clone, Debug, Default)]
+pub struct EncodingRegistry {
+    overrides: BTreeMap<Interface, Encoding>,
+}
+
+impl EncodingRegistry {
+    pub fn with(mut self, iface: Interface, enc: Encoding) -> Self {
+        self.overrides.insert(iface, enc);
+        self
+    }
+
+    pub fn get(&self, iface: Interface) -> Encoding {
+        self.overrides.get(&iface).cloned().unwrap_or(Encoding::Hex0x)
+    }
+
+    pub fn encode(&self, iface: Interface, bytes: &[u8]) -> Result<String> {
+        self.get(iface).encode(bytes)
+    }
+
+    pub fn decode(&self, iface: Interface, s: &str) -> Result<Vec<u8>> {
+        self.get(iface).decode(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bech32_reference_vectors() -> Result<()> {
+        // BIP-173 valid strings
+        assert_eq!(from_bech32("A12UEL5L")?, ("a".to_string(), vec![]));
+        let s = "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw";
+        let (hrp, data) = from_bech32(s)?;
+        assert_eq!(data.len(), 20);
+        assert_eq!(bech32(&hrp, &data)?, s);
+        assert!(from_bech32("a12uel5m").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_round_trips() -> Result<()> {
+        let reg = EncodingRegistry::default()
+            .with(Interface::Http, Encoding::Base64)
+            .with(Interface::Export, Encoding::Bech32 { hrp: "frost".into() });
+        let key = [0x02u8; 33];
+        for iface in [Interface::Cli, Interface::Http, Interface::Export] {
+            let s = reg.encode(iface, &key)?;
+            assert_eq!(reg.decode(iface, &s)?, key);
+        }
+        assert!(reg.encode(Interface::Cli, &key)?.starts_with("0x"));
+        assert_eq!(from_bare_hex(&bare_hex(&key))?, key);
+        assert!(from_bare_hex(&hex0x(&key)).is_err());
+        assert_eq!(reg.encode(Interface::Http, b"hi")?, "aGk=");
+        // wrong HRP
+        let other = Encoding::Bech32 { hrp: "dvrf".into() }.encode(&key)?;
+        assert!(reg.decode(Interface::Export, &other).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+//! File exports for operators and contract tooling. Building the exported data is pure; only
+//! `export_verification_input` touches the filesystem.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use k256::ecdsa::{Signature, VerifyingKey};
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+
+use crate::encoding::hex0x;
+
+/// Input of an on-chain `ecrecover` check
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FrostVerificationInput {
+    pub message_hash: String,
+    pub signature: String,
+    pub expected_signer: String,
+}
+
+pub fn verification_input(sig: &Signature, vk: &VerifyingKey, msg: &[u8]) -> FrostVerificationInput {
+    let msg_hash = Keccak256::digest(msg);
+    let pub_bytes = vk.to_encoded_point(false);
+    let hash = Keccak256::digest(&pub_bytes.as_bytes()[1..]);
+    let eth_addr = &hash[12..];
+
+    FrostVerificationInput {
+        message_hash: hex0x(&msg_hash),
+        signature: hex0x(&sig.to_bytes()),
+        expected_signer: hex0x(eth_addr),
+    }
+}
+
+/// `verification_input` as pretty JSON at `path`
+pub fn export_verification_input(sig: &Signature, vk: &VerifyingKey, msg: &[u8], path: &Path) -> std::io::Result<()> {
+    let data = verification_input(sig, vk, msg);
+    let mut file = File::create(path)?;
+    file.write_all(serde_json::to_string_pretty(&data)?.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::{signature::Signer, SigningKey};
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::utils::keccak256;
+
+    #[test]
+    fn test_export_verification_input() -> std::io::Result<()> {
+        let sk = SigningKey::random(&mut OsRng);
+        let msg = b"frostlab/export";
+        let sig: Signature = sk.sign(msg);
+        let data = verification_input(&sig, sk.verifying_key(), msg);
+        assert_eq!(data.message_hash, hex0x(&keccak256(msg)));
+        assert_eq!(data.expected_signer.len(), 2 + 40);
+
+        let path = std::env::temp_dir().join(format!("frostlab-export-{}.json", std::process::id()));
+        export_verification_input(&sig, sk.verifying_key(), msg, &path)?;
+        let back: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(back["expected_signer"], data.expected_signer);
+        Ok(())
+    }
+}
@@ -1,12 +1,45 @@
+#[cfg(feature = "protocol")]
 use std::collections::BTreeMap;
 use anyhow::Result;
 use frost_secp256k1_evm as frost;
+#[cfg(feature = "protocol")]
 use frost::rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "protocol")]
 use frost::{round1, round2};
+#[cfg(feature = "protocol")]
 use crate::dkg::{DkgOutput, Identifier};
+use crate::messages::AttestationMessage;
 
 /// FROST signature
+#[cfg(feature = "protocol")]
 pub fn frost_sign<R: RngCore + CryptoRng>(
+    msg: &AttestationMessage,
+    out: &DkgOutput,
+    signer_ids: &[Identifier],
+    rng: &mut R,
+) -> Result<frost::Signature> {
+    sign_bytes(&msg.to_signing_bytes(), out, signer_ids, rng)
+}
+
+/// FROST signature over raw bytes, no attestation domain (reference-vector interop only)
+#[cfg(feature = "interop")]
+pub fn frost_sign_raw<R: RngCore + CryptoRng>(
+    msg: &[u8],
+    out: &DkgOutput,
+    signer_ids: &[Identifier],
+    rng: &mut R,
+) -> Result<frost::Signature> {
+    sign_bytes(msg, out, signer_ids, rng)
+}
+
+/// verify raw bytes, no attestation domain (reference-vector interop only)
+#[cfg(feature = "interop")]
+pub fn frost_verify_raw(msg: &[u8], sig: &frost::Signature, vk: &frost::VerifyingKey) -> Result<bool> {
+    Ok(vk.verify(msg, sig).is_ok())
+}
+
+#[cfg(feature = "protocol")]
+fn sign_bytes<R: RngCore + CryptoRng>(
     msg: &[u8],
     out: &DkgOutput,
     signer_ids: &[Identifier],
@@ -40,13 +73,18 @@ pub fn frost_sign<R: RngCore + CryptoRng>(
 }
 
 /// verify
-pub fn frost_verify(msg: &[u8], sig: &frost::Signature, out: &DkgOutput) -> Result<bool> {
-    let vk = out.public_key_package.verifying_key();
-    let ok = vk.verify(msg, sig).is_ok();
+#[cfg(feature = "protocol")]
+pub fn frost_verify(msg: &AttestationMessage, sig: &frost::Signature, out: &DkgOutput) -> Result<bool> {
+    frost_verify_with_key(msg, sig, out.public_key_package.verifying_key())
+}
+
+/// verify against a bare group key (no DKG output needed)
+pub fn frost_verify_with_key(msg: &AttestationMessage, sig: &frost::Signature, vk: &frost::VerifyingKey) -> Result<bool> {
+    let ok = vk.verify(&msg.to_signing_bytes(), sig).is_ok();
     Ok(ok)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "protocol"))]
 mod tests {
     use super::*;
     use frost_secp256k1_evm::rand_core::OsRng;
@@ -60,7 +98,7 @@ mod tests {
         let all_ids = out.all_ids();
         let signers = &all_ids[..cfg.min_signers as usize];
 
-        let msg = b"attestation";
+        let msg = &AttestationMessage::new(b"attestation".to_vec());
 
         // sign
         let sig = frost_sign(msg, &out, signers, &mut rng)?;
@@ -0,0 +1,188 @@
+//! Golden files for every encoding downstream code depends on: DLEQ proofs, quorum
+//! certificates, committee reports, randomness certificates, beacon inclusion proofs and the
+//! EVM export. Everything is derived from fixed seeds through `GoldenRng`, so regenerating on
+//! an unchanged tree reproduces the files byte for byte; `tests/golden.rs` compares them
+//! against `tests/golden/`.
+
+use anyhow::Result;
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use k256::ProjectivePoint;
+use serde_json::{json, Value};
+
+use crate::beacon::{beacon_input, BeaconRound};
+use crate::beacon_proof::{prove_round, ChainInfo};
+use crate::cert::QuorumCert;
+use crate::ddh_dvrf::run_ddh_dvrf_cert_with_rng;
+use crate::dkg::{run_dealerless_dkg, DkgOutput};
+use crate::encoding::hex0x;
+use crate::messages::DvrfInput;
+use crate::randomness_cert::{certify_randomness, round_transcript_hash};
+use crate::scenarios::{demo_config, evm_export_demo_with_rng, DEMO_INPUT};
+use crate::utils::{dvrf_base_point, hash_to_scalar_keccak, keccak256, point_bytes_compressed, prove_eq_with_nonce};
+use crate::version::ProtocolVersion;
+
+/// Beacon schedule of the golden chain
+pub const GOLDEN_GENESIS: u64 = 1_700_000_000;
+pub const GOLDEN_PERIOD: u64 = 30;
+
+/// keccak(seed || counter) stream. Deterministic by design: golden files only, never keys.
+pub struct GoldenRng {
+    seed: [u8; 32],
+    counter: u64,
+    buf: Vec<u8>,
+}
+
+impl GoldenRng {
+    pub fn new(label: &str) -> Self {
+        Self { seed: keccak256(label.as_bytes()), counter: 0, buf: Vec::new() }
+    }
+}
+
+impl RngCore for GoldenRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut b = [0u8; 4];
+        self.fill_bytes(&mut b);
+        u32::from_le_bytes(b)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut b = [0u8; 8];
+        self.fill_bytes(&mut b);
+        u64::from_le_bytes(b)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for d in dest {
+            if self.buf.is_empty() {
+                self.buf = keccak256(&[&self.seed[..], &self.counter.to_be_bytes()].concat()).to_vec();
+                self.counter += 1;
+            }
+            *d = self.buf.remove(0);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), frost::rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for GoldenRng {}
+
+fn point_hex(p: &ProjectivePoint) -> String {
+    hex0x(&point_bytes_compressed(p))
+}
+
+fn cert_json(cert: &QuorumCert) -> Value {
+    json!({
+        "version": cert.version.0,
+        "msg": hex0x(cert.msg.raw()),
+        "output": point_hex(&cert.output),
+        "partials": cert.partials.iter().map(|p| json!({
+            "version": p.version.0,
+            "identifier": hex0x(&p.id.serialize()),
+            "partial_eval": point_hex(&p.v_i),
+            "proof": hex0x(&p.proof.to_bytes()),
+        })).collect::<Vec<_>>(),
+        "randomness": hex0x(&cert.randomness()),
+        "transcript_hash": hex0x(&round_transcript_hash(cert)),
+    })
+}
+
+fn golden_dkg(rng: &mut GoldenRng) -> Result<DkgOutput> {
+    run_dealerless_dkg(demo_config(), rng)
+}
+
+/// One DLEQ proof from a fixed share and nonce
+fn dleq_proof() -> Result<Value> {
+    let sk = hash_to_scalar_keccak(b"frostlab/golden/share");
+    let r = hash_to_scalar_keccak(b"frostlab/golden/nonce");
+    let msg = DvrfInput::new(b"golden".to_vec());
+    let vk = ProjectivePoint::GENERATOR * sk;
+    let version = ProtocolVersion::CURRENT;
+    let (v, proof) = prove_eq_with_nonce(version, &msg, vk, sk, r)?;
+    Ok(json!({
+        "version": version.0,
+        "msg": hex0x(msg.raw()),
+        "base_point": point_hex(&dvrf_base_point(&msg)),
+        "verifying_share": point_hex(&vk),
+        "partial_eval": point_hex(&v),
+        "proof": hex0x(&proof.to_bytes()),
+    }))
+}
+
+fn quorum_cert_and_committee() -> Result<(Value, Value, Value)> {
+    let mut rng = GoldenRng::new("frostlab/golden/quorum-cert");
+    let out = golden_dkg(&mut rng)?;
+    let t = out.min_signers();
+    let signers = &out.all_ids()[..t as usize];
+    let cert = run_ddh_dvrf_cert_with_rng(&DvrfInput::new(DEMO_INPUT.to_vec()), &out.key_packages, &out.public_key_package, signers, &mut rng);
+    let rc = certify_randomness(&cert, &out, t, 1, 1, signers, &mut rng)?;
+    let randomness_cert = json!({
+        "version": rc.version.0,
+        "epoch": rc.epoch,
+        "round": rc.round,
+        "msg_hash": hex0x(&rc.msg_hash),
+        "transcript_hash": hex0x(&rc.transcript_hash),
+        "randomness": hex0x(&rc.randomness),
+        "signature": hex0x(&rc.signature.serialize()?),
+    });
+    Ok((cert_json(&cert), serde_json::to_value(out.committee_info()?.to_report())?, randomness_cert))
+}
+
+fn beacon() -> Result<Value> {
+    let mut rng = GoldenRng::new("frostlab/golden/beacon");
+    let out = golden_dkg(&mut rng)?;
+    let chain = ChainInfo::from_committee(&out.committee_info()?, GOLDEN_GENESIS, GOLDEN_PERIOD)?;
+    let signers = &out.all_ids()[..out.min_signers() as usize];
+    let mut link = chain.genesis_link();
+    let mut rounds = Vec::new();
+    for r in 1..=2u64 {
+        let cert = run_ddh_dvrf_cert_with_rng(&beacon_input(r), &out.key_packages, &out.public_key_package, signers, &mut rng);
+        let round = BeaconRound { round: r, cert };
+        let proof = prove_round(&chain, &round, link, chain.round_time(r).expect("scheduled"), &out, signers, &mut rng)?;
+        link = proof.link();
+        rounds.push(json!({
+            "round": r,
+            "input": hex0x(round.cert.msg.raw()),
+            "randomness": hex0x(&round.randomness()),
+            "inclusion_proof": hex0x(&proof.to_bytes()?),
+            "link": hex0x(&link),
+        }));
+    }
+    Ok(json!({
+        "group_id": hex0x(&chain.group_id),
+        "group_key": hex0x(&chain.group_key),
+        "min_signers": chain.min_signers,
+        "genesis_time": chain.genesis_time,
+        "period": chain.period,
+        "chain_hash": hex0x(&chain.hash()),
+        "rounds": rounds,
+    }))
+}
+
+fn evm_export() -> Result<Value> {
+    let e = evm_export_demo_with_rng(&mut GoldenRng::new("frostlab/golden/evm-export"))?;
+    Ok(json!({
+        "committee": serde_json::to_value(&e.committee)?,
+        "attestation_abi": e.attestation_abi,
+        "signature": e.signature,
+        "randomness": e.randomness,
+        "lagrange_abi": e.lagrange_abi,
+    }))
+}
+
+/// (file name, contents) of every golden file
+pub fn golden_files() -> Result<Vec<(&'static str, String)>> {
+    let (cert, committee, randomness_cert) = quorum_cert_and_committee()?;
+    let files = [
+        ("dleq_proof.json", dleq_proof()?),
+        ("quorum_cert.json", cert),
+        ("committee.json", committee),
+        ("randomness_certificate.json", randomness_cert),
+        ("beacon.json", beacon()?),
+        ("evm_export.json", evm_export()?),
+    ];
+    files.into_iter().map(|(name, v)| Ok((name, serde_json::to_string_pretty(&v)? + "\n"))).collect()
+}
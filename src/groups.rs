@@ -0,0 +1,238 @@
+//! Several independent committees in one process. Groups are keyed by their `group_id`
+//! (`CommitteeInfo::group_id`); every incoming session message names the group and the member
+//! it is for, and is handed to that member's `Participant` only.
+//!
+//! Per-group state is namespaced so groups never see each other's sessions: session ids in a
+//! shared `SessionStore` are rewritten to keccak(domain || group_id || session_id), file-backed
+//! key material lives under `<root>/<namespace>/`, and request counters are kept per group.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+
+use crate::committee::CommitteeInfo;
+use crate::coordinator::{Coordinator, SessionResult, SessionStore};
+use crate::dkg::{DkgOutput, Identifier, KeyPackage, PublicKeyPackage};
+use crate::encoding::bare_hex;
+use crate::participant::{Participant, ParticipantReply, ParticipantRequest, SessionId};
+use crate::utils::keccak256;
+use crate::version::Versioned;
+
+const SESSION_NAMESPACE_DOMAIN: &[u8] = b"frostlab/group-session/v1";
+
+pub type GroupId = [u8; 32];
+
+/// Directory / metric-label name of a group
+pub fn namespace(group_id: &GroupId) -> String {
+    format!("group-{}", bare_hex(group_id))
+}
+
+/// Session id of `session_id` inside `group_id`'s namespace
+pub fn namespaced_session_id(group_id: &GroupId, session_id: &SessionId) -> SessionId {
+    keccak256(&[SESSION_NAMESPACE_DOMAIN, &group_id[..], &session_id[..]].concat())
+}
+
+/// One group's view of a store shared by all groups
+pub struct NamespacedStore<S: SessionStore> {
+    group_id: GroupId,
+    inner: Arc<S>,
+}
+
+impl<S: SessionStore> NamespacedStore<S> {
+    pub fn new(group_id: GroupId, inner: Arc<S>) -> Self {
+        Self { group_id, inner }
+    }
+}
+
+impl<S: SessionStore> SessionStore for NamespacedStore<S> {
+    fn get(&self, id: &SessionId) -> Option<SessionResult> {
+        self.inner.get(&namespaced_session_id(&self.group_id, id))
+    }
+
+    fn put_if_absent(&self, id: SessionId, result: SessionResult) -> SessionResult {
+        self.inner.put_if_absent(namespaced_session_id(&self.group_id, &id), result)
+    }
+}
+
+/// A session message for member `to` of group `group_id`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupMessage {
+    pub group_id: GroupId,
+    pub to: Identifier,
+    pub request: Versioned<ParticipantRequest>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GroupMetrics {
+    pub requests: u64,
+    /// requests the participant refused or failed on
+    pub failures: u64,
+}
+
+struct Group {
+    committee: CommitteeInfo,
+    public_key_package: PublicKeyPackage,
+    members: BTreeMap<Identifier, Participant>,
+    metrics: GroupMetrics,
+}
+
+#[derive(Default)]
+pub struct GroupManager {
+    groups: BTreeMap<GroupId, Group>,
+}
+
+impl GroupManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Join a group with one share; adding a second share of the same group is allowed,
+    /// adding the same member twice is not
+    pub fn add_key_package(&mut self, key_package: KeyPackage, public_key_package: PublicKeyPackage) -> Result<GroupId> {
+        let committee = CommitteeInfo::from_public_key_package(&public_key_package, *key_package.min_signers())?;
+        let group_id = committee.group_id;
+        let id = *key_package.identifier();
+        let member = Participant::try_new(key_package, public_key_package.clone())?;
+        let group = self.groups.entry(group_id).or_insert_with(|| Group {
+            committee,
+            public_key_package,
+            members: BTreeMap::new(),
+            metrics: GroupMetrics::default(),
+        });
+        if group.members.contains_key(&id) {
+            bail!("member already loaded in {}", namespace(&group_id));
+        }
+        group.members.insert(id, member);
+        Ok(group_id)
+    }
+
+    /// Every share of a local DKG run
+    pub fn add_dkg_output(&mut self, out: &DkgOutput) -> Result<GroupId> {
+        let group_id = out.committee_info()?.group_id;
+        for kp in out.key_packages.values() {
+            self.add_key_package(kp.clone(), out.public_key_package.clone())?;
+        }
+        Ok(group_id)
+    }
+
+    /// Leave a group, dropping its shares and open sessions
+    pub fn remove_group(&mut self, group_id: &GroupId) -> Option<CommitteeInfo> {
+        self.groups.remove(group_id).map(|g| g.committee)
+    }
+
+    pub fn group_ids(&self) -> Vec<GroupId> {
+        self.groups.keys().copied().collect()
+    }
+
+    pub fn committee(&self, group_id: &GroupId) -> Option<&CommitteeInfo> {
+        self.groups.get(group_id).map(|g| &g.committee)
+    }
+
+    pub fn participant(&self, group_id: &GroupId, id: Identifier) -> Option<&Participant> {
+        self.groups.get(group_id)?.members.get(&id)
+    }
+
+    pub fn participant_mut(&mut self, group_id: &GroupId, id: Identifier) -> Option<&mut Participant> {
+        self.groups.get_mut(group_id)?.members.get_mut(&id)
+    }
+
+    /// Hand `msg` to the member it is addressed to
+    pub fn route<R: RngCore + CryptoRng>(&mut self, msg: GroupMessage, rng: &mut R) -> Result<Versioned<ParticipantReply>> {
+        let group = self.groups.get_mut(&msg.group_id).ok_or_else(|| anyhow!("unknown group {}", namespace(&msg.group_id)))?;
+        let Some(member) = group.members.get_mut(&msg.to) else {
+            bail!("no share for this member in {}", namespace(&msg.group_id));
+        };
+        group.metrics.requests += 1;
+        let reply = member.handle(msg.request, rng);
+        if reply.is_err() {
+            group.metrics.failures += 1;
+        }
+        reply
+    }
+
+    /// Coordinator for one group over a store shared by all groups
+    pub fn coordinator<S: SessionStore>(&self, group_id: &GroupId, store: Arc<S>) -> Result<Coordinator<NamespacedStore<S>>> {
+        let group = self.groups.get(group_id).ok_or_else(|| anyhow!("unknown group {}", namespace(group_id)))?;
+        let store = Arc::new(NamespacedStore::new(*group_id, store));
+        Ok(Coordinator::new(store, group.public_key_package.clone(), group.committee.min_signers))
+    }
+
+    /// Where a group's file-backed state (keystore, audit exports) goes under `root`
+    pub fn storage_dir(&self, root: &Path, group_id: &GroupId) -> Result<PathBuf> {
+        if !self.groups.contains_key(group_id) {
+            bail!("unknown group {}", namespace(group_id));
+        }
+        Ok(root.join(namespace(group_id)))
+    }
+
+    pub fn metrics(&self, group_id: &GroupId) -> Option<GroupMetrics> {
+        self.groups.get(group_id).map(|g| g.metrics)
+    }
+
+    /// Metrics of every group, labelled by namespace
+    pub fn metrics_report(&self) -> BTreeMap<String, GroupMetrics> {
+        self.groups.iter().map(|(id, g)| (namespace(id), g.metrics)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::coordinator::MemorySessionStore;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::messages::DvrfInput;
+    use crate::participant::participants_from_dkg;
+    use crate::version::ProtocolVersion;
+
+    #[test]
+    fn test_groups_are_isolated() -> Result<()> {
+        let a = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let b = run_dealerless_dkg(DkgConfig::new(4, 3)?, &mut OsRng)?;
+        let mut mgr = GroupManager::new();
+        let ga = mgr.add_dkg_output(&a)?;
+        let id_b = b.all_ids()[0];
+        let gb = mgr.add_key_package(b.key_packages[&id_b].clone(), b.public_key_package.clone())?;
+        assert_ne!(ga, gb);
+        assert_eq!(mgr.group_ids().len(), 2);
+        assert!(mgr.add_key_package(b.key_packages[&id_b].clone(), b.public_key_package.clone()).is_err());
+
+        // same session id in both groups, each answered with that group's share
+        let session_id = [9u8; 32];
+        let msg = DvrfInput::new(b"tenant".to_vec());
+        let req = Versioned::new(ProtocolVersion::CURRENT, ParticipantRequest::PartialEval { session_id, msg: msg.clone() });
+        let ra = mgr.route(GroupMessage { group_id: ga, to: a.all_ids()[0], request: req.clone() }, &mut OsRng)?;
+        let rb = mgr.route(GroupMessage { group_id: gb, to: id_b, request: req.clone() }, &mut OsRng)?;
+        assert_ne!(ra, rb);
+
+        // unknown group or member, or a conflicting request: not counted against another group
+        assert!(mgr.route(GroupMessage { group_id: [0; 32], to: id_b, request: req.clone() }, &mut OsRng).is_err());
+        assert!(mgr.route(GroupMessage { group_id: gb, to: b.all_ids()[1], request: req }, &mut OsRng).is_err());
+        let conflicting = ParticipantRequest::PartialEval { session_id, msg: DvrfInput::new(b"other".to_vec()) };
+        let conflicting = Versioned::new(ProtocolVersion::CURRENT, conflicting);
+        assert!(mgr.route(GroupMessage { group_id: ga, to: a.all_ids()[0], request: conflicting }, &mut OsRng).is_err());
+        assert_eq!(mgr.metrics(&ga), Some(GroupMetrics { requests: 2, failures: 1 }));
+        assert_eq!(mgr.metrics(&gb), Some(GroupMetrics { requests: 1, failures: 0 }));
+        assert_eq!(mgr.metrics_report().len(), 2);
+
+        // one backing store, no collisions between the groups' sessions
+        let store = Arc::new(MemorySessionStore::default());
+        let ca = mgr.coordinator(&ga, store.clone())?;
+        let cb = mgr.coordinator(&gb, store.clone())?;
+        let (mut pa, mut pb) = (participants_from_dkg(&a)?, participants_from_dkg(&b)?);
+        let cert_a = ca.run_dvrf(session_id, &msg, &mut pa, &a.all_ids()[..2])?;
+        let cert_b = cb.run_dvrf(session_id, &msg, &mut pb, &b.all_ids()[..3])?;
+        assert_ne!(cert_a.output, cert_b.output);
+        assert!(store.get(&session_id).is_none());
+        assert_eq!(store.get(&namespaced_session_id(&gb, &session_id)), Some(SessionResult::Dvrf(cert_b)));
+
+        assert_ne!(mgr.storage_dir(Path::new("/var/lib/frostlab"), &ga)?, mgr.storage_dir(Path::new("/var/lib/frostlab"), &gb)?);
+        assert!(mgr.remove_group(&ga).is_some());
+        assert!(mgr.storage_dir(Path::new("/var/lib/frostlab"), &ga).is_err());
+        Ok(())
+    }
+}
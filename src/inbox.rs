@@ -0,0 +1,149 @@
+//! Intake for round messages that may arrive twice, late or early. Messages are keyed by
+//! (session, sender, round):
+//!
+//! - the same payload again is a `Duplicate` and changes nothing
+//! - a different payload under a key already seen is an `Equivocation`: it is reported (once
+//!   per key) and the first payload is kept
+//! - a message for a round that is not active yet is `Buffered` and released when the session
+//!   advances to that round; a first message for a round already left behind is `Stale`
+//!
+//! Sessions nobody opened yet buffer everything.
+
+use std::collections::BTreeMap;
+
+use crate::audit::Hash32;
+use crate::dkg::Identifier;
+use crate::participant::SessionId;
+use crate::utils::keccak256;
+
+/// Round number within a session (DKG round, signing attempt, ...)
+pub type Round = u32;
+
+/// Two different payloads from one sender for one round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equivocation {
+    pub session_id: SessionId,
+    pub sender: Identifier,
+    pub round: Round,
+    /// keccak of the payload that was kept
+    pub first: Hash32,
+    pub second: Hash32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    Accepted,
+    Duplicate,
+    Buffered,
+    Stale,
+    Equivocation(Equivocation),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Inbox {
+    active: BTreeMap<SessionId, Round>,
+    messages: BTreeMap<(SessionId, Round, Identifier), Vec<u8>>,
+    equivocations: Vec<Equivocation>,
+}
+
+impl Inbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn active_round(&self, session_id: &SessionId) -> Option<Round> {
+        self.active.get(session_id).copied()
+    }
+
+    pub fn deliver(&mut self, session_id: SessionId, sender: Identifier, round: Round, payload: &[u8]) -> Delivery {
+        let key = (session_id, round, sender);
+        if let Some(prev) = self.messages.get(&key) {
+            if prev == payload {
+                return Delivery::Duplicate;
+            }
+            let e = Equivocation { session_id, sender, round, first: keccak256(prev), second: keccak256(payload) };
+            let known = self.equivocations.iter().any(|k| (k.session_id, k.round, k.sender) == key);
+            if !known {
+                self.equivocations.push(e.clone());
+            }
+            return Delivery::Equivocation(e);
+        }
+        let delivery = match self.active.get(&session_id) {
+            Some(active) if round < *active => return Delivery::Stale,
+            Some(active) if round == *active => Delivery::Accepted,
+            _ => Delivery::Buffered,
+        };
+        self.messages.insert(key, payload.to_vec());
+        delivery
+    }
+
+    /// Make `round` the active round of `session_id` (rounds only move forward) and return
+    /// what was buffered for it
+    pub fn advance(&mut self, session_id: SessionId, round: Round) -> BTreeMap<Identifier, Vec<u8>> {
+        let active = self.active.entry(session_id).or_insert(round);
+        *active = (*active).max(round);
+        self.received(&session_id, round)
+    }
+
+    /// Every message kept for `round`, by sender
+    pub fn received(&self, session_id: &SessionId, round: Round) -> BTreeMap<Identifier, Vec<u8>> {
+        self.messages
+            .iter()
+            .filter(|((s, r, _), _)| s == session_id && *r == round)
+            .map(|((_, _, sender), payload)| (*sender, payload.clone()))
+            .collect()
+    }
+
+    pub fn equivocations(&self) -> &[Equivocation] {
+        &self.equivocations
+    }
+
+    /// Drop a kept message that turned out to be invalid, so the sender's next one is accepted
+    pub fn retract(&mut self, session_id: &SessionId, sender: Identifier, round: Round) {
+        self.messages.remove(&(*session_id, round, sender));
+    }
+
+    /// Drop a finished session's messages; its equivocation reports stay
+    pub fn forget(&mut self, session_id: &SessionId) {
+        self.active.remove(session_id);
+        self.messages.retain(|(s, _, _), _| s != session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_buffer_equivocation() {
+        let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().unwrap()).collect();
+        let sid = [1u8; 32];
+        let mut inbox = Inbox::new();
+
+        // before the session opens everything waits
+        assert_eq!(inbox.deliver(sid, ids[0], 1, b"a1"), Delivery::Buffered);
+        assert_eq!(inbox.advance(sid, 1), BTreeMap::from([(ids[0], b"a1".to_vec())]));
+        assert_eq!(inbox.deliver(sid, ids[0], 1, b"a1"), Delivery::Duplicate);
+        assert_eq!(inbox.deliver(sid, ids[1], 1, b"b1"), Delivery::Accepted);
+
+        // round 2 arrives early
+        assert_eq!(inbox.deliver(sid, ids[2], 2, b"c2"), Delivery::Buffered);
+        assert!(inbox.received(&sid, 1).len() == 2 && !inbox.received(&sid, 1).contains_key(&ids[2]));
+        assert_eq!(inbox.advance(sid, 2).len(), 1);
+        assert_eq!(inbox.deliver(sid, ids[2], 1, b"c1"), Delivery::Stale);
+        assert_eq!(inbox.advance(sid, 1).len(), 2);
+        assert_eq!(inbox.active_round(&sid), Some(2));
+
+        let Delivery::Equivocation(e) = inbox.deliver(sid, ids[1], 1, b"b1'") else { panic!("expected equivocation") };
+        assert_eq!((e.sender, e.round, e.first), (ids[1], 1, keccak256(b"b1")));
+        assert_eq!(inbox.received(&sid, 1)[&ids[1]], b"b1".to_vec());
+        assert!(matches!(inbox.deliver(sid, ids[1], 1, b"b1''"), Delivery::Equivocation(_)));
+        assert_eq!(inbox.equivocations().len(), 1);
+        inbox.retract(&sid, ids[2], 2);
+        assert_eq!(inbox.deliver(sid, ids[2], 2, b"c2'"), Delivery::Accepted);
+
+        inbox.forget(&sid);
+        assert!(inbox.received(&sid, 2).is_empty());
+        assert_eq!(inbox.equivocations().len(), 1);
+    }
+}
@@ -0,0 +1,205 @@
+//! DDH-DVRF test vectors in the layout of the FROST reference vectors (`vectors.json`):
+//! same config/inputs/outputs structure, same group secret and polynomial, fixed nonces.
+//! The proofs are made under the protocol version named in the config.
+//! `generate_dvrf_vectors` produces them, `check_dvrf_vectors` recomputes every value.
+
+use anyhow::{bail, Result};
+use k256::{ProjectivePoint, Scalar};
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::{bare_hex, from_bare_hex};
+use crate::messages::DvrfInput;
+use crate::version::ProtocolVersion;
+use crate::utils::{
+    dvrf_base_point, hash_to_scalar_keccak, keccak256, lagrange_combine_points, point_bytes_compressed,
+    point_from_hex, proof_from_hex, prove_eq_with_nonce, scalar_from_hex, verify_eq,
+};
+
+/// group secret and polynomial of the FROST(secp256k1, KECCAK-256) reference vectors
+const GROUP_SECRET_KEY: &str = "0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114";
+const SHARE_COEFFICIENT: &str = "fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorConfig {
+    #[serde(rename = "MAX_PARTICIPANTS")]
+    pub max_participants: String,
+    #[serde(rename = "NUM_PARTICIPANTS")]
+    pub num_participants: String,
+    #[serde(rename = "MIN_PARTICIPANTS")]
+    pub min_participants: String,
+    pub name: String,
+    pub group: String,
+    pub hash: String,
+    #[serde(rename = "PROTOCOL_VERSION")]
+    pub protocol_version: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipantShare {
+    pub identifier: u16,
+    pub participant_share: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorInputs {
+    pub participant_list: Vec<u16>,
+    pub group_secret_key: String,
+    pub verifying_key_key: String,
+    pub message: String,
+    pub share_polynomial_coefficients: Vec<String>,
+    pub participant_shares: Vec<ParticipantShare>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialOutput {
+    pub identifier: u16,
+    pub verifying_share: String,
+    pub nonce: String,
+    pub partial_eval: String,
+    pub proof: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialOutputs {
+    pub outputs: Vec<PartialOutput>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalOutput {
+    pub base_point: String,
+    pub output: String,
+    pub randomness: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DvrfVectors {
+    pub config: VectorConfig,
+    pub inputs: VectorInputs,
+    pub partial_outputs: PartialOutputs,
+    pub final_output: FinalOutput,
+}
+
+fn scalar_hex(s: &Scalar) -> String {
+    bare_hex(&s.to_bytes())
+}
+
+fn point_hex(p: &ProjectivePoint) -> String {
+    bare_hex(&point_bytes_compressed(p))
+}
+
+/// fixed per-participant nonce for the vectors
+fn vector_nonce(id: u16) -> Scalar {
+    let mut buf = b"frostlab/dvrf-vector-nonce".to_vec();
+    buf.extend_from_slice(&id.to_be_bytes());
+    hash_to_scalar_keccak(&buf)
+}
+
+/// f(i) = a0 + a1·i + …
+fn eval_poly(coeffs: &[Scalar], x: u16) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::ZERO, |acc, c| acc * Scalar::from(x as u64) + c)
+}
+
+/// 2-of-3 vectors over participants {1, 3}, message "test"
+pub fn generate_dvrf_vectors() -> Result<DvrfVectors> {
+    let (max, min) = (3u16, 2u16);
+    let participant_list = vec![1u16, 3];
+    let msg = b"test".to_vec();
+    let input = DvrfInput::new(msg.clone());
+
+    let coeffs = [scalar_from_hex(GROUP_SECRET_KEY)?, scalar_from_hex(SHARE_COEFFICIENT)?];
+    let participant_shares = (1..=max)
+        .map(|i| ParticipantShare { identifier: i, participant_share: scalar_hex(&eval_poly(&coeffs, i)) })
+        .collect();
+
+    let mut outputs = Vec::new();
+    let mut points = Vec::new();
+    for &i in &participant_list {
+        let sk_i = eval_poly(&coeffs, i);
+        let vk_i = ProjectivePoint::GENERATOR * sk_i;
+        let r = vector_nonce(i);
+        let (v_i, proof) = prove_eq_with_nonce(ProtocolVersion::CURRENT, &input, vk_i, sk_i, r)?;
+        outputs.push(PartialOutput {
+            identifier: i,
+            verifying_share: point_hex(&vk_i),
+            nonce: scalar_hex(&r),
+            partial_eval: point_hex(&v_i),
+            proof: bare_hex(&proof.to_bytes()),
+        });
+        points.push((i as u64, *v_i));
+    }
+    let v = lagrange_combine_points(&points);
+
+    Ok(DvrfVectors {
+        config: VectorConfig {
+            max_participants: max.to_string(),
+            num_participants: participant_list.len().to_string(),
+            min_participants: min.to_string(),
+            name: "DDH-DVRF(secp256k1, KECCAK-256)".to_string(),
+            group: "secp256k1".to_string(),
+            hash: "KECCAK-256".to_string(),
+            protocol_version: ProtocolVersion::CURRENT.0.to_string(),
+        },
+        inputs: VectorInputs {
+            participant_list,
+            group_secret_key: GROUP_SECRET_KEY.to_string(),
+            verifying_key_key: point_hex(&(ProjectivePoint::GENERATOR * coeffs[0])),
+            message: bare_hex(&msg),
+            share_polynomial_coefficients: vec![SHARE_COEFFICIENT.to_string()],
+            participant_shares,
+        },
+        partial_outputs: PartialOutputs { outputs },
+        final_output: FinalOutput {
+            base_point: point_hex(&dvrf_base_point(&input)),
+            output: point_hex(&v),
+            randomness: bare_hex(&keccak256(&point_bytes_compressed(&v))),
+        },
+    })
+}
+
+/// Recompute every value in `v` with this crate and compare
+pub fn check_dvrf_vectors(v: &DvrfVectors) -> Result<()> {
+    let version = ProtocolVersion(v.config.protocol_version.parse()?);
+    let input = DvrfInput::new(from_bare_hex(&v.inputs.message)?);
+    let mut coeffs = vec![scalar_from_hex(&v.inputs.group_secret_key)?];
+    for c in &v.inputs.share_polynomial_coefficients {
+        coeffs.push(scalar_from_hex(c)?);
+    }
+    if point_hex(&(ProjectivePoint::GENERATOR * coeffs[0])) != v.inputs.verifying_key_key {
+        bail!("group verifying key mismatch");
+    }
+    for s in &v.inputs.participant_shares {
+        if scalar_hex(&eval_poly(&coeffs, s.identifier)) != s.participant_share {
+            bail!("share mismatch for participant {}", s.identifier);
+        }
+    }
+
+    let mut points = Vec::new();
+    for o in &v.partial_outputs.outputs {
+        let sk_i = eval_poly(&coeffs, o.identifier);
+        let vk_i = point_from_hex(&o.verifying_share)?;
+        if *vk_i != ProjectivePoint::GENERATOR * sk_i {
+            bail!("verifying share mismatch for participant {}", o.identifier);
+        }
+        let (v_i, proof) = prove_eq_with_nonce(version, &input, *vk_i, sk_i, scalar_from_hex(&o.nonce)?)?;
+        if point_hex(&v_i) != o.partial_eval || bare_hex(&proof.to_bytes()) != o.proof {
+            bail!("partial eval / proof mismatch for participant {}", o.identifier);
+        }
+        let parsed = proof_from_hex(&o.proof)?;
+        if !verify_eq(version, &input, &vk_i, &v_i, &parsed) {
+            bail!("proof of participant {} does not verify", o.identifier);
+        }
+        points.push((o.identifier as u64, *v_i));
+    }
+
+    let out = lagrange_combine_points(&points);
+    if point_hex(&dvrf_base_point(&input)) != v.final_output.base_point {
+        bail!("base point mismatch");
+    }
+    if point_hex(&out) != v.final_output.output || out != dvrf_base_point(&input) * coeffs[0] {
+        bail!("combined output mismatch");
+    }
+    if bare_hex(&keccak256(&point_bytes_compressed(&out))) != v.final_output.randomness {
+        bail!("randomness mismatch");
+    }
+    Ok(())
+}
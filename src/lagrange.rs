@@ -0,0 +1,174 @@
+//! Lagrange coefficients λ_i (at x = 0) for a signer set, for aggregators outside this crate:
+//! a contract or service holding raw partials (i, v_i) computes v = Σ λ_i·v_i itself.
+//!
+//! Wire form: abi.encode(uint256[] identifiers, uint256[] coefficients), identifiers in
+//! ascending order; the keccak of that encoding is the set's digest.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, Result};
+use k256::{ProjectivePoint, Scalar};
+
+use crate::ddh_dvrf::Identifier;
+use crate::utils::{keccak256, scalar_from_canonical_bytes};
+#[cfg(feature = "protocol")]
+use anyhow::anyhow;
+#[cfg(feature = "protocol")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "protocol")]
+use crate::encoding::{from_hex0x, hex0x};
+
+fn id_scalar(id: &Identifier) -> Result<Scalar> {
+    scalar_from_canonical_bytes(&id.serialize())
+}
+
+/// λ_i = Π_{j≠i} x_j / (x_j − x_i) for every signer; the set must be non-empty and distinct
+pub fn lagrange_coefficients(signers: &[Identifier]) -> Result<BTreeMap<Identifier, Scalar>> {
+    if signers.is_empty() {
+        bail!("empty signer set");
+    }
+    let mut xs = BTreeMap::new();
+    for id in signers {
+        if xs.insert(*id, id_scalar(id)?).is_some() {
+            bail!("duplicate signer in set");
+        }
+    }
+    let mut out = BTreeMap::new();
+    for (id, x_i) in &xs {
+        let (mut num, mut den) = (Scalar::ONE, Scalar::ONE);
+        for (other, x_j) in &xs {
+            if other != id {
+                num *= x_j;
+                den *= *x_j - x_i;
+            }
+        }
+        out.insert(*id, num * den.invert().expect("distinct identifiers"));
+    }
+    Ok(out)
+}
+
+/// Σ λ_i·v_i over exactly the signers the coefficients were computed for
+pub fn combine_with_coefficients(
+    coefficients: &BTreeMap<Identifier, Scalar>,
+    partials: &[(Identifier, ProjectivePoint)],
+) -> Result<ProjectivePoint> {
+    if partials.len() != coefficients.len() {
+        bail!("expected {} partials, got {}", coefficients.len(), partials.len());
+    }
+    let mut acc = ProjectivePoint::IDENTITY;
+    let mut seen = BTreeSet::new();
+    for (id, v_i) in partials {
+        let Some(l) = coefficients.get(id) else { bail!("partial from a signer outside the set") };
+        if !seen.insert(*id) {
+            bail!("duplicate partial");
+        }
+        acc += *v_i * l;
+    }
+    Ok(acc)
+}
+
+/// abi.encode(uint256[] identifiers, uint256[] coefficients)
+pub fn coefficients_abi(coefficients: &BTreeMap<Identifier, Scalar>) -> Vec<u8> {
+    let n = coefficients.len();
+    let word = |x: usize| -> [u8; 32] {
+        let mut w = [0u8; 32];
+        w[24..].copy_from_slice(&(x as u64).to_be_bytes());
+        w
+    };
+    let mut out = Vec::with_capacity(32 * (4 + 2 * n));
+    out.extend_from_slice(&word(0x40));
+    out.extend_from_slice(&word(0x40 + 32 * (n + 1)));
+    out.extend_from_slice(&word(n));
+    for id in coefficients.keys() {
+        out.extend_from_slice(&id.serialize());
+    }
+    out.extend_from_slice(&word(n));
+    for l in coefficients.values() {
+        out.extend_from_slice(&l.to_bytes());
+    }
+    out
+}
+
+pub fn coefficients_digest(coefficients: &BTreeMap<Identifier, Scalar>) -> [u8; 32] {
+    keccak256(&coefficients_abi(coefficients))
+}
+
+/// JSON form handed to external aggregators
+#[cfg(feature = "protocol")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoefficientSet {
+    /// 0x-hex identifiers, ascending
+    pub signers: Vec<String>,
+    /// 0x-hex λ_i, same order
+    pub coefficients: Vec<String>,
+    /// 0x-hex keccak of the ABI encoding
+    pub digest: String,
+}
+
+#[cfg(feature = "protocol")]
+impl CoefficientSet {
+    pub fn new(signers: &[Identifier]) -> Result<Self> {
+        let c = lagrange_coefficients(signers)?;
+        Ok(Self {
+            signers: c.keys().map(|id| hex0x(&id.serialize())).collect(),
+            coefficients: c.values().map(|l| hex0x(&l.to_bytes())).collect(),
+            digest: hex0x(&coefficients_digest(&c)),
+        })
+    }
+
+    /// Parse and check that the coefficients are the ones for this signer set
+    pub fn to_map(&self) -> Result<BTreeMap<Identifier, Scalar>> {
+        let ids = self
+            .signers
+            .iter()
+            .map(|s| Identifier::deserialize(&from_hex0x(s)?).map_err(|e| anyhow!("bad identifier: {e}")))
+            .collect::<Result<Vec<_>>>()?;
+        let expected = lagrange_coefficients(&ids)?;
+        let given = self
+            .coefficients
+            .iter()
+            .map(|s| scalar_from_canonical_bytes(&from_hex0x(s)?))
+            .collect::<Result<Vec<_>>>()?;
+        if given.len() != ids.len() || expected.values().ne(given.iter()) {
+            bail!("coefficients do not match the signer set");
+        }
+        if from_hex0x(&self.digest)? != coefficients_digest(&expected) {
+            bail!("coefficient set digest mismatch");
+        }
+        Ok(expected)
+    }
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use crate::ddh_dvrf::id_as_u64;
+    use crate::utils::lagrange_combine_points;
+
+    #[test]
+    fn test_coefficients_recombine_and_round_trip() -> Result<()> {
+        let ids: Vec<Identifier> = [5u16, 1, 3].iter().map(|i| Identifier::try_from(*i).unwrap()).collect();
+        let c = lagrange_coefficients(&ids)?;
+        // f(x) = 7 + 2x: Σ λ_i f(x_i) = f(0)
+        let f = |i: u64| Scalar::from(7 + 2 * i);
+        let partials: Vec<(Identifier, ProjectivePoint)> =
+            ids.iter().map(|id| (*id, ProjectivePoint::GENERATOR * f(id_as_u64(*id)))).collect();
+        let v = combine_with_coefficients(&c, &partials)?;
+        assert_eq!(v, ProjectivePoint::GENERATOR * Scalar::from(7u64));
+        let pts: Vec<(u64, ProjectivePoint)> = partials.iter().map(|(id, p)| (id_as_u64(*id), *p)).collect();
+        assert_eq!(v, lagrange_combine_points(&pts));
+        assert!(combine_with_coefficients(&c, &partials[..2]).is_err());
+        assert!(lagrange_coefficients(&[ids[0], ids[0]]).is_err());
+
+        let set = CoefficientSet::new(&ids)?;
+        let json = serde_json::to_string(&set)?;
+        let back: CoefficientSet = serde_json::from_str(&json)?;
+        assert_eq!(back.to_map()?, c);
+        assert_eq!(coefficients_abi(&c).len(), 32 * 10);
+
+        let mut tampered = set.clone();
+        tampered.coefficients.swap(0, 1);
+        assert!(tampered.to_map().is_err());
+        Ok(())
+    }
+}
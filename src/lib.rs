@@ -1,4 +1,90 @@
-pub mod dkg;
+//! Three layers, each a feature that pulls in the one below:
+//!
+//! - `core`: math and proofs only. With `default-features = false, features = ["core"]` the
+//!   public verification path is compiled: `utils::verify_eq`, `cert::{verify_combined,
+//!   QuorumCert}`, `frost_ext::frost_verify_with_key`, beacon and randomness certificates.
+//!   This is what contract tooling and light clients depend on.
+//! - `protocol`: DKG, DVRF and FROST sessions: participants, the coordinator, ceremonies.
+//! - `service`: what a node runs around the protocol: rate-limited request handling
+//!   (`service`), several committees per process (`groups`), file exports (`export`) and the
+//!   demo binary.
+//!
+//! `verify` is the old name of `core`; `prover` (the default) is everything.
+
+pub mod messages;
+pub mod version;
+pub mod transcript;
 pub mod utils;
+pub mod validated;
+pub mod ddh_dvrf;
+pub mod cert;
+pub mod vdf;
 pub mod frost_ext;
-pub mod ddh_dvrf;
\ No newline at end of file
+pub mod commit_reveal;
+pub mod beacon;
+pub mod beacon_proof;
+pub mod threshold_decrypt;
+pub mod mempool;
+pub mod combiner;
+pub mod round_attestation;
+pub mod lagrange;
+pub mod randomness_cert;
+pub mod committee;
+
+#[cfg(feature = "protocol")]
+pub mod dkg;
+#[cfg(feature = "protocol")]
+pub mod dkg_party;
+#[cfg(feature = "protocol")]
+pub mod inbox;
+#[cfg(feature = "protocol")]
+pub mod possession;
+#[cfg(feature = "protocol")]
+pub mod simulate;
+#[cfg(feature = "protocol")]
+pub mod audit;
+#[cfg(feature = "protocol")]
+pub mod participant;
+#[cfg(feature = "protocol")]
+pub mod coordinator;
+#[cfg(feature = "protocol")]
+pub mod ceremony;
+#[cfg(feature = "interop")]
+pub mod interop;
+#[cfg(feature = "interop")]
+pub mod golden;
+#[cfg(feature = "protocol")]
+pub mod cache;
+#[cfg(feature = "protocol")]
+pub mod liveness;
+#[cfg(feature = "protocol")]
+pub mod broadcast;
+#[cfg(feature = "protocol")]
+pub mod onchain_dkg;
+#[cfg(feature = "protocol")]
+pub mod encoding;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "protocol")]
+pub mod participation;
+#[cfg(feature = "protocol")]
+pub mod replay;
+#[cfg(feature = "protocol")]
+pub mod subshare;
+#[cfg(feature = "protocol")]
+pub mod selection;
+#[cfg(feature = "secure-memory")]
+pub mod secure_memory;
+#[cfg(feature = "protocol")]
+pub mod scenarios;
+#[cfg(feature = "bls")]
+pub mod bls;
+#[cfg(feature = "blind")]
+pub mod blind;
+
+#[cfg(feature = "service")]
+pub mod service;
+#[cfg(feature = "service")]
+pub mod groups;
+#[cfg(feature = "service")]
+pub mod export;
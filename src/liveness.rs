@@ -0,0 +1,146 @@
+//! Liveness check: the coordinator sends a fresh nonce, each participant answers with a
+//! Schnorr proof of knowledge of sk_i bound to (nonce, id, vk_i). The proof shows the share
+//! is loaded and usable without producing any DVRF output or signature share.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::dkg::{Identifier, PublicKeyPackage};
+use crate::ddh_dvrf::{id_as_u64, validated_vk_share};
+use crate::transcript::{KeccakTranscript, Transcript};
+
+const LIVENESS_PROTOCOL: &[u8] = b"frostlab/liveness/v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LivenessProof {
+    pub id: Identifier,
+    pub commitment: ProjectivePoint,
+    pub response: Scalar,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LivenessStatus {
+    Alive,
+    InvalidProof,
+    Unreachable,
+}
+
+/// Outcome of one liveness sweep over the whole committee
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LivenessReport {
+    pub nonce: [u8; 32],
+    pub threshold: u16,
+    pub statuses: BTreeMap<Identifier, LivenessStatus>,
+}
+
+fn challenge(nonce: &[u8; 32], id: Identifier, vk_i: &ProjectivePoint, commitment: &ProjectivePoint) -> Scalar {
+    let mut t = KeccakTranscript::new(LIVENESS_PROTOCOL);
+    t.append_message(b"nonce", nonce);
+    t.append_message(b"id", &id.serialize());
+    t.append_point(b"vk", vk_i);
+    t.append_point(b"R", commitment);
+    t.challenge_scalar(b"ch")
+}
+
+/// Participant side: R = w·G, z = w + c·sk_i
+pub fn prove_possession<R: RngCore + CryptoRng>(
+    nonce: &[u8; 32],
+    id: Identifier,
+    sk_i: Scalar,
+    vk_i: &ProjectivePoint,
+    rng: &mut R,
+) -> LivenessProof {
+    let w = Scalar::random(rng);
+    let commitment = ProjectivePoint::GENERATOR * w;
+    let c = challenge(nonce, id, vk_i, &commitment);
+    LivenessProof { id, commitment, response: w + c * sk_i }
+}
+
+pub fn verify_possession(nonce: &[u8; 32], public_key_package: &PublicKeyPackage, proof: &LivenessProof) -> Result<()> {
+    let vk_i = validated_vk_share(public_key_package, proof.id)?;
+    let c = challenge(nonce, proof.id, &vk_i, &proof.commitment);
+    if ProjectivePoint::GENERATOR * proof.response != proof.commitment + *vk_i * c {
+        bail!("invalid liveness proof from id={}", id_as_u64(proof.id));
+    }
+    Ok(())
+}
+
+/// Every committee member without a valid answer to `nonce` is marked down
+pub fn aggregate_liveness(
+    nonce: [u8; 32],
+    public_key_package: &PublicKeyPackage,
+    threshold: u16,
+    responses: &[LivenessProof],
+) -> LivenessReport {
+    let mut statuses: BTreeMap<_, _> = public_key_package
+        .verifying_shares()
+        .keys()
+        .map(|id| (*id, LivenessStatus::Unreachable))
+        .collect();
+    for r in responses {
+        if let Some(st) = statuses.get_mut(&r.id) {
+            let ok = verify_possession(&nonce, public_key_package, r).is_ok();
+            // one valid answer is enough, a later bad duplicate does not undo it
+            if ok {
+                *st = LivenessStatus::Alive;
+            } else if *st != LivenessStatus::Alive {
+                *st = LivenessStatus::InvalidProof;
+            }
+        }
+    }
+    LivenessReport { nonce, threshold, statuses }
+}
+
+impl LivenessReport {
+    pub fn alive(&self) -> Vec<Identifier> {
+        self.statuses.iter().filter(|(_, s)| **s == LivenessStatus::Alive).map(|(id, _)| *id).collect()
+    }
+
+    /// At least t members answered correctly
+    pub fn quorum_reachable(&self) -> bool {
+        self.alive().len() >= self.threshold as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use std::sync::Arc;
+    use crate::coordinator::{Coordinator, MemorySessionStore};
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::participant::participants_from_dkg;
+
+    #[test]
+    fn test_liveness_report() -> Result<()> {
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let nonce = [7u8; 32];
+
+        let mut responses: Vec<_> = ids[..3].iter().map(|id| parts[id].liveness(&nonce, &mut OsRng)).collect();
+        // replaying an answer to an older nonce does not count
+        responses.push(parts[&ids[3]].liveness(&[0u8; 32], &mut OsRng));
+
+        let report = aggregate_liveness(nonce, &out.public_key_package, cfg.min_signers, &responses);
+        assert_eq!(report.alive(), ids[..3].to_vec());
+        assert_eq!(report.statuses[&ids[3]], LivenessStatus::InvalidProof);
+        assert_eq!(report.statuses[&ids[4]], LivenessStatus::Unreachable);
+        assert!(report.quorum_reachable());
+
+        let report = aggregate_liveness(nonce, &out.public_key_package, cfg.min_signers, &responses[..2]);
+        assert!(!report.quorum_reachable());
+
+        // coordinator sweep with three nodes offline
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let mut online = parts;
+        assert!(coord.check_liveness(&online, &mut OsRng).quorum_reachable());
+        online.retain(|id, _| *id == ids[0] || *id == ids[1]);
+        assert!(!coord.check_liveness(&online, &mut OsRng).quorum_reachable());
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Encrypted-mempool flow: transactions are encrypted to a future beacon round and the
+//! committee only releases decryption shares once that round has been produced.
+//!
+//! The round gate is a policy of honest participants (`release_decryption_share` refuses
+//! before the round exists), so it holds as long as fewer than t participants collude;
+//! the beacon value itself is not a decryption key.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+
+use crate::beacon::BeaconRound;
+use crate::ddh_dvrf::PublicKeyPackage;
+use crate::threshold_decrypt::{combine_decryption_shares, encrypt, Ciphertext, DecryptionShare};
+#[cfg(feature = "protocol")]
+use crate::{ddh_dvrf::KeyPackage, threshold_decrypt::decryption_share};
+
+const ROUND_AAD_LABEL: &[u8] = b"frostlab/mempool/round";
+
+/// A ciphertext that opens no earlier than beacon round `round`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundCiphertext {
+    pub round: u64,
+    pub ct: Ciphertext,
+}
+
+/// The target round is authenticated, so it cannot be moved to an earlier round
+fn round_aad(round: u64) -> Vec<u8> {
+    let mut aad = ROUND_AAD_LABEL.to_vec();
+    aad.extend_from_slice(&round.to_be_bytes());
+    aad
+}
+
+pub fn encrypt_to_round<R: RngCore + CryptoRng>(
+    group_key: &frost::VerifyingKey,
+    round: u64,
+    plaintext: &[u8],
+    rng: &mut R,
+) -> Result<RoundCiphertext> {
+    Ok(RoundCiphertext { round, ct: encrypt(group_key, &round_aad(round), plaintext, rng)? })
+}
+
+fn check_round(beacon_round: &BeaconRound, public_key_package: &PublicKeyPackage, threshold: u16, ciphertext: &RoundCiphertext) -> Result<()> {
+    beacon_round.verify(public_key_package, threshold)?;
+    if beacon_round.round < ciphertext.round {
+        bail!("beacon round {} has not reached target round {}", beacon_round.round, ciphertext.round);
+    }
+    Ok(())
+}
+
+/// Participant side: release d_i only against a valid beacon round at or past the target
+#[cfg(feature = "protocol")]
+pub fn release_decryption_share(
+    beacon_round: &BeaconRound,
+    ciphertext: &RoundCiphertext,
+    key_package: &KeyPackage,
+    public_key_package: &PublicKeyPackage,
+    threshold: u16,
+) -> Result<DecryptionShare> {
+    check_round(beacon_round, public_key_package, threshold, ciphertext)?;
+    decryption_share(&ciphertext.ct, &round_aad(ciphertext.round), key_package, public_key_package)
+}
+
+/// Open `ciphertext` with the shares released for `beacon_round`
+pub fn decrypt_with_round(
+    beacon_round: &BeaconRound,
+    ciphertext: &RoundCiphertext,
+    shares: &[DecryptionShare],
+    public_key_package: &PublicKeyPackage,
+    threshold: u16,
+) -> Result<Vec<u8>> {
+    check_round(beacon_round, public_key_package, threshold, ciphertext)?;
+    combine_decryption_shares(&ciphertext.ct, &round_aad(ciphertext.round), public_key_package, shares, threshold)
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::beacon::produce_round;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_encrypt_to_round() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let (pk, t) = (&out.public_key_package, cfg.min_signers);
+        let ids = out.all_ids();
+        let tx = encrypt_to_round(pk.verifying_key(), 10, b"tx: buy 5", &mut OsRng)?;
+
+        // round 9 exists, round 10 does not yet: nobody releases a share
+        let r9 = produce_round(9, &out.key_packages, pk, &ids[..3]);
+        assert!(release_decryption_share(&r9, &tx, &out.key_packages[&ids[0]], pk, t).is_err());
+
+        let r10 = produce_round(10, &out.key_packages, pk, &ids[..3]);
+        let shares = ids[1..].iter()
+            .map(|id| release_decryption_share(&r10, &tx, &out.key_packages[id], pk, t))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(decrypt_with_round(&r10, &tx, &shares, pk, t)?, b"tx: buy 5");
+
+        // moving the ciphertext to an earlier round breaks authentication
+        let moved = RoundCiphertext { round: 9, ct: tx.ct.clone() };
+        assert!(release_decryption_share(&r9, &moved, &out.key_packages[&ids[0]], pk, t).is_err());
+        Ok(())
+    }
+}
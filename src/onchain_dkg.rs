@@ -0,0 +1,697 @@
+//! DKG coordinated through a registry contract: round-1 commitments and encrypted round-2
+//! shares are posted as transactions, and every party rebuilds the packages it needs from
+//! the contract's event logs, so the whole ceremony is public and replayable.
+//!
+//! - `postCommitment(bytes32 dkgId, bytes32 sender, bytes commitment, bytes proofOfKnowledge)`
+//!   → `CommitmentPosted(bytes32 indexed dkgId, bytes32 sender, bytes commitment, bytes proofOfKnowledge)`;
+//!   commitment = the t compressed coefficient commitments, proof = R (33) || μ (32)
+//! - `postShare(bytes32 dkgId, bytes32 sender, bytes32 recipient, bytes ciphertext)`
+//!   → `SharePosted(bytes32 indexed dkgId, bytes32 sender, bytes32 recipient, bytes ciphertext)`;
+//!   ciphertext = E (33) || share ⊕ keystream (32) || tag (32), hashed ElGamal to the
+//!   recipient's encryption key, bound to (dkgId, sender, recipient)
+//! - `postComplaint(bytes32 dkgId, bytes32 sender, bytes32 recipient, bytes complaint)`
+//!   → `ComplaintPosted(bytes32 indexed dkgId, bytes32 sender, bytes32 recipient, bytes complaint)`;
+//!   complaint = uint16 version || S (33) || DLEQ proof (64), S = sk·E the recipient's ECDH
+//!   point, proven against its encryption key; just the version if E itself is not a point
+//!
+//! A recipient cannot blame a sender on its word alone: with S anyone reruns the decryption
+//! of the posted ciphertext (`verify_share_complaint`), so a complaint about a share that
+//! opens fine is exposed as false.
+//!
+//! Calldata and logs are public input. A post is attributed to the member whose registered
+//! account signed the transaction, never to the sender word it carries: posts from other
+//! accounts are ignored, and a member's malformed, forged or equivocating post is reported
+//! against that member without spoiling everyone else's.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::keys::dkg::{round1, round2};
+use frost::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::broadcast::{abi_decode_words_bytes, abi_encode_words_bytes, id_word, member_for_account, selector, ContractTransport, EventLog};
+use crate::dkg::{DkgConfig, DkgOutput, Identifier, KeyPackage};
+use crate::encoding::hex0x;
+use crate::possession::{prove_possession, verify_possessions};
+use crate::utils::{keccak256, point_bytes_compressed, point_from_compressed_bytes, prove_dleq_with_rng, verify_dleq, Proof};
+use crate::validated::ValidatedPoint;
+use crate::version::{ProtocolVersion, VersionRange};
+
+pub const POST_COMMITMENT_SIGNATURE: &str = "postCommitment(bytes32,bytes32,bytes,bytes)";
+pub const COMMITMENT_EVENT_SIGNATURE: &str = "CommitmentPosted(bytes32,bytes32,bytes,bytes)";
+pub const POST_SHARE_SIGNATURE: &str = "postShare(bytes32,bytes32,bytes32,bytes)";
+pub const SHARE_EVENT_SIGNATURE: &str = "SharePosted(bytes32,bytes32,bytes32,bytes)";
+pub const POST_COMPLAINT_SIGNATURE: &str = "postComplaint(bytes32,bytes32,bytes32,bytes)";
+pub const COMPLAINT_EVENT_SIGNATURE: &str = "ComplaintPosted(bytes32,bytes32,bytes32,bytes)";
+
+const SHARE_KDF_LABEL: &[u8] = b"frostlab/onchain-dkg/kdf/v1";
+const SHARE_MAC_LABEL: &[u8] = b"frostlab/onchain-dkg/mac/v1";
+pub const SHARE_CIPHERTEXT_LEN: usize = 33 + 32 + 32;
+
+/// (compressed coefficient commitments, proof of knowledge) of a round-1 package
+pub fn encode_round1(pkg: &round1::Package) -> Result<(Vec<u8>, Vec<u8>)> {
+    let commitment = pkg.commitment().serialize()?.concat();
+    Ok((commitment, pkg.proof_of_knowledge().serialize()?))
+}
+
+pub fn decode_round1(commitment: &[u8], proof_of_knowledge: &[u8]) -> Result<round1::Package> {
+    if commitment.is_empty() || commitment.len() % 33 != 0 {
+        bail!("commitment must be a non-empty list of 33-byte points");
+    }
+    let coefficients: Vec<Vec<u8>> = commitment.chunks(33).map(|c| c.to_vec()).collect();
+    Ok(round1::Package::new(
+        frost::keys::VerifiableSecretSharingCommitment::deserialize(coefficients)?,
+        frost::Signature::deserialize(proof_of_knowledge)?,
+    ))
+}
+
+pub fn commitment_calldata(dkg_id: &[u8; 32], sender: Identifier, pkg: &round1::Package) -> Result<Vec<u8>> {
+    let (commitment, pok) = encode_round1(pkg)?;
+    let mut out = selector(POST_COMMITMENT_SIGNATURE).to_vec();
+    out.extend_from_slice(&abi_encode_words_bytes(&[*dkg_id, id_word(sender)], &[&commitment, &pok]));
+    Ok(out)
+}
+
+pub fn share_calldata(dkg_id: &[u8; 32], sender: Identifier, recipient: Identifier, ciphertext: &[u8]) -> Vec<u8> {
+    let mut out = selector(POST_SHARE_SIGNATURE).to_vec();
+    out.extend_from_slice(&abi_encode_words_bytes(&[*dkg_id, id_word(sender), id_word(recipient)], &[ciphertext]));
+    out
+}
+
+pub fn complaint_calldata(dkg_id: &[u8; 32], complaint: &ShareComplaint) -> Vec<u8> {
+    let mut out = selector(POST_COMPLAINT_SIGNATURE).to_vec();
+    let words = [*dkg_id, id_word(complaint.sender), id_word(complaint.recipient)];
+    out.extend_from_slice(&abi_encode_words_bytes(&words, &[&complaint.to_bytes()]));
+    out
+}
+
+/// A decoded registry transaction, e.g. for an indexer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryCall {
+    Commitment { dkg_id: [u8; 32], sender: Identifier, commitment: Vec<u8>, proof_of_knowledge: Vec<u8> },
+    Share { dkg_id: [u8; 32], sender: Identifier, recipient: Identifier, ciphertext: Vec<u8> },
+    Complaint { dkg_id: [u8; 32], sender: Identifier, recipient: Identifier, complaint: Vec<u8> },
+}
+
+impl RegistryCall {
+    pub fn decode(calldata: &[u8]) -> Result<Self> {
+        let sel = calldata.get(..4).unwrap_or_default();
+        if sel == selector(POST_COMMITMENT_SIGNATURE) {
+            let (words, mut bytes) = abi_decode_words_bytes(&calldata[4..], 2, 2)?;
+            let proof_of_knowledge = bytes.pop().expect("two bytes args");
+            let commitment = bytes.pop().expect("two bytes args");
+            Ok(RegistryCall::Commitment { dkg_id: words[0], sender: Identifier::deserialize(&words[1])?, commitment, proof_of_knowledge })
+        } else if sel == selector(POST_SHARE_SIGNATURE) {
+            let (words, mut bytes) = abi_decode_words_bytes(&calldata[4..], 3, 1)?;
+            Ok(RegistryCall::Share {
+                dkg_id: words[0],
+                sender: Identifier::deserialize(&words[1])?,
+                recipient: Identifier::deserialize(&words[2])?,
+                ciphertext: bytes.remove(0),
+            })
+        } else if sel == selector(POST_COMPLAINT_SIGNATURE) {
+            let (words, mut bytes) = abi_decode_words_bytes(&calldata[4..], 3, 1)?;
+            Ok(RegistryCall::Complaint {
+                dkg_id: words[0],
+                sender: Identifier::deserialize(&words[1])?,
+                recipient: Identifier::deserialize(&words[2])?,
+                complaint: bytes.remove(0),
+            })
+        } else {
+            bail!("not a registry call");
+        }
+    }
+
+    /// Topics and data of the event the registry emits for this call
+    pub fn event(&self) -> ([[u8; 32]; 2], Vec<u8>) {
+        match self {
+            RegistryCall::Commitment { dkg_id, sender, commitment, proof_of_knowledge } => (
+                [keccak256(COMMITMENT_EVENT_SIGNATURE.as_bytes()), *dkg_id],
+                abi_encode_words_bytes(&[id_word(*sender)], &[commitment, proof_of_knowledge]),
+            ),
+            RegistryCall::Share { dkg_id, sender, recipient, ciphertext } => (
+                [keccak256(SHARE_EVENT_SIGNATURE.as_bytes()), *dkg_id],
+                abi_encode_words_bytes(&[id_word(*sender), id_word(*recipient)], &[ciphertext]),
+            ),
+            RegistryCall::Complaint { dkg_id, sender, recipient, complaint } => (
+                [keccak256(COMPLAINT_EVENT_SIGNATURE.as_bytes()), *dkg_id],
+                abi_encode_words_bytes(&[id_word(*sender), id_word(*recipient)], &[complaint]),
+            ),
+        }
+    }
+}
+
+/// A ceremony member's key for receiving round-2 shares
+#[derive(Clone)]
+pub struct ShareEncryptionKey {
+    secret: Scalar,
+    pub public: ProjectivePoint,
+}
+
+impl ShareEncryptionKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let secret = Scalar::random(&mut *rng);
+        Self { secret, public: ProjectivePoint::GENERATOR * secret }
+    }
+}
+
+fn share_context(dkg_id: &[u8; 32], sender: Identifier, recipient: Identifier) -> Vec<u8> {
+    let mut ctx = dkg_id.to_vec();
+    ctx.extend_from_slice(&id_word(sender));
+    ctx.extend_from_slice(&id_word(recipient));
+    ctx
+}
+
+fn share_pad(s: &ProjectivePoint, e: &ProjectivePoint, ctx: &[u8]) -> [u8; 32] {
+    let mut buf = SHARE_KDF_LABEL.to_vec();
+    buf.extend_from_slice(&point_bytes_compressed(s));
+    buf.extend_from_slice(&point_bytes_compressed(e));
+    buf.extend_from_slice(ctx);
+    keccak256(&buf)
+}
+
+fn share_tag(s: &ProjectivePoint, ctx: &[u8], body: &[u8]) -> [u8; 32] {
+    let mut buf = SHARE_MAC_LABEL.to_vec();
+    buf.extend_from_slice(&point_bytes_compressed(s));
+    buf.extend_from_slice(ctx);
+    buf.extend_from_slice(body);
+    keccak256(&buf)
+}
+
+pub fn encrypt_share<R: RngCore + CryptoRng>(
+    dkg_id: &[u8; 32],
+    sender: Identifier,
+    recipient: Identifier,
+    recipient_key: &ProjectivePoint,
+    pkg: &round2::Package,
+    rng: &mut R,
+) -> Vec<u8> {
+    let ctx = share_context(dkg_id, sender, recipient);
+    let k = Scalar::random(&mut *rng);
+    let e = ProjectivePoint::GENERATOR * k;
+    let s = *recipient_key * k;
+    let body: Vec<u8> = pkg.signing_share().serialize().iter().zip(share_pad(&s, &e, &ctx)).map(|(a, b)| a ^ b).collect();
+    let mut out = point_bytes_compressed(&e).to_vec();
+    out.extend_from_slice(&body);
+    out.extend_from_slice(&share_tag(&s, &ctx, &body));
+    out
+}
+
+/// E of a ciphertext of the right length
+fn ciphertext_point(ciphertext: &[u8]) -> Result<ValidatedPoint> {
+    if ciphertext.len() != SHARE_CIPHERTEXT_LEN {
+        bail!("share ciphertext must be {} bytes, got {}", SHARE_CIPHERTEXT_LEN, ciphertext.len());
+    }
+    ValidatedPoint::new(point_from_compressed_bytes(&ciphertext[..33])?)
+}
+
+/// Open a ciphertext given its ECDH point S
+fn open_share(ctx: &[u8], e: &ProjectivePoint, s: &ProjectivePoint, ciphertext: &[u8]) -> Result<round2::Package> {
+    let (body, tag) = ciphertext[33..].split_at(32);
+    if share_tag(s, ctx, body) != tag {
+        bail!("share ciphertext authentication failed");
+    }
+    let share: Vec<u8> = body.iter().zip(share_pad(s, e, ctx)).map(|(a, b)| a ^ b).collect();
+    Ok(round2::Package::new(frost::keys::SigningShare::deserialize(&share)?))
+}
+
+pub fn decrypt_share(
+    dkg_id: &[u8; 32],
+    sender: Identifier,
+    recipient: Identifier,
+    key: &ShareEncryptionKey,
+    ciphertext: &[u8],
+) -> Result<round2::Package> {
+    let e = ciphertext_point(ciphertext)?;
+    open_share(&share_context(dkg_id, sender, recipient), &e, &(*e * key.secret), ciphertext)
+}
+
+/// A recipient's public evidence that `sender`'s ciphertext does not open: S = sk·E with a
+/// DLEQ proof against the recipient's encryption key, or no disclosure when E is not a point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShareComplaint {
+    pub version: ProtocolVersion,
+    pub sender: Identifier,
+    pub recipient: Identifier,
+    pub disclosure: Option<(ValidatedPoint, Proof)>,
+}
+
+impl ShareComplaint {
+    /// uint16 version || S (33) || proof (64), or just the version
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.version.0.to_be_bytes().to_vec();
+        if let Some((s, proof)) = &self.disclosure {
+            out.extend_from_slice(&point_bytes_compressed(s));
+            out.extend_from_slice(&proof.to_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(sender: Identifier, recipient: Identifier, bytes: &[u8]) -> Result<Self> {
+        let version = match bytes.get(..2) {
+            Some(v) => ProtocolVersion(u16::from_be_bytes([v[0], v[1]])),
+            None => bail!("complaint too short"),
+        };
+        let disclosure = match bytes.len() {
+            2 => None,
+            99 => Some((ValidatedPoint::new(point_from_compressed_bytes(&bytes[2..35])?)?, Proof::from_bytes(&bytes[35..])?)),
+            n => bail!("complaint must be 2 or 99 bytes, got {}", n),
+        };
+        Ok(Self { version, sender, recipient, disclosure })
+    }
+}
+
+/// Complaint about `sender`'s ciphertext to `recipient`; refused if the share opens fine
+pub fn share_complaint<R: RngCore + CryptoRng>(
+    dkg_id: &[u8; 32],
+    sender: Identifier,
+    recipient: Identifier,
+    key: &ShareEncryptionKey,
+    ciphertext: &[u8],
+    rng: &mut R,
+) -> Result<ShareComplaint> {
+    if decrypt_share(dkg_id, sender, recipient, key, ciphertext).is_ok() {
+        bail!("share decrypts; nothing to complain about");
+    }
+    let version = ProtocolVersion::CURRENT;
+    let disclosure = match ciphertext_point(ciphertext) {
+        Ok(e) => Some(prove_dleq_with_rng(version, &e, key.public, key.secret, rng)?),
+        Err(_) => None,
+    };
+    Ok(ShareComplaint { version, sender, recipient, disclosure })
+}
+
+/// Ok if the complaint holds, i.e. the sender is at fault: `ciphertext` is malformed, or the
+/// proven S does not open it. `recipient_key` is the complainer's registered encryption key.
+pub fn verify_share_complaint(dkg_id: &[u8; 32], complaint: &ShareComplaint, recipient_key: &ProjectivePoint, ciphertext: &[u8]) -> Result<()> {
+    let Ok(e) = ciphertext_point(ciphertext) else { return Ok(()) };
+    let Some((s, proof)) = &complaint.disclosure else { bail!("complaint discloses no shared secret") };
+    if !VersionRange::supported().contains(complaint.version) {
+        bail!("complaint under unsupported protocol version {}", complaint.version.0);
+    }
+    if !verify_dleq(complaint.version, &e, &ValidatedPoint::new(*recipient_key)?, s, proof) {
+        bail!("invalid DLEQ proof of the shared secret");
+    }
+    if open_share(&share_context(dkg_id, complaint.sender, complaint.recipient), &e, s, ciphertext).is_ok() {
+        bail!("share opens under the disclosed secret; false complaint");
+    }
+    Ok(())
+}
+
+/// Why a member's posts were thrown out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryFault {
+    /// posted two different payloads for the same slot
+    Equivocation,
+    /// post that does not decode or claims another sender
+    Malformed(String),
+    /// round-2 share that does not decrypt, with the recipient's complaint to post
+    UndecryptableShare(ShareComplaint),
+}
+
+/// What a read recovered: the usable posts, and the members whose posts were not
+#[derive(Clone, Debug)]
+pub struct RegistryRead<P> {
+    pub packages: BTreeMap<Identifier, P>,
+    pub faults: BTreeMap<Identifier, RegistryFault>,
+}
+
+impl<P> RegistryRead<P> {
+    /// The packages, or an error naming every faulty member
+    pub fn into_packages(self) -> Result<BTreeMap<Identifier, P>> {
+        if !self.faults.is_empty() {
+            let faulty: Vec<String> = self.faults.iter().map(|(id, f)| format!("{} ({:?})", hex0x(&id.serialize()), f)).collect();
+            bail!("faulty registry posts from {}", faulty.join(", "));
+        }
+        Ok(self.packages)
+    }
+}
+
+/// Member-attributed payloads of the logs on one topic, before decoding
+fn attribute_logs(
+    members: &BTreeMap<Identifier, [u8; 20]>,
+    logs: Vec<EventLog>,
+    mut decode: impl FnMut(Identifier, &[u8]) -> Result<Option<Vec<u8>>>,
+) -> RegistryRead<Vec<u8>> {
+    let mut read = RegistryRead { packages: BTreeMap::new(), faults: BTreeMap::new() };
+    for log in logs {
+        let Some(member) = member_for_account(members, &log.from) else { continue };
+        if read.faults.contains_key(&member) {
+            continue;
+        }
+        let payload = match decode(member, &log.data) {
+            Ok(Some(payload)) => payload,
+            Ok(None) => continue,
+            Err(e) => {
+                read.packages.remove(&member);
+                read.faults.insert(member, RegistryFault::Malformed(e.to_string()));
+                continue;
+            }
+        };
+        match read.packages.get(&member) {
+            Some(prev) if *prev != payload => {
+                read.packages.remove(&member);
+                read.faults.insert(member, RegistryFault::Equivocation);
+            }
+            Some(_) => {}
+            None => {
+                read.packages.insert(member, payload);
+            }
+        }
+    }
+    read
+}
+
+/// Reads one ceremony (`dkg_id`) back from the registry's logs; `members` maps every member
+/// to the account it posts from
+pub struct OnchainDkgReader<'a, T: ContractTransport> {
+    pub address: [u8; 20],
+    pub dkg_id: [u8; 32],
+    members: &'a BTreeMap<Identifier, [u8; 20]>,
+    transport: &'a T,
+}
+
+impl<'a, T: ContractTransport> OnchainDkgReader<'a, T> {
+    pub fn new(address: [u8; 20], dkg_id: [u8; 32], members: &'a BTreeMap<Identifier, [u8; 20]>, transport: &'a T) -> Self {
+        Self { address, dkg_id, members, transport }
+    }
+
+    /// Every member's round-1 package; errors only if the logs cannot be read
+    pub fn round1_packages(&self) -> Result<RegistryRead<round1::Package>> {
+        let topics = [keccak256(COMMITMENT_EVENT_SIGNATURE.as_bytes()), self.dkg_id];
+        let raw = attribute_logs(self.members, self.transport.logs(self.address, &topics)?, |member, data| {
+            let (words, bytes) = abi_decode_words_bytes(data, 1, 2)?;
+            if words[0] != id_word(member) {
+                bail!("posted as another sender");
+            }
+            decode_round1(&bytes[0], &bytes[1])?;
+            Ok(Some([&bytes[0][..], &bytes[1][..]].concat()))
+        });
+        let packages = raw.packages.into_iter()
+            .map(|(id, payload)| {
+                let split = payload.len() - 65;
+                (id, decode_round1(&payload[..split], &payload[split..]).expect("decoded above"))
+            })
+            .collect();
+        Ok(RegistryRead { packages, faults: raw.faults })
+    }
+
+    /// Round-2 ciphertexts addressed to `recipient`, by sender, undecrypted
+    fn round2_ciphertexts_for(&self, recipient: Identifier) -> Result<RegistryRead<Vec<u8>>> {
+        let topics = [keccak256(SHARE_EVENT_SIGNATURE.as_bytes()), self.dkg_id];
+        Ok(attribute_logs(self.members, self.transport.logs(self.address, &topics)?, |member, data| {
+            let (words, mut bytes) = abi_decode_words_bytes(data, 2, 1)?;
+            if words[0] != id_word(member) {
+                bail!("posted as another sender");
+            }
+            Ok((words[1] == id_word(recipient)).then(|| bytes.remove(0)))
+        }))
+    }
+
+    /// The round-2 packages addressed to `recipient`, decrypted, by sender. A share that fails
+    /// to decrypt is reported against its sender with a complaint anyone can check once posted.
+    pub fn round2_packages_for<R: RngCore + CryptoRng>(
+        &self,
+        recipient: Identifier,
+        key: &ShareEncryptionKey,
+        rng: &mut R,
+    ) -> Result<RegistryRead<round2::Package>> {
+        let raw = self.round2_ciphertexts_for(recipient)?;
+        let mut read = RegistryRead { packages: BTreeMap::new(), faults: raw.faults };
+        for (sender, ct) in raw.packages {
+            match decrypt_share(&self.dkg_id, sender, recipient, key, &ct) {
+                Ok(pkg) => {
+                    read.packages.insert(sender, pkg);
+                }
+                Err(_) => {
+                    let complaint = share_complaint(&self.dkg_id, sender, recipient, key, &ct, rng)?;
+                    read.faults.insert(sender, RegistryFault::UndecryptableShare(complaint));
+                }
+            }
+        }
+        Ok(read)
+    }
+
+    /// Complaints posted by their recipients' accounts; posts naming another recipient or
+    /// that do not decode are skipped
+    pub fn complaints(&self) -> Result<Vec<ShareComplaint>> {
+        let topics = [keccak256(COMPLAINT_EVENT_SIGNATURE.as_bytes()), self.dkg_id];
+        let mut out = Vec::new();
+        for log in self.transport.logs(self.address, &topics)? {
+            let Some(member) = member_for_account(self.members, &log.from) else { continue };
+            let Ok((words, bytes)) = abi_decode_words_bytes(&log.data, 2, 1) else { continue };
+            if words[1] != id_word(member) {
+                continue;
+            }
+            let Ok(sender) = Identifier::deserialize(&words[0]) else { continue };
+            if let Ok(c) = ShareComplaint::from_bytes(sender, member, &bytes[0]) {
+                out.push(c);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Ok if `complaint` holds against the ciphertext its sender posted; `recipient_key` is the
+    /// complainer's registered encryption key. A sender that posted nothing or equivocated is
+    /// at fault anyway.
+    pub fn check_complaint(&self, complaint: &ShareComplaint, recipient_key: &ProjectivePoint) -> Result<()> {
+        let read = self.round2_ciphertexts_for(complaint.recipient)?;
+        match read.packages.get(&complaint.sender) {
+            Some(ct) => verify_share_complaint(&self.dkg_id, complaint, recipient_key, ct),
+            None => Ok(()),
+        }
+    }
+
+    /// keccak(id || serialized round-1 package) in identifier order, as `DkgOutput::transcript_hash`.
+    /// Fails if any member's round-1 post is faulty.
+    pub fn transcript_hash(&self) -> Result<[u8; 32]> {
+        let mut transcript = Vec::new();
+        for (id, pkg) in self.round1_packages()?.into_packages()? {
+            transcript.extend_from_slice(&id.serialize());
+            transcript.extend_from_slice(&pkg.serialize()?);
+        }
+        Ok(keccak256(&transcript))
+    }
+}
+
+/// Local run of a whole ceremony through the registry: every party posts its commitment,
+/// reads all commitments from the logs, posts encrypted shares, and finishes from the shares
+/// addressed to it. `members` holds one account per party, parties 1..=max_signers.
+/// Encryption keys are generated here; a deployment registers them first.
+pub fn run_onchain_dkg<T: ContractTransport, R: RngCore + CryptoRng>(
+    cfg: DkgConfig,
+    address: [u8; 20],
+    dkg_id: [u8; 32],
+    members: &BTreeMap<Identifier, [u8; 20]>,
+    transport: &mut T,
+    rng: &mut R,
+) -> Result<DkgOutput> {
+    let (n, t) = (cfg.max_signers, cfg.min_signers);
+    let ids: Vec<Identifier> = (1..=n).map(|i| i.try_into().expect("nonzero id")).collect();
+    if members.len() != ids.len() || !ids.iter().all(|id| members.contains_key(id)) {
+        bail!("members must be parties 1..={}", n);
+    }
+    let enc_keys: BTreeMap<Identifier, ShareEncryptionKey> = ids.iter().map(|id| (*id, ShareEncryptionKey::generate(rng))).collect();
+
+    let mut round1_secret = BTreeMap::new();
+    for id in &ids {
+        let (secret, pkg) = frost::keys::dkg::part1(*id, n, t, &mut *rng)?;
+        transport.send_transaction(members[id], address, commitment_calldata(&dkg_id, *id, &pkg)?)?;
+        round1_secret.insert(*id, secret);
+    }
+    let round1 = OnchainDkgReader::new(address, dkg_id, members, &*transport).round1_packages()?.into_packages()?;
+    if round1.len() != ids.len() || !ids.iter().all(|id| round1.contains_key(id)) {
+        bail!("registry holds {} commitments, expected {}", round1.len(), n);
+    }
+    let others = |me: &Identifier| -> BTreeMap<Identifier, round1::Package> {
+        round1.iter().filter(|(id, _)| *id != me).map(|(id, p)| (*id, p.clone())).collect()
+    };
+
+    let mut round2_secret = BTreeMap::new();
+    for id in &ids {
+        let secret = round1_secret.remove(id).expect("round-1 secret");
+        let (secret, packages) = frost::keys::dkg::part2(secret, &others(id))?;
+        for (recipient, pkg) in packages {
+            let ct = encrypt_share(&dkg_id, *id, recipient, &enc_keys[&recipient].public, &pkg, rng);
+            transport.send_transaction(members[id], address, share_calldata(&dkg_id, *id, recipient, &ct))?;
+        }
+        round2_secret.insert(*id, secret);
+    }
+
+    let reader = OnchainDkgReader::new(address, dkg_id, members, &*transport);
+    let mut key_packages = BTreeMap::<Identifier, KeyPackage>::new();
+    let mut public_key_package = None;
+    for id in &ids {
+        let received = reader.round2_packages_for(*id, &enc_keys[id], &mut *rng)?.into_packages()?;
+        let (kp, pkpkg) = frost::keys::dkg::part3(&round2_secret[id], &others(id), &received)?;
+        key_packages.insert(*id, kp);
+        public_key_package.get_or_insert(pkpkg);
+    }
+    let public_key_package = public_key_package.expect("at least two parties");
+    let transcript_hash = reader.transcript_hash()?;
+    let possession_proofs = key_packages.iter().map(|(id, kp)| (*id, prove_possession(&transcript_hash, kp, &mut *rng))).collect();
+    verify_possessions(&transcript_hash, &public_key_package, &possession_proofs)?;
+    Ok(DkgOutput { key_packages, public_key_package, transcript_hash, possession_proofs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::frost_ext::{frost_sign, frost_verify};
+    use crate::messages::AttestationMessage;
+
+    /// Stand-in registry: every call emits its event
+    #[derive(Default)]
+    struct MockRegistry {
+        logs: Vec<([u8; 20], [[u8; 32]; 2], EventLog)>,
+    }
+
+    impl ContractTransport for MockRegistry {
+        fn send_transaction(&mut self, from: [u8; 20], to: [u8; 20], calldata: Vec<u8>) -> Result<()> {
+            let (topics, data) = RegistryCall::decode(&calldata)?.event();
+            self.logs.push((to, topics, EventLog { from, data }));
+            Ok(())
+        }
+
+        fn logs(&self, address: [u8; 20], topics: &[[u8; 32]]) -> Result<Vec<EventLog>> {
+            Ok(self.logs.iter().filter(|(a, t, _)| *a == address && t[..] == *topics).map(|(_, _, l)| l.clone()).collect())
+        }
+    }
+
+    fn accounts(n: u16) -> BTreeMap<Identifier, [u8; 20]> {
+        (1..=n).map(|i| (i.try_into().unwrap(), [i as u8; 20])).collect()
+    }
+
+    #[test]
+    fn test_dkg_through_registry_logs() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let members = accounts(4);
+        let mut chain = MockRegistry::default();
+        let out = run_onchain_dkg(cfg, [0x11; 20], [0x22; 32], &members, &mut chain, &mut OsRng)?;
+        // 4 commitments + 4·3 shares
+        assert_eq!(chain.logs.len(), 16);
+
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let sig = frost_sign(&msg, &out, &out.all_ids()[1..], &mut OsRng)?;
+        assert!(frost_verify(&msg, &sig, &out)?);
+
+        // another ceremony on the same registry does not mix in
+        let other = run_onchain_dkg(cfg, [0x11; 20], [0x33; 32], &members, &mut chain, &mut OsRng)?;
+        assert_ne!(other.transcript_hash, out.transcript_hash);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_posts_blamed_on_their_signer() -> Result<()> {
+        let mut rng = OsRng;
+        let members = accounts(3);
+        let ids: Vec<Identifier> = members.keys().copied().collect();
+        let (address, dkg_id) = ([0x11; 20], [0x22; 32]);
+        let mut chain = MockRegistry::default();
+        for id in &ids {
+            let (_, pkg) = frost::keys::dkg::part1(*id, 3, 2, &mut rng)?;
+            chain.send_transaction(members[id], address, commitment_calldata(&dkg_id, *id, &pkg)?)?;
+        }
+
+        // junk from an outsider under a member's id, and from a non-member id, is ignored
+        let (_, junk) = frost::keys::dkg::part1(ids[1], 3, 2, &mut rng)?;
+        chain.send_transaction([0xee; 20], address, commitment_calldata(&dkg_id, ids[1], &junk)?)?;
+        chain.send_transaction([0xee; 20], address, commitment_calldata(&dkg_id, 9u16.try_into()?, &junk)?)?;
+        let reader = OnchainDkgReader::new(address, dkg_id, &members, &chain);
+        let read = reader.round1_packages()?;
+        assert!(read.faults.is_empty());
+        assert_eq!(read.packages.len(), 3);
+
+        // a member posting under another id only spoils its own slot
+        chain.send_transaction(members[&ids[2]], address, commitment_calldata(&dkg_id, ids[1], &junk)?)?;
+        let reader = OnchainDkgReader::new(address, dkg_id, &members, &chain);
+        let read = reader.round1_packages()?;
+        assert!(matches!(read.faults[&ids[2]], RegistryFault::Malformed(_)));
+        assert_eq!(read.packages.keys().copied().collect::<Vec<_>>(), ids[..2]);
+        assert!(reader.transcript_hash().is_err());
+
+        // a share that does not decrypt is blamed on the member that signed it
+        let key = ShareEncryptionKey::generate(&mut rng);
+        chain.send_transaction(members[&ids[0]], address, share_calldata(&dkg_id, ids[0], ids[1], &[0u8; SHARE_CIPHERTEXT_LEN]))?;
+        let reader = OnchainDkgReader::new(address, dkg_id, &members, &chain);
+        let read = reader.round2_packages_for(ids[1], &key, &mut rng)?;
+        assert_eq!(read.faults.keys().copied().collect::<Vec<_>>(), vec![ids[0]]);
+        let RegistryFault::UndecryptableShare(complaint) = read.faults[&ids[0]] else { panic!("expected a complaint") };
+        reader.check_complaint(&complaint, &key.public)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_share_complaints_are_publicly_checkable() -> Result<()> {
+        let mut rng = OsRng;
+        let members = accounts(3);
+        let ids: Vec<Identifier> = members.keys().copied().collect();
+        let (address, dkg_id) = ([0x11; 20], [0x22; 32]);
+        let key = ShareEncryptionKey::generate(&mut rng);
+        let pkg = round2::Package::new(frost::keys::SigningShare::deserialize(&[7u8; 32])?);
+        let mut chain = MockRegistry::default();
+
+        // ids[0] posts a share whose tag is garbled: E is a point, so the complaint discloses S
+        let mut bad = encrypt_share(&dkg_id, ids[0], ids[1], &key.public, &pkg, &mut rng);
+        bad[SHARE_CIPHERTEXT_LEN - 1] ^= 1;
+        chain.send_transaction(members[&ids[0]], address, share_calldata(&dkg_id, ids[0], ids[1], &bad))?;
+        // ids[2] posts an honest one
+        let good = encrypt_share(&dkg_id, ids[2], ids[1], &key.public, &pkg, &mut rng);
+        chain.send_transaction(members[&ids[2]], address, share_calldata(&dkg_id, ids[2], ids[1], &good))?;
+
+        let reader = OnchainDkgReader::new(address, dkg_id, &members, &chain);
+        let read = reader.round2_packages_for(ids[1], &key, &mut rng)?;
+        let RegistryFault::UndecryptableShare(complaint) = read.faults[&ids[0]] else { panic!("expected a complaint") };
+        assert!(complaint.disclosure.is_some());
+        assert_eq!(ShareComplaint::from_bytes(ids[0], ids[1], &complaint.to_bytes())?, complaint);
+
+        // posted by the recipient's account, anyone can uphold it
+        chain.send_transaction(members[&ids[1]], address, complaint_calldata(&dkg_id, &complaint))?;
+        // a complaint posted by someone else's account is not the recipient's
+        chain.send_transaction(members[&ids[2]], address, complaint_calldata(&dkg_id, &complaint))?;
+        let reader = OnchainDkgReader::new(address, dkg_id, &members, &chain);
+        assert_eq!(reader.complaints()?, vec![complaint]);
+        reader.check_complaint(&complaint, &key.public)?;
+
+        // the honest sender cannot be blamed: no complaint is made, and a forged one fails
+        assert!(share_complaint(&dkg_id, ids[2], ids[1], &key, &good, &mut rng).is_err());
+        let e = ciphertext_point(&good)?;
+        let liar = Scalar::random(&mut rng);
+        let forged = ShareComplaint {
+            version: ProtocolVersion::CURRENT,
+            sender: ids[2],
+            recipient: ids[1],
+            disclosure: Some(prove_dleq_with_rng(ProtocolVersion::CURRENT, &e, ProjectivePoint::GENERATOR * liar, liar, &mut rng)?),
+        };
+        assert!(reader.check_complaint(&forged, &key.public).is_err());
+        assert!(reader.check_complaint(&ShareComplaint { disclosure: None, ..forged }, &key.public).is_err());
+        // the true S opens the share: a proven disclosure cannot frame it either
+        let honest = ShareComplaint { disclosure: Some(prove_dleq_with_rng(ProtocolVersion::CURRENT, &e, key.public, key.secret, &mut rng)?), ..forged };
+        assert!(reader.check_complaint(&honest, &key.public).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_share_ciphertext_bound_to_recipient() -> Result<()> {
+        let mut rng = OsRng;
+        let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().unwrap()).collect();
+        let key = ShareEncryptionKey::generate(&mut rng);
+        let pkg = round2::Package::new(frost::keys::SigningShare::deserialize(&[7u8; 32])?);
+        let ct = encrypt_share(&[1; 32], ids[0], ids[1], &key.public, &pkg, &mut rng);
+        assert_eq!(ct.len(), SHARE_CIPHERTEXT_LEN);
+        assert_eq!(decrypt_share(&[1; 32], ids[0], ids[1], &key, &ct)?, pkg);
+        // replayed to another recipient or ceremony
+        assert!(decrypt_share(&[1; 32], ids[0], ids[2], &key, &ct).is_err());
+        assert!(decrypt_share(&[2; 32], ids[0], ids[1], &key, &ct).is_err());
+
+        let call = share_calldata(&[1; 32], ids[0], ids[1], &ct);
+        assert_eq!(
+            RegistryCall::decode(&call)?,
+            RegistryCall::Share { dkg_id: [1; 32], sender: ids[0], recipient: ids[1], ciphertext: ct }
+        );
+        Ok(())
+    }
+}
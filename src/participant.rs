@@ -0,0 +1,585 @@
+//! Participant-side share holder: produces DVRF partial evals and FROST signature shares,
+//! recording every use of the secret share in an audit log.
+//!
+//! Responses are deduplicated by session id: several coordinators asking for the same session
+//! get the same answer, and a conflicting request under a known session id is refused.
+//!
+//! Each session is bound to the protocol version it was opened under (`open_session`, or
+//! `ProtocolVersion::OLDEST` for the unversioned calls); `handle` is the versioned wire entry point.
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, OsRng, RngCore};
+use frost::{round1, round2};
+use k256::ProjectivePoint;
+
+use crate::audit::{AuditLog, Hash32, ShareUse};
+use crate::cert::PartialEval;
+use crate::ddh_dvrf::{scalar_from_keypackage, vk_share_from_public_pkg};
+use crate::dkg::{DkgOutput, Identifier, KeyPackage, PublicKeyPackage};
+use crate::liveness::{prove_possession, LivenessProof};
+use crate::messages::{AttestationMessage, DvrfInput};
+#[cfg(feature = "secure-memory")]
+use crate::secure_memory::{SecureKeyPackage, SecureNonces};
+#[cfg(feature = "bls")]
+use crate::bls::{BlsKeyShare, BlsPartialSignature};
+#[cfg(feature = "blind")]
+use crate::blind::{BlindChallenge, BlindCommitment, BlindSessions, BlindShare, BlindSigningPolicy};
+use crate::utils::{keccak256, prove_eq_with_rng};
+use crate::version::{ProtocolVersion, VersionRange, Versioned};
+
+/// Session identifier chosen by the coordinator
+pub type SessionId = [u8; 32];
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Source of audit-log timestamps, unix seconds
+pub type Clock = Box<dyn Fn() -> u64 + Send + Sync>;
+
+/// Share held by a participant; in locked memory with `secure-memory`
+#[cfg(not(feature = "secure-memory"))]
+type StoredKeyPackage = KeyPackage;
+#[cfg(feature = "secure-memory")]
+type StoredKeyPackage = SecureKeyPackage;
+
+#[cfg(not(feature = "secure-memory"))]
+fn store_key_package(key_package: KeyPackage) -> Result<StoredKeyPackage> {
+    Ok(key_package)
+}
+
+#[cfg(feature = "secure-memory")]
+fn store_key_package(key_package: KeyPackage) -> Result<StoredKeyPackage> {
+    SecureKeyPackage::new(key_package)
+}
+
+/// Nonces held by a signing session; in locked memory with `secure-memory`
+#[cfg(not(feature = "secure-memory"))]
+type StoredNonces = round1::SigningNonces;
+#[cfg(feature = "secure-memory")]
+type StoredNonces = SecureNonces;
+
+#[cfg(not(feature = "secure-memory"))]
+fn store_nonces(nonces: round1::SigningNonces) -> Result<StoredNonces> {
+    Ok(nonces)
+}
+
+/// Fails if the nonces cannot be locked in memory
+#[cfg(feature = "secure-memory")]
+fn store_nonces(nonces: round1::SigningNonces) -> Result<StoredNonces> {
+    SecureNonces::new(nonces)
+}
+
+#[cfg(not(feature = "secure-memory"))]
+fn with_nonces<T>(nonces: &StoredNonces, f: impl FnOnce(&round1::SigningNonces) -> T) -> T {
+    f(nonces)
+}
+
+#[cfg(feature = "secure-memory")]
+fn with_nonces<T>(nonces: &StoredNonces, f: impl FnOnce(&round1::SigningNonces) -> T) -> T {
+    nonces.with_nonces(f)
+}
+
+/// Per-session FROST state
+struct SigningSession {
+    commitments: round1::SigningCommitments,
+    /// dropped once the share is produced
+    nonces: Option<StoredNonces>,
+    /// (hash of the signing package, share)
+    signed: Option<(Hash32, round2::SignatureShare)>,
+}
+
+/// One committee member
+pub struct Participant {
+    /// in locked memory with `secure-memory`, unpacked per use
+    key_package: StoredKeyPackage,
+    public_key_package: PublicKeyPackage,
+    /// session → (hash of msg, answer)
+    evals: BTreeMap<SessionId, (Hash32, PartialEval)>,
+    signing: BTreeMap<SessionId, SigningSession>,
+    versions: VersionRange,
+    session_versions: BTreeMap<SessionId, ProtocolVersion>,
+    #[cfg(feature = "blind")]
+    blind: BlindSessions,
+    #[cfg(feature = "bls")]
+    bls_share: Option<BlsKeyShare>,
+    audit: AuditLog,
+    clock: Clock,
+}
+
+/// Requests a coordinator sends to a participant, inside a `Versioned` envelope
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticipantRequest {
+    /// which versions do you speak; answered under any envelope version
+    Hello,
+    PartialEval { session_id: SessionId, msg: DvrfInput },
+    Commit { session_id: SessionId },
+    Sign { session_id: SessionId, signing_package: frost::SigningPackage },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticipantReply {
+    Versions(VersionRange),
+    PartialEval(PartialEval),
+    Commitments(round1::SigningCommitments),
+    SignatureShare(round2::SignatureShare),
+}
+
+impl Participant {
+    /// Infallible without `secure-memory`; with it use `try_new`
+    #[cfg(not(feature = "secure-memory"))]
+    pub fn new(key_package: KeyPackage, public_key_package: PublicKeyPackage) -> Self {
+        Self::from_stored(key_package, public_key_package)
+    }
+
+    /// With `secure-memory`, fails if the share cannot be locked in memory
+    pub fn try_new(key_package: KeyPackage, public_key_package: PublicKeyPackage) -> Result<Self> {
+        Ok(Self::from_stored(store_key_package(key_package)?, public_key_package))
+    }
+
+    fn from_stored(key_package: StoredKeyPackage, public_key_package: PublicKeyPackage) -> Self {
+        Self {
+            key_package,
+            public_key_package,
+            evals: BTreeMap::new(),
+            signing: BTreeMap::new(),
+            versions: VersionRange::supported(),
+            session_versions: BTreeMap::new(),
+            #[cfg(feature = "blind")]
+            blind: BlindSessions::default(),
+            #[cfg(feature = "bls")]
+            bls_share: None,
+            audit: AuditLog::new(),
+            clock: Box::new(unix_now),
+        }
+    }
+
+    /// Timestamp audit entries with `clock` instead of the wall clock (record / replay)
+    pub fn set_clock(&mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    #[cfg(not(feature = "secure-memory"))]
+    pub fn id(&self) -> Identifier {
+        *self.key_package.identifier()
+    }
+
+    /// kept outside the locked buffer, so no secret is unpacked
+    #[cfg(feature = "secure-memory")]
+    pub fn id(&self) -> Identifier {
+        self.key_package.id()
+    }
+
+    #[cfg(not(feature = "secure-memory"))]
+    fn with_key<T>(&self, f: impl FnOnce(&KeyPackage) -> T) -> T {
+        f(&self.key_package)
+    }
+
+    #[cfg(feature = "secure-memory")]
+    fn with_key<T>(&self, f: impl FnOnce(&KeyPackage) -> T) -> T {
+        self.key_package.with_key_package(f)
+    }
+
+    pub fn verifying_share(&self) -> ProjectivePoint {
+        vk_share_from_public_pkg(&self.public_key_package, self.id())
+    }
+
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    pub fn supported_versions(&self) -> VersionRange {
+        self.versions
+    }
+
+    /// Restrict (or, after an upgrade, widen) the versions this node accepts for new sessions
+    pub fn set_supported_versions(&mut self, versions: VersionRange) {
+        self.versions = versions;
+    }
+
+    pub fn session_version(&self, session_id: &SessionId) -> Option<ProtocolVersion> {
+        self.session_versions.get(session_id).copied()
+    }
+
+    /// Bind `session_id` to `version`. Re-opening under the same version is a no-op; a
+    /// session never changes version.
+    pub fn open_session(&mut self, session_id: SessionId, version: ProtocolVersion) -> Result<()> {
+        if !self.versions.contains(version) {
+            bail!("protocol version {} not supported", version.0);
+        }
+        match self.session_versions.get(&session_id) {
+            Some(v) if *v != version => bail!("session already runs under protocol version {}", v.0),
+            Some(_) => {}
+            None => {
+                self.session_versions.insert(session_id, version);
+            }
+        }
+        Ok(())
+    }
+
+    /// Version of a session reached through the unversioned calls: OLDEST unless opened otherwise.
+    /// Nothing is recorded here, so requests for unknown sessions leave no state behind.
+    fn legacy_session_version(&self, session_id: SessionId) -> Result<ProtocolVersion> {
+        let v = self.session_version(&session_id).unwrap_or(ProtocolVersion::OLDEST);
+        if !self.versions.contains(v) {
+            bail!("protocol version {} not supported", v.0);
+        }
+        Ok(v)
+    }
+
+    /// Versioned wire entry point: checks the envelope, binds the session, answers under the
+    /// same version
+    pub fn handle<R: RngCore + CryptoRng>(
+        &mut self,
+        req: Versioned<ParticipantRequest>,
+        rng: &mut R,
+    ) -> Result<Versioned<ParticipantReply>> {
+        let version = req.version;
+        let body = match req.body {
+            ParticipantRequest::Hello => {
+                return Ok(Versioned::new(self.versions.max, ParticipantReply::Versions(self.versions)));
+            }
+            body => Versioned::new(version, body).open(&self.versions)?,
+        };
+        let reply = match body {
+            ParticipantRequest::Hello => unreachable!("answered above"),
+            ParticipantRequest::PartialEval { session_id, msg } => {
+                self.open_session(session_id, version)?;
+                ParticipantReply::PartialEval(self.partial_eval_with_rng(session_id, &msg, rng)?)
+            }
+            ParticipantRequest::Commit { session_id } => {
+                self.open_session(session_id, version)?;
+                ParticipantReply::Commitments(self.commit(session_id, rng)?)
+            }
+            ParticipantRequest::Sign { session_id, signing_package } => {
+                if self.session_version(&session_id) != Some(version) {
+                    bail!("session was not committed under protocol version {}", version.0);
+                }
+                ParticipantReply::SignatureShare(self.sign(session_id, &signing_package)?)
+            }
+        };
+        Ok(Versioned::new(version, reply))
+    }
+
+    /// DVRF partial evaluation (v_i, π_i) on `msg`.
+    /// A repeated request for the same session returns the first answer.
+    pub fn partial_eval(&mut self, session_id: SessionId, msg: &DvrfInput) -> Result<PartialEval> {
+        self.partial_eval_with_rng(session_id, msg, &mut OsRng)
+    }
+
+    /// `partial_eval` with the proof nonce drawn from `rng`
+    pub fn partial_eval_with_rng<R: RngCore + CryptoRng>(
+        &mut self,
+        session_id: SessionId,
+        msg: &DvrfInput,
+        rng: &mut R,
+    ) -> Result<PartialEval> {
+        let version = self.legacy_session_version(session_id)?;
+        let msg_hash = keccak256(&msg.to_hash_input());
+        if let Some((h, eval)) = self.evals.get(&session_id) {
+            if *h != msg_hash { bail!("session already used for a different DVRF message"); }
+            return Ok(*eval);
+        }
+
+        let vk_i = self.verifying_share();
+        let (v_i, proof) = self.with_key(|kp| prove_eq_with_rng(version, msg, vk_i, scalar_from_keypackage(kp), rng))?;
+        let eval = PartialEval { version, id: self.id(), v_i, proof };
+        self.audit.append(ShareUse::PartialEval, session_id, &msg.to_hash_input(), (self.clock)());
+        // pin the version only once the session holds an answer
+        self.session_versions.insert(session_id, version);
+        self.evals.insert(session_id, (msg_hash, eval));
+        Ok(eval)
+    }
+
+    /// Answer a liveness challenge; proves the share is loaded without using it for output
+    pub fn liveness<R: RngCore + CryptoRng>(&self, nonce: &[u8; 32], rng: &mut R) -> LivenessProof {
+        let (id, vk_i) = (self.id(), self.verifying_share());
+        self.with_key(|kp| prove_possession(nonce, id, scalar_from_keypackage(kp), &vk_i, rng))
+    }
+
+    /// Signing sessions holding unused nonces
+    pub fn open_signing_sessions(&self) -> usize {
+        self.signing.values().filter(|s| s.nonces.is_some()).count()
+    }
+
+    pub fn has_signing_session(&self, session_id: &SessionId) -> bool {
+        self.signing.contains_key(session_id)
+    }
+
+    /// Drop the nonces of an unfinished session (coordinator gave up / timed out)
+    pub fn abort_signing(&mut self, session_id: &SessionId) {
+        if matches!(self.signing.get(session_id), Some(s) if s.signed.is_none()) {
+            self.signing.remove(session_id);
+            self.session_versions.remove(session_id);
+        }
+    }
+
+    /// Drop everything kept for `session_id` (cached answer, nonces, version) once the
+    /// coordinator has stored the result; a later request under the id starts a new session
+    pub fn forget_session(&mut self, session_id: &SessionId) {
+        self.evals.remove(session_id);
+        self.signing.remove(session_id);
+        self.session_versions.remove(session_id);
+    }
+
+    /// FROST round 1: nonces for `session_id`, generated once per session
+    pub fn commit<R: RngCore + CryptoRng>(&mut self, session_id: SessionId, rng: &mut R) -> Result<round1::SigningCommitments> {
+        if !self.signing.contains_key(&session_id) {
+            let (nonces, commitments) = self.with_key(|kp| round1::commit(kp.signing_share(), rng));
+            let nonces = store_nonces(nonces)?;
+            self.signing.insert(session_id, SigningSession { commitments, nonces: Some(nonces), signed: None });
+        }
+        self.session_versions.entry(session_id).or_insert(ProtocolVersion::OLDEST);
+        Ok(self.signing[&session_id].commitments)
+    }
+
+    /// FROST round 2. Nonces are used for exactly one signing package: the same package
+    /// gets the cached share back, any other package is refused. Only attestation-domain
+    /// messages are signed.
+    pub fn sign(&mut self, session_id: SessionId, signing_package: &frost::SigningPackage) -> Result<round2::SignatureShare> {
+        if AttestationMessage::from_signing_bytes(signing_package.message()).is_none() {
+            bail!("signing package message is not an attestation message");
+        }
+        self.legacy_session_version(session_id)?;
+        let pkg_hash = keccak256(&signing_package.serialize()?);
+        let session = self.signing.get(&session_id)
+            .ok_or_else(|| anyhow!("no round-1 nonces for session"))?;
+
+        if let Some((h, share)) = &session.signed {
+            if *h != pkg_hash { bail!("session already signed a different signing package"); }
+            return Ok(*share);
+        }
+
+        // nonces are only dropped once a share exists; a failed attempt leaves the session as it was
+        let Some(nonces) = session.nonces.as_ref() else { bail!("signing session has no nonces") };
+        let share = with_nonces(nonces, |n| self.with_key(|kp| round2::sign(signing_package, n, kp)))?;
+        let session = self.signing.get_mut(&session_id).expect("session exists");
+        session.nonces = None;
+        session.signed = Some((pkg_hash, share));
+        self.audit.append(ShareUse::SignatureShare, session_id, signing_package.message(), (self.clock)());
+        Ok(share)
+    }
+}
+
+#[cfg(feature = "blind")]
+impl Participant {
+    /// Blind signing is refused unless explicitly allowed here
+    pub fn set_blind_policy(&mut self, policy: BlindSigningPolicy) {
+        self.blind.policy = policy;
+    }
+
+    /// Blind round 1: nonce commitments for `session_id`, generated once per session
+    pub fn blind_commit<R: RngCore + CryptoRng>(&mut self, session_id: SessionId, rng: &mut R) -> Result<BlindCommitment> {
+        let id = self.id();
+        self.blind.commit(id, session_id, (self.clock)(), rng)
+    }
+
+    /// Drop an unanswered blind session and free its slot under the policy cap
+    pub fn abort_blind_session(&mut self, session_id: &SessionId) {
+        self.blind.abort(session_id);
+    }
+
+    /// Blind round 2: share over the blinded challenge; the session closes once answered
+    pub fn blind_sign(&mut self, challenge: &BlindChallenge) -> Result<BlindShare> {
+        let (x_i, threshold) = self.with_key(|kp| (scalar_from_keypackage(kp), *kp.min_signers()));
+        let share = self.blind.sign(x_i, &self.public_key_package, threshold, challenge, (self.clock)())?;
+        self.audit.append(ShareUse::BlindSignatureShare, challenge.session_id, &challenge.to_bytes(), (self.clock)());
+        Ok(share)
+    }
+}
+
+#[cfg(feature = "bls")]
+impl Participant {
+    /// Take this member's share from the BLS DKG
+    pub fn set_bls_share(&mut self, share: BlsKeyShare) -> Result<()> {
+        if share.id != self.id() {
+            bail!("BLS share belongs to another member");
+        }
+        self.bls_share = Some(share);
+        Ok(())
+    }
+
+    /// Partial BLS signature on an attestation message
+    pub fn bls_sign(&mut self, session_id: SessionId, msg: &AttestationMessage) -> Result<BlsPartialSignature> {
+        let Some(share) = &self.bls_share else { bail!("participant holds no BLS share") };
+        let partial = share.sign(msg);
+        self.audit.append(ShareUse::BlsSignatureShare, session_id, &msg.to_signing_bytes(), (self.clock)());
+        Ok(partial)
+    }
+}
+
+/// Split a local DKG output into one `Participant` per identifier
+pub fn participants_from_dkg(out: &DkgOutput) -> Result<BTreeMap<Identifier, Participant>> {
+    out.key_packages
+        .iter()
+        .map(|(id, kp)| Ok((*id, Participant::try_new(kp.clone(), out.public_key_package.clone())?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::audit::verify_chain;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::utils::keccak256;
+    use crate::validated::ValidatedPoint;
+
+    #[test]
+    fn test_participant_records_share_usage() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+
+        // DVRF partial
+        let p1 = parts.get_mut(&ids[0]).unwrap();
+        let input = DvrfInput::new(b"dvrf input".to_vec());
+        let eval = p1.partial_eval([7u8; 32], &input)?;
+        assert!(eval.verify(&input, &ValidatedPoint::new(p1.verifying_share())?));
+
+        // FROST signing with two participants
+        let sid = [9u8; 32];
+        let mut commitments = BTreeMap::new();
+        for id in &ids[..2] {
+            commitments.insert(*id, parts.get_mut(id).unwrap().commit(sid, &mut rng)?);
+        }
+        let msg = AttestationMessage::new(b"attestation".to_vec());
+        let pkg = frost::SigningPackage::new(commitments, &msg.to_signing_bytes());
+        let mut shares = BTreeMap::new();
+        for id in &ids[..2] {
+            shares.insert(*id, parts.get_mut(id).unwrap().sign(sid, &pkg)?);
+        }
+        let sig = frost::aggregate(&pkg, &shares, &out.public_key_package)?;
+        assert!(out.public_key_package.verifying_key().verify(&msg.to_signing_bytes(), &sig).is_ok());
+
+        // nonces never sign a second package
+        let other = frost::SigningPackage::new(pkg.signing_commitments().clone(), &AttestationMessage::new(b"other".to_vec()).to_signing_bytes());
+        assert!(parts.get_mut(&ids[0]).unwrap().sign(sid, &other).is_err());
+
+        let log = parts[&ids[0]].audit_log();
+        assert_eq!(log.entries().len(), 2);
+        assert_eq!(log.entries()[0].kind, ShareUse::PartialEval);
+        assert_eq!(log.entries()[1].kind, ShareUse::SignatureShare);
+        assert_eq!(log.entries()[1].message_hash, keccak256(&msg.to_signing_bytes()));
+        verify_chain(log.entries())?;
+        assert_eq!(parts[&ids[2]].audit_log().entries().len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_participant_dedups_sessions() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let p = parts.values_mut().next().unwrap();
+
+        let m = DvrfInput::new(b"m".to_vec());
+        let a = p.partial_eval([1u8; 32], &m)?;
+        let b = p.partial_eval([1u8; 32], &m)?;
+        assert_eq!(a, b);
+        assert!(p.partial_eval([1u8; 32], &DvrfInput::new(b"m'".to_vec())).is_err());
+
+        // raw bytes without the attestation prefix are never signed
+        let mut rng2 = OsRng;
+        let c = p.commit([3u8; 32], &mut rng2)?;
+        let raw = frost::SigningPackage::new(BTreeMap::from([(p.id(), c)]), b"m");
+        assert!(p.sign([3u8; 32], &raw).is_err());
+        p.abort_signing(&[3u8; 32]);
+
+        let c1 = p.commit([2u8; 32], &mut rng)?;
+        let c2 = p.commit([2u8; 32], &mut rng)?;
+        assert_eq!(c1, c2);
+
+        // one audit entry per distinct session
+        assert_eq!(p.audit_log().entries().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forget_session_drops_state() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let p = parts.values_mut().next().unwrap();
+
+        let m = DvrfInput::new(b"m".to_vec());
+        p.partial_eval([1u8; 32], &m)?;
+        let c = p.commit([2u8; 32], &mut rng)?;
+        let pkg = frost::SigningPackage::new(BTreeMap::from([(p.id(), c)]), &AttestationMessage::new(b"a".to_vec()).to_signing_bytes());
+        p.sign([2u8; 32], &pkg)?;
+        assert_eq!((p.evals.len(), p.signing.len(), p.session_versions.len()), (1, 1, 2));
+
+        p.forget_session(&[1u8; 32]);
+        p.forget_session(&[2u8; 32]);
+        assert_eq!((p.evals.len(), p.signing.len(), p.session_versions.len()), (0, 0, 0));
+        // a forgotten id is a new session
+        p.partial_eval([1u8; 32], &DvrfInput::new(b"m'".to_vec()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_sign_keeps_nonces() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let ids = out.all_ids();
+        let sid = [4u8; 32];
+        let commitments = ids[..2].iter().map(|id| Ok((*id, parts.get_mut(id).unwrap().commit(sid, &mut rng)?))).collect::<Result<BTreeMap<_, _>>>()?;
+        let msg = AttestationMessage::new(b"retry".to_vec()).to_signing_bytes();
+
+        // a package without this signer's commitment fails in round2::sign, twice, without panicking
+        let p = parts.get_mut(&ids[0]).unwrap();
+        let foreign = frost::SigningPackage::new(BTreeMap::from([(ids[1], commitments[&ids[1]])]), &msg);
+        assert!(p.sign(sid, &foreign).is_err());
+        assert!(p.sign(sid, &foreign).is_err());
+        assert_eq!(p.open_signing_sessions(), 1);
+
+        let pkg = frost::SigningPackage::new(commitments, &msg);
+        let share = p.sign(sid, &pkg)?;
+        assert_eq!(p.sign(sid, &pkg)?, share);
+        assert_eq!(p.open_signing_sessions(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_versioned_requests_bind_sessions() -> Result<()> {
+        let mut rng = OsRng;
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut rng)?;
+        let mut parts = participants_from_dkg(&out)?;
+        let p = parts.values_mut().next().unwrap();
+        let v2 = ProtocolVersion::V2;
+
+        let hello = p.handle(Versioned::new(ProtocolVersion(9), ParticipantRequest::Hello), &mut rng)?;
+        assert_eq!(hello.body, ParticipantReply::Versions(VersionRange::supported()));
+
+        let m = DvrfInput::new(b"m".to_vec());
+        let req = |sid| Versioned::new(v2, ParticipantRequest::PartialEval { session_id: sid, msg: m.clone() });
+        let reply = p.handle(req([1u8; 32]), &mut rng)?;
+        assert_eq!(reply.version, v2);
+        assert!(matches!(reply.body, ParticipantReply::PartialEval(_)));
+        assert_eq!(p.session_version(&[1u8; 32]), Some(v2));
+
+        // unknown and retired versions refused, and a session never switches version
+        let future = Versioned::new(ProtocolVersion(3), ParticipantRequest::Commit { session_id: [2u8; 32] });
+        assert!(p.handle(future, &mut rng).is_err());
+        let retired = Versioned::new(ProtocolVersion::V1, ParticipantRequest::PartialEval { session_id: [6u8; 32], msg: m.clone() });
+        assert!(p.handle(retired, &mut rng).is_err());
+        assert!(p.open_session([1u8; 32], ProtocolVersion(3)).is_err());
+
+        // plain calls are OLDEST
+        p.commit([3u8; 32], &mut rng)?;
+        assert_eq!(p.session_version(&[3u8; 32]), Some(v2));
+        assert_eq!(p.partial_eval([4u8; 32], &m)?.version, v2);
+
+        // a refused request for an unknown session leaves no state behind
+        let pkg = frost::SigningPackage::new(BTreeMap::new(), &AttestationMessage::new(b"a".to_vec()).to_signing_bytes());
+        assert!(p.sign([5u8; 32], &pkg).is_err());
+        assert_eq!(p.session_version(&[5u8; 32]), None);
+        Ok(())
+    }
+}
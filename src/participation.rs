@@ -0,0 +1,75 @@
+//! Per-round participation hooks for reward / slashing accounting.
+//!
+//! The coordinator calls every registered `ParticipationHook` once per round it actually runs
+//! (not for rounds answered from the session store or the cache) with the verified
+//! contributors and whoever misbehaved. Hooks only observe; they cannot change the outcome.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::cache::RoundKind;
+use crate::dkg::Identifier;
+use crate::participant::SessionId;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Misbehavior {
+    /// DVRF partial whose DLEQ proof does not verify
+    InvalidProof,
+    /// FROST share rejected by aggregation
+    InvalidSignatureShare,
+    /// committed in round 1, never delivered a round-2 share and was replaced
+    DroppedOut,
+    /// sent two different messages for the same round (e.g. two round-2 shares for one
+    /// signing attempt); reported by `Coordinator::finish_signing`
+    Equivocation,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundParticipation {
+    pub kind: RoundKind,
+    pub session_id: SessionId,
+    /// signers whose contribution verified
+    pub contributors: Vec<Identifier>,
+    pub misbehaving: Vec<(Identifier, Misbehavior)>,
+    /// whether the round produced an output
+    pub completed: bool,
+}
+
+pub trait ParticipationHook: Send + Sync {
+    fn on_round(&self, round: &RoundParticipation);
+}
+
+/// Per-identifier counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub contributed: u64,
+    pub misbehaved: u64,
+}
+
+/// Reference hook: counts contributions and misbehavior per identifier in memory
+#[derive(Default)]
+pub struct ParticipationTally {
+    inner: Mutex<BTreeMap<Identifier, Tally>>,
+}
+
+impl ParticipationTally {
+    pub fn get(&self, id: &Identifier) -> Tally {
+        self.inner.lock().expect("tally lock").get(id).copied().unwrap_or_default()
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<Identifier, Tally> {
+        self.inner.lock().expect("tally lock").clone()
+    }
+}
+
+impl ParticipationHook for ParticipationTally {
+    fn on_round(&self, round: &RoundParticipation) {
+        let mut t = self.inner.lock().expect("tally lock");
+        for id in &round.contributors {
+            t.entry(*id).or_default().contributed += 1;
+        }
+        for (id, _) in &round.misbehaving {
+            t.entry(*id).or_default().misbehaved += 1;
+        }
+    }
+}
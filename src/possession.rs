@@ -0,0 +1,130 @@
+//! Proof of possession at DKG enrollment. Once part 3 is done every member signs the DKG
+//! session id with its share: a Schnorr signature (R = w·G, z = w + c·sk_i) that verifies under
+//! its verifying share vk_i. A member that cannot produce one does not know the secret behind
+//! the key it enrolled, which rules out rogue-key enrollment when identifiers or keys come from
+//! outside the DKG.
+//!
+//! The session id of a DKG is its transcript hash: every member knows it once round 1 is
+//! public, and it commits to all round-1 packages, so a proof cannot be carried over from
+//! another ceremony.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::ddh_dvrf::{id_as_u64, scalar_from_keypackage, validated_vk_share};
+use crate::dkg::{Identifier, KeyPackage, PublicKeyPackage};
+use crate::transcript::{KeccakTranscript, Transcript};
+use crate::utils::{point_bytes_compressed, scalar_from_canonical_bytes};
+use crate::validated::ValidatedPoint;
+
+const POSSESSION_PROTOCOL: &[u8] = b"frostlab/dkg-possession/v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PossessionProof {
+    pub id: Identifier,
+    pub commitment: ProjectivePoint,
+    pub response: Scalar,
+}
+
+impl PossessionProof {
+    pub const BYTES: usize = 65;
+
+    /// compressed R || z
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..33].copy_from_slice(&point_bytes_compressed(&self.commitment));
+        out[33..].copy_from_slice(&self.response.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(id: Identifier, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::BYTES {
+            bail!("proof of possession must be {} bytes, got {}", Self::BYTES, bytes.len());
+        }
+        let commitment = ValidatedPoint::from_compressed(&bytes[..33])?.point();
+        let response = scalar_from_canonical_bytes(&bytes[33..])?;
+        Ok(Self { id, commitment, response })
+    }
+}
+
+fn challenge(session_id: &[u8; 32], id: Identifier, vk_i: &ProjectivePoint, commitment: &ProjectivePoint) -> Scalar {
+    let mut t = KeccakTranscript::new(POSSESSION_PROTOCOL);
+    t.append_message(b"session", session_id);
+    t.append_message(b"id", &id.serialize());
+    t.append_point(b"vk", vk_i);
+    t.append_point(b"R", commitment);
+    t.challenge_scalar(b"ch")
+}
+
+/// Member side, right after part 3
+pub fn prove_possession<R: RngCore + CryptoRng>(session_id: &[u8; 32], key_package: &KeyPackage, rng: &mut R) -> PossessionProof {
+    let id = *key_package.identifier();
+    let vk_i = key_package.verifying_share().to_element();
+    let w = Scalar::random(rng);
+    let commitment = ProjectivePoint::GENERATOR * w;
+    let c = challenge(session_id, id, &vk_i, &commitment);
+    PossessionProof { id, commitment, response: w + c * scalar_from_keypackage(key_package) }
+}
+
+pub fn verify_possession(session_id: &[u8; 32], vk_i: &ValidatedPoint, proof: &PossessionProof) -> Result<()> {
+    let c = challenge(session_id, proof.id, vk_i, &proof.commitment);
+    if ProjectivePoint::GENERATOR * proof.response != proof.commitment + **vk_i * c {
+        bail!("invalid proof of possession from id={}", id_as_u64(proof.id));
+    }
+    Ok(())
+}
+
+/// Exactly one valid proof from every member of `public_key_package`
+pub fn verify_possessions(
+    session_id: &[u8; 32],
+    public_key_package: &PublicKeyPackage,
+    proofs: &BTreeMap<Identifier, PossessionProof>,
+) -> Result<()> {
+    let members = public_key_package.verifying_shares();
+    if let Some(id) = proofs.keys().find(|id| !members.contains_key(*id)) {
+        bail!("proof of possession from non-member id={}", id_as_u64(*id));
+    }
+    for id in members.keys() {
+        let Some(proof) = proofs.get(id) else {
+            bail!("no proof of possession from id={}", id_as_u64(*id));
+        };
+        if proof.id != *id {
+            bail!("proof of possession filed under the wrong member id={}", id_as_u64(*id));
+        }
+        verify_possession(session_id, &validated_vk_share(public_key_package, *id)?, proof)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_possession_bound_to_share_and_session() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let sid = out.transcript_hash;
+        verify_possessions(&sid, &out.public_key_package, &out.possession_proofs)?;
+        assert!(verify_possessions(&[0u8; 32], &out.public_key_package, &out.possession_proofs).is_err());
+
+        let ids = out.all_ids();
+        let proof = out.possession_proofs[&ids[0]];
+        assert_eq!(PossessionProof::from_bytes(ids[0], &proof.to_bytes())?, proof);
+
+        // a proof made with someone else's share does not enroll this member
+        let mut proofs = out.possession_proofs.clone();
+        proofs.insert(ids[0], PossessionProof { id: ids[0], ..prove_possession(&sid, &out.key_packages[&ids[1]], &mut OsRng) });
+        assert!(verify_possessions(&sid, &out.public_key_package, &proofs).is_err());
+        proofs.insert(ids[0], proofs[&ids[1]]);
+        assert!(verify_possessions(&sid, &out.public_key_package, &proofs).is_err());
+
+        proofs.remove(&ids[0]);
+        assert!(verify_possessions(&sid, &out.public_key_package, &proofs).is_err());
+        Ok(())
+    }
+}
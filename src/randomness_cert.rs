@@ -0,0 +1,152 @@
+//! Proof-of-randomness certificate: the group FROST-signs the hash of the whole DVRF round
+//! transcript (version, message, every (i, v_i, π_i), combined output). A consumer that cannot verify
+//! DLEQ proofs trusts one Schnorr check against the group key instead; anyone holding the
+//! round data can still recompute the transcript hash and audit it. For rounds with a VDF
+//! stage the certified randomness is the VDF output, checked against the configured backend.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+
+use crate::cert::{CertConfig, QuorumCert};
+use crate::ddh_dvrf::PublicKeyPackage;
+use crate::frost_ext::frost_verify_with_key;
+use crate::messages::{AttestationMessage, RANDOMNESS_CERT_TAG};
+use crate::transcript::{KeccakTranscript, Transcript};
+use crate::utils::keccak256;
+use crate::version::ProtocolVersion;
+#[cfg(feature = "protocol")]
+use frost::rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "protocol")]
+use crate::{dkg::{DkgOutput, Identifier}, frost_ext::frost_sign};
+
+const RANDOMNESS_CERT_PROTOCOL: &[u8] = b"frostlab/randomness-cert/v1";
+
+/// Framed hash of everything the round produced
+pub fn round_transcript_hash(cert: &QuorumCert) -> [u8; 32] {
+    let mut t = KeccakTranscript::new(RANDOMNESS_CERT_PROTOCOL);
+    t.append_u64(b"version", cert.version.0 as u64);
+    t.append_message(b"msg", &cert.msg.to_hash_input());
+    t.append_u64(b"n", cert.partials.len() as u64);
+    for p in &cert.partials {
+        t.append_message(b"id", &p.id.serialize());
+        t.append_point(b"v_i", &p.v_i);
+        t.append_message(b"proof", &p.proof.to_bytes());
+    }
+    t.append_point(b"v", &cert.output);
+    t.challenge_bytes(b"transcript-hash")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RandomnessCertificate {
+    pub version: ProtocolVersion,
+    pub epoch: u64,
+    pub round: u64,
+    /// keccak(msg.to_hash_input()) of the round's DVRF input
+    pub msg_hash: [u8; 32],
+    pub transcript_hash: [u8; 32],
+    pub randomness: [u8; 32],
+    pub signature: frost::Signature,
+}
+
+/// Signed bytes, tagged `RANDOMNESS_CERT_TAG` in the attestation domain:
+/// u16 version || u64 epoch || u64 round || msg_hash || transcript_hash || randomness
+fn certificate_message(version: ProtocolVersion, epoch: u64, round: u64, digests: [&[u8; 32]; 3]) -> AttestationMessage {
+    let mut raw = Vec::with_capacity(2 + 8 + 8 + 3 * 32);
+    raw.extend_from_slice(&version.0.to_be_bytes());
+    raw.extend_from_slice(&epoch.to_be_bytes());
+    raw.extend_from_slice(&round.to_be_bytes());
+    for d in digests {
+        raw.extend_from_slice(d);
+    }
+    AttestationMessage::tagged(RANDOMNESS_CERT_TAG, raw)
+}
+
+impl RandomnessCertificate {
+    pub fn message(&self) -> AttestationMessage {
+        certificate_message(self.version, self.epoch, self.round, [&self.msg_hash, &self.transcript_hash, &self.randomness])
+    }
+
+    /// The cheap check: one group signature
+    pub fn verify(&self, group_key: &frost::VerifyingKey) -> Result<()> {
+        if !frost_verify_with_key(&self.message(), &self.signature, group_key)? {
+            bail!("invalid group signature on randomness certificate");
+        }
+        Ok(())
+    }
+
+    /// Full audit against the round data: DLEQ proofs, transcript hash, randomness, signature
+    pub fn verify_with_round<'a>(&self, cert: &QuorumCert, public_key_package: &PublicKeyPackage, config: impl Into<CertConfig<'a>>) -> Result<()> {
+        cert.verify(public_key_package, config)?;
+        if cert.version != self.version
+            || keccak256(&cert.msg.to_hash_input()) != self.msg_hash
+            || round_transcript_hash(cert) != self.transcript_hash
+            || cert.randomness() != self.randomness
+        {
+            bail!("randomness certificate does not describe this round");
+        }
+        self.verify(public_key_package.verifying_key())
+    }
+}
+
+/// Verify the round, then have `signers` sign its transcript hash as round `round` of `epoch`
+#[cfg(feature = "protocol")]
+pub fn certify_randomness<'a, R: RngCore + CryptoRng>(
+    cert: &QuorumCert,
+    out: &DkgOutput,
+    config: impl Into<CertConfig<'a>>,
+    epoch: u64,
+    round: u64,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> Result<RandomnessCertificate> {
+    cert.verify(&out.public_key_package, config)?;
+    let msg_hash = keccak256(&cert.msg.to_hash_input());
+    let transcript_hash = round_transcript_hash(cert);
+    let randomness = cert.randomness();
+    let msg = certificate_message(cert.version, epoch, round, [&msg_hash, &transcript_hash, &randomness]);
+    let signature = frost_sign(&msg, out, signers, rng)?;
+    Ok(RandomnessCertificate { version: cert.version, epoch, round, msg_hash, transcript_hash, randomness, signature })
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::messages::DvrfInput;
+
+    #[test]
+    fn test_randomness_certificate() -> Result<()> {
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let cert = run_ddh_dvrf_cert(&DvrfInput::new(b"epoch 4".to_vec()), &out.key_packages, &out.public_key_package, &ids[1..4]);
+
+        let rc = certify_randomness(&cert, &out, cfg.min_signers, 4, 1, &ids[..3], &mut OsRng)?;
+        rc.verify(out.public_key_package.verifying_key())?;
+        rc.verify_with_round(&cert, &out.public_key_package, cfg.min_signers)?;
+
+        // another quorum yields the same randomness but a different transcript
+        let other = run_ddh_dvrf_cert(&cert.msg, &out.key_packages, &out.public_key_package, &ids[2..]);
+        assert_eq!(other.randomness(), cert.randomness());
+        assert!(rc.verify_with_round(&other, &out.public_key_package, cfg.min_signers).is_err());
+
+        let mut forged = rc;
+        forged.randomness[0] ^= 1;
+        assert!(forged.verify(out.public_key_package.verifying_key()).is_err());
+        let mut forged = rc;
+        forged.version = ProtocolVersion(3);
+        assert!(forged.verify(out.public_key_package.verifying_key()).is_err());
+        // the signature covers which round this was
+        for forged in [RandomnessCertificate { epoch: 5, ..rc }, RandomnessCertificate { round: 2, ..rc }] {
+            assert!(forged.verify(out.public_key_package.verifying_key()).is_err());
+        }
+        let mut forged = rc;
+        forged.msg_hash[0] ^= 1;
+        assert!(forged.verify(out.public_key_package.verifying_key()).is_err());
+        assert!(forged.verify_with_round(&cert, &out.public_key_package, cfg.min_signers).is_err());
+        assert_eq!(rc.message().tag(), RANDOMNESS_CERT_TAG);
+        Ok(())
+    }
+}
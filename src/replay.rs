@@ -0,0 +1,252 @@
+//! Deterministic record / replay of protocol runs.
+//!
+//! `ReplayableRng` records every byte a run draws from its RNG; `ProtocolTrace` keeps that
+//! tape together with every message the run emitted. Replaying the tape through the same
+//! code must reproduce the trace exactly, and replaying it through a different code path
+//! (serial vs incremental / batched) is a differential test of the two.
+//!
+//! A replaying RNG is fully predictable: tests and debugging only, never production keys.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{impls, CryptoRng, Error, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cert::QuorumCert;
+use crate::ddh_dvrf::run_ddh_dvrf_cert_with_rng;
+use crate::dkg::{run_dealerless_dkg, DkgConfig, DkgOutput};
+use crate::encoding::{from_hex0x, hex0x};
+use crate::frost_ext::frost_sign;
+use crate::messages::{AttestationMessage, DvrfInput};
+
+pub struct ReplayableRng {
+    /// `None` while replaying
+    source: Option<Box<dyn RngCore + Send>>,
+    tape: Vec<u8>,
+    pos: usize,
+}
+
+impl ReplayableRng {
+    pub fn recording<R: RngCore + CryptoRng + Send + 'static>(inner: R) -> Self {
+        Self { source: Some(Box::new(inner)), tape: Vec::new(), pos: 0 }
+    }
+
+    pub fn replaying(tape: Vec<u8>) -> Self {
+        Self { source: None, tape, pos: 0 }
+    }
+
+    /// Bytes drawn so far (recording) / consumed so far (replaying)
+    pub fn tape(&self) -> &[u8] {
+        match self.source {
+            Some(_) => &self.tape,
+            None => &self.tape[..self.pos],
+        }
+    }
+}
+
+impl RngCore for ReplayableRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    /// Panics when a replay asks for more bytes than were recorded
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match &mut self.source {
+            Some(src) => {
+                src.fill_bytes(dest);
+                self.tape.extend_from_slice(dest);
+            }
+            None => {
+                let end = self.pos + dest.len();
+                assert!(end <= self.tape.len(), "replay tape exhausted at byte {}", self.pos);
+                dest.copy_from_slice(&self.tape[self.pos..end]);
+                self.pos = end;
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ReplayableRng {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub label: String,
+    /// 0x-hex payload
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolTrace {
+    /// 0x-hex RNG tape
+    pub tape: String,
+    pub events: Vec<TraceEvent>,
+}
+
+impl ProtocolTrace {
+    pub fn record(&mut self, label: &str, data: &[u8]) {
+        self.events.push(TraceEvent { label: label.to_string(), data: hex0x(data) });
+    }
+
+    /// Index of the first event where the two runs differ
+    pub fn first_divergence(&self, other: &ProtocolTrace) -> Option<usize> {
+        let n = self.events.len().min(other.events.len());
+        (0..n)
+            .find(|k| self.events[*k] != other.events[*k])
+            .or((self.events.len() != other.events.len()).then_some(n))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+/// Run `f` on a recording RNG; the trace carries the tape and whatever `f` recorded
+pub fn record_run<R, T, F>(rng: R, f: F) -> Result<(T, ProtocolTrace)>
+where
+    R: RngCore + CryptoRng + Send + 'static,
+    F: FnOnce(&mut ReplayableRng, &mut ProtocolTrace) -> Result<T>,
+{
+    let mut rng = ReplayableRng::recording(rng);
+    let mut trace = ProtocolTrace::default();
+    let out = f(&mut rng, &mut trace)?;
+    trace.tape = hex0x(rng.tape());
+    Ok((out, trace))
+}
+
+/// Run `f` on the tape of `recorded` and compare the traces
+pub fn replay_run<T, F>(recorded: &ProtocolTrace, f: F) -> Result<T>
+where
+    F: FnOnce(&mut ReplayableRng, &mut ProtocolTrace) -> Result<T>,
+{
+    let mut rng = ReplayableRng::replaying(from_hex0x(&recorded.tape)?);
+    let mut trace = ProtocolTrace::default();
+    let out = f(&mut rng, &mut trace)?;
+    trace.tape = hex0x(rng.tape());
+    if let Some(k) = recorded.first_divergence(&trace) {
+        let label = recorded.events.get(k).or(trace.events.get(k)).map(|e| e.label.as_str()).unwrap_or("");
+        bail!("replay diverged at event {} ({})", k, label);
+    }
+    if trace.tape != recorded.tape {
+        bail!("replay consumed a different amount of randomness");
+    }
+    Ok(out)
+}
+
+fn record_cert(trace: &mut ProtocolTrace, round: usize, cert: &QuorumCert) {
+    for p in &cert.partials {
+        trace.record(&format!("round{round}/partial"), &[&p.id.serialize()[..], &p.v_i.to_compressed(), &p.proof.to_bytes()].concat());
+    }
+    trace.record(&format!("round{round}/randomness"), &cert.randomness());
+}
+
+/// DKG, then per message one DVRF round and a FROST signature on its randomness, all drawn
+/// from `rng`. The first `min_signers` identifiers take part in every round.
+pub fn traced_session(
+    cfg: DkgConfig,
+    msgs: &[DvrfInput],
+    rng: &mut ReplayableRng,
+    trace: &mut ProtocolTrace,
+) -> Result<DkgOutput> {
+    let out = run_dealerless_dkg(cfg, rng)?;
+    trace.record("dkg/transcript", &out.transcript_hash);
+    trace.record("dkg/group_key", &out.public_key_package.verifying_key().serialize()?);
+    let signers = &out.all_ids()[..cfg.min_signers as usize];
+    for (k, msg) in msgs.iter().enumerate() {
+        let cert = run_ddh_dvrf_cert_with_rng(msg, &out.key_packages, &out.public_key_package, signers, rng);
+        record_cert(trace, k, &cert);
+        let sig = frost_sign(&AttestationMessage::new(cert.randomness().to_vec()), &out, signers, rng)?;
+        trace.record(&format!("round{k}/signature"), &sig.serialize()?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::combiner::IncrementalCombiner;
+    use crate::participant::participants_from_dkg;
+    use crate::round_attestation::run_dual_round;
+    use crate::threshold_decrypt::{decryption_share_with_rng, encrypt};
+
+    fn msgs() -> Vec<DvrfInput> {
+        (0..3u8).map(|i| DvrfInput::new(vec![i; 4])).collect()
+    }
+
+    #[test]
+    fn test_replay_reproduces_and_diffs_code_paths() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let (_, trace) = record_run(OsRng, |rng, t| traced_session(cfg, &msgs(), rng, t))?;
+        let trace = ProtocolTrace::from_json(&trace.to_json()?)?;
+        replay_run(&trace, |rng, t| traced_session(cfg, &msgs(), rng, t))?;
+
+        // same tape through the incremental combiner instead of the serial one
+        replay_run(&trace, |rng, t| {
+            let out = run_dealerless_dkg(cfg, rng)?;
+            t.record("dkg/transcript", &out.transcript_hash);
+            t.record("dkg/group_key", &out.public_key_package.verifying_key().serialize()?);
+            let signers = &out.all_ids()[..cfg.min_signers as usize];
+            for (k, msg) in msgs().iter().enumerate() {
+                let serial = run_ddh_dvrf_cert_with_rng(msg, &out.key_packages, &out.public_key_package, signers, rng);
+                let mut c = IncrementalCombiner::new(serial.version, msg.clone(), out.public_key_package.clone(), cfg.min_signers)?;
+                let mut cert = None;
+                for p in serial.partials {
+                    cert = c.add(p)?.cloned().or(cert);
+                }
+                let cert = cert.expect("quorum");
+                record_cert(t, k, &cert);
+                let sig = frost_sign(&AttestationMessage::new(cert.randomness().to_vec()), &out, signers, rng)?;
+                t.record(&format!("round{k}/signature"), &sig.serialize()?);
+            }
+            Ok(())
+        })?;
+
+        // a run that changes the signed message diverges at that round's signature
+        let err = replay_run(&trace, |rng, t| traced_session(cfg, &[msgs()[0].clone(), DvrfInput::new(b"x".to_vec()), msgs()[2].clone()], rng, t))
+            .err()
+            .expect("diverges");
+        assert!(err.to_string().contains("round1/partial"), "{err}");
+        Ok(())
+    }
+
+    /// participant evals, dual rounds and decryption shares, audit-logged under a fixed clock
+    fn participant_session(rng: &mut ReplayableRng, t: &mut ProtocolTrace) -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, rng)?;
+        let ids = out.all_ids();
+        let mut parts = participants_from_dkg(&out)?;
+        let msg = DvrfInput::new(b"m".to_vec());
+        for (k, id) in ids.iter().enumerate() {
+            let p = parts.get_mut(id).expect("member");
+            p.set_clock(|| 1_700_000_000);
+            let eval = p.partial_eval_with_rng([k as u8; 32], &msg, rng)?;
+            t.record("participant/partial", &[&eval.v_i.to_compressed()[..], &eval.proof.to_bytes()].concat());
+            t.record("participant/audit_head", &p.audit_log().head());
+        }
+        let dual = run_dual_round(1, 1, &msg, &out, &ids[..2], rng)?;
+        record_cert(t, 0, &dual.cert);
+        t.record("dual/signature", &dual.signature.serialize()?);
+        let ct = encrypt(out.public_key_package.verifying_key(), b"", b"sealed", rng)?;
+        let share = decryption_share_with_rng(&ct, b"", &out.key_packages[&ids[0]], &out.public_key_package, rng)?;
+        t.record("decrypt/share", &share.proof.to_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_covers_participant_paths() -> Result<()> {
+        let (_, trace) = record_run(OsRng, participant_session)?;
+        replay_run(&trace, participant_session)
+    }
+}
@@ -0,0 +1,207 @@
+//! Dual-output round: one round yields the DVRF certificate for M and a FROST signature over
+//! a canonical attestation binding it, so a contract can consume the randomness with a single
+//! signature check.
+//!
+//! attestation = abi.encode(uint16 version, uint64 epoch, uint64 round, bytes32 msgHash,
+//!                          bytes32 vrfOutput, uint256 contributorsBitmap)
+//! version = the certificate's protocol version, msgHash = keccak(DVRF hash input of M),
+//! vrfOutput = cert randomness, bit (i - 1) of the bitmap is set for every contributing
+//! identifier i.
+//!
+//! signed = 0x17 || "frostlab/attestation/v1" || 0x11 || "round-attestation" || attestation
+//! (`AttestationMessage::tagged(ROUND_ATTESTATION_TAG, ..)`); a contract hashes exactly these
+//! bytes, so no other attestation the group signs can pass as a round attestation.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+
+use crate::cert::{CertConfig, QuorumCert};
+use crate::ddh_dvrf::{id_as_u64, PublicKeyPackage};
+use crate::frost_ext::frost_verify_with_key;
+use crate::messages::{AttestationMessage, ROUND_ATTESTATION_TAG};
+use crate::utils::keccak256;
+use crate::version::ProtocolVersion;
+#[cfg(feature = "protocol")]
+use frost::rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "protocol")]
+use crate::{messages::DvrfInput, ddh_dvrf::run_ddh_dvrf_cert_with_rng, dkg::{DkgOutput, Identifier}, frost_ext::frost_sign};
+
+pub const ROUND_ATTESTATION_ABI_LEN: usize = 6 * 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundAttestation {
+    pub version: ProtocolVersion,
+    pub epoch: u64,
+    pub round: u64,
+    pub msg_hash: [u8; 32],
+    pub vrf_output: [u8; 32],
+    /// big-endian uint256
+    pub contributors_bitmap: [u8; 32],
+}
+
+fn u64_word(x: u64) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[24..].copy_from_slice(&x.to_be_bytes());
+    w
+}
+
+fn word_u16(w: &[u8]) -> Result<u16> {
+    if w[..30].iter().any(|b| *b != 0) {
+        bail!("uint16 word out of range");
+    }
+    Ok(u16::from_be_bytes(w[30..32].try_into().expect("2 bytes")))
+}
+
+fn word_u64(w: &[u8]) -> Result<u64> {
+    if w[..24].iter().any(|b| *b != 0) {
+        bail!("uint64 word out of range");
+    }
+    Ok(u64::from_be_bytes(w[24..32].try_into().expect("8 bytes")))
+}
+
+/// Bitmap of the certificate's contributors; identifiers must be in 1..=256
+pub fn contributors_bitmap(cert: &QuorumCert) -> Result<[u8; 32]> {
+    let mut bm = [0u8; 32];
+    for p in &cert.partials {
+        let i = id_as_u64(p.id);
+        if !(1..=256).contains(&i) {
+            bail!("identifier {} does not fit the contributor bitmap", i);
+        }
+        let bit = (i - 1) as usize;
+        bm[31 - bit / 8] |= 1 << (bit % 8);
+    }
+    Ok(bm)
+}
+
+impl RoundAttestation {
+    pub fn from_cert(epoch: u64, round: u64, cert: &QuorumCert) -> Result<Self> {
+        Ok(Self {
+            version: cert.version,
+            epoch,
+            round,
+            msg_hash: keccak256(&cert.msg.to_hash_input()),
+            vrf_output: cert.randomness(),
+            contributors_bitmap: contributors_bitmap(cert)?,
+        })
+    }
+
+    pub fn to_abi(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(ROUND_ATTESTATION_ABI_LEN);
+        out.extend_from_slice(&u64_word(self.version.0 as u64));
+        out.extend_from_slice(&u64_word(self.epoch));
+        out.extend_from_slice(&u64_word(self.round));
+        out.extend_from_slice(&self.msg_hash);
+        out.extend_from_slice(&self.vrf_output);
+        out.extend_from_slice(&self.contributors_bitmap);
+        out
+    }
+
+    pub fn from_abi(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != ROUND_ATTESTATION_ABI_LEN {
+            bail!("round attestation must be {} bytes, got {}", ROUND_ATTESTATION_ABI_LEN, bytes.len());
+        }
+        let w = |k: usize| -> [u8; 32] { bytes[32 * k..32 * (k + 1)].try_into().expect("32 bytes") };
+        Ok(Self {
+            version: ProtocolVersion(word_u16(&w(0))?),
+            epoch: word_u64(&w(1))?,
+            round: word_u64(&w(2))?,
+            msg_hash: w(3),
+            vrf_output: w(4),
+            contributors_bitmap: w(5),
+        })
+    }
+
+    /// What the group signs
+    pub fn message(&self) -> AttestationMessage {
+        AttestationMessage::tagged(ROUND_ATTESTATION_TAG, self.to_abi())
+    }
+}
+
+/// Certificate + the signed attestation over it
+#[derive(Clone, Debug, PartialEq)]
+pub struct DualOutput {
+    pub cert: QuorumCert,
+    pub attestation: RoundAttestation,
+    pub signature: frost::Signature,
+}
+
+impl DualOutput {
+    /// Certificate valid, attestation describes exactly this certificate, group signature valid
+    pub fn verify<'a>(&self, public_key_package: &PublicKeyPackage, config: impl Into<CertConfig<'a>>) -> Result<()> {
+        self.cert.verify(public_key_package, config)?;
+        let expected = RoundAttestation::from_cert(self.attestation.epoch, self.attestation.round, &self.cert)?;
+        if expected != self.attestation {
+            bail!("attestation does not match the certificate");
+        }
+        if !frost_verify_with_key(&self.attestation.message(), &self.signature, public_key_package.verifying_key())? {
+            bail!("invalid group signature on round attestation");
+        }
+        Ok(())
+    }
+}
+
+/// Evaluate M with `signers`, then have the same signers sign the attestation
+#[cfg(feature = "protocol")]
+pub fn run_dual_round<R: RngCore + CryptoRng>(
+    epoch: u64,
+    round: u64,
+    msg: &DvrfInput,
+    out: &DkgOutput,
+    signers: &[Identifier],
+    rng: &mut R,
+) -> Result<DualOutput> {
+    let cert = run_ddh_dvrf_cert_with_rng(msg, &out.key_packages, &out.public_key_package, signers, rng);
+    let attestation = RoundAttestation::from_cert(epoch, round, &cert)?;
+    let signature = frost_sign(&attestation.message(), out, signers, rng)?;
+    Ok(DualOutput { cert, attestation, signature })
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::ddh_dvrf::run_ddh_dvrf_cert;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_dual_round() -> Result<()> {
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let msg = DvrfInput::new(b"M".to_vec());
+
+        let dual = run_dual_round(2, 17, &msg, &out, &[ids[0], ids[2], ids[4]], &mut OsRng)?;
+        dual.verify(&out.public_key_package, cfg.min_signers)?;
+        assert_eq!(dual.attestation.contributors_bitmap[31], 0b10101);
+        assert_eq!(RoundAttestation::from_abi(&dual.attestation.to_abi())?, dual.attestation);
+
+        assert_eq!(dual.attestation.version, dual.cert.version);
+
+        // attestation that claims another round or version is not what was signed
+        let mut moved = dual.clone();
+        moved.attestation.round = 18;
+        assert!(moved.verify(&out.public_key_package, cfg.min_signers).is_err());
+        let mut moved = dual.clone();
+        moved.attestation.version = ProtocolVersion(3);
+        assert!(moved.verify(&out.public_key_package, cfg.min_signers).is_err());
+        let mut abi = dual.attestation.to_abi();
+        abi[29] = 1;
+        assert!(RoundAttestation::from_abi(&abi).is_err());
+
+        // the signature does not carry over to another certificate
+        let other = run_ddh_dvrf_cert(&DvrfInput::new(b"M'".to_vec()), &out.key_packages, &out.public_key_package, &ids[..3]);
+        let swapped = DualOutput { cert: other, ..dual.clone() };
+        assert!(swapped.verify(&out.public_key_package, cfg.min_signers).is_err());
+
+        // signed layout as documented; the ABI signed as an application payload does not verify
+        let signed = dual.attestation.message().to_signing_bytes();
+        assert_eq!(&signed[..42], b"\x17frostlab/attestation/v1\x11round-attestation");
+        assert_eq!(&signed[42..], &dual.attestation.to_abi()[..]);
+        let relabeled = DualOutput {
+            signature: frost_sign(&AttestationMessage::new(dual.attestation.to_abi()), &out, &[ids[0], ids[2], ids[4]], &mut OsRng)?,
+            ..dual
+        };
+        assert!(relabeled.verify(&out.public_key_package, cfg.min_signers).is_err());
+        Ok(())
+    }
+}
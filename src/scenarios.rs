@@ -0,0 +1,131 @@
+//! The demo flows as library calls: each scenario runs a fresh DKG, drives the protocol end to
+//! end and returns everything it produced, already verified. The `examples/` binaries and the
+//! demo binary only print these results.
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::beacon::{produce_round_with_rng, BeaconRound};
+use crate::cert::QuorumCert;
+use crate::committee::{CommitteeInfo, CommitteeReport};
+use crate::dkg::{run_dealerless_dkg, DkgConfig, DkgOutput};
+use crate::encoding::hex0x;
+use crate::frost_ext::frost_verify;
+use crate::lagrange::{coefficients_abi, lagrange_coefficients};
+use crate::messages::DvrfInput;
+use crate::round_attestation::DualOutput;
+use crate::selection::{run_dual_round_selected, run_dvrf_round_with_rng, SignerSelection};
+
+/// DVRF input of the full demo
+pub const DEMO_INPUT: &[u8] = b"dvrfddhhello";
+
+/// 4-of-5, as in the demo binary and the bench
+pub fn demo_config() -> DkgConfig {
+    DkgConfig { max_signers: 5, min_signers: 4 }
+}
+
+/// DKG → DVRF over a random quorum → dual-output round (epoch 1, round 1) over the first t
+pub struct FullDemo {
+    pub dkg: DkgOutput,
+    pub committee: CommitteeInfo,
+    pub cert: QuorumCert,
+    pub dual: DualOutput,
+}
+
+pub fn full_demo(cfg: DkgConfig) -> Result<FullDemo> {
+    full_demo_with_rng(cfg, &mut OsRng)
+}
+
+pub fn full_demo_with_rng<R: RngCore + CryptoRng>(cfg: DkgConfig, rng: &mut R) -> Result<FullDemo> {
+    let dkg = run_dealerless_dkg(cfg, rng)?;
+    let committee = dkg.committee_info()?;
+    let msg = DvrfInput::new(DEMO_INPUT.to_vec());
+
+    let quorum = SignerSelection::Random(&mut *rng).select(&dkg.all_ids(), cfg.min_signers)?;
+    let cert = run_dvrf_round_with_rng(&msg, &dkg, SignerSelection::Explicit(quorum), rng)?;
+    cert.verify(&dkg.public_key_package, cfg.min_signers)?;
+
+    let dual = run_dual_round_selected(1, 1, &msg, &dkg, SignerSelection::FirstT, rng)?;
+    if !frost_verify(&dual.attestation.message(), &dual.signature, &dkg)? {
+        bail!("group signature on the round attestation does not verify");
+    }
+    dual.verify(&dkg.public_key_package, cfg.min_signers)?;
+    Ok(FullDemo { dkg, committee, cert, dual })
+}
+
+/// Beacon rounds 1..=n, each from a VRF-seeded quorum drawn from the previous round's value
+pub struct BeaconDemo {
+    pub committee: CommitteeInfo,
+    pub rounds: Vec<BeaconRound>,
+}
+
+pub fn beacon_demo(rounds: u64) -> Result<BeaconDemo> {
+    beacon_demo_with_rng(rounds, &mut OsRng)
+}
+
+pub fn beacon_demo_with_rng<R: RngCore + CryptoRng>(rounds: u64, rng: &mut R) -> Result<BeaconDemo> {
+    let cfg = demo_config();
+    let dkg = run_dealerless_dkg(cfg, rng)?;
+    let ids = dkg.all_ids();
+    let mut out = Vec::with_capacity(rounds as usize);
+    let mut seed = [0u8; 32];
+    for round in 1..=rounds {
+        let signers = SignerSelection::VrfSeeded(seed).select(&ids, cfg.min_signers)?;
+        let r = produce_round_with_rng(round, &dkg.key_packages, &dkg.public_key_package, &signers, rng);
+        r.verify(&dkg.public_key_package, cfg.min_signers)?;
+        seed = r.randomness();
+        out.push(r);
+    }
+    Ok(BeaconDemo { committee: dkg.committee_info()?, rounds: out })
+}
+
+/// What a contract needs to consume one dual-output round, 0x-hex throughout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmExport {
+    pub committee: CommitteeReport,
+    /// abi.encode of the round attestation, the signed message
+    pub attestation_abi: String,
+    /// R (compressed) || z
+    pub signature: String,
+    pub randomness: String,
+    /// abi.encode(uint256[] identifiers, uint256[] coefficients) for the contributors
+    pub lagrange_abi: String,
+}
+
+pub fn evm_export_demo() -> Result<EvmExport> {
+    evm_export_demo_with_rng(&mut OsRng)
+}
+
+pub fn evm_export_demo_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<EvmExport> {
+    let demo = full_demo_with_rng(demo_config(), rng)?;
+    let contributors: Vec<_> = demo.dual.cert.partials.iter().map(|p| p.id).collect();
+    Ok(EvmExport {
+        committee: demo.committee.to_report(),
+        attestation_abi: hex0x(&demo.dual.attestation.to_abi()),
+        signature: hex0x(&demo.dual.signature.serialize()?),
+        randomness: hex0x(&demo.dual.attestation.vrf_output),
+        lagrange_abi: hex0x(&coefficients_abi(&lagrange_coefficients(&contributors)?)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenarios() -> Result<()> {
+        let demo = full_demo(DkgConfig::new(4, 3)?)?;
+        assert_eq!(demo.cert.partials.len(), 3);
+        assert_eq!(demo.cert.randomness(), demo.dual.cert.randomness());
+        assert_eq!((demo.committee.min_signers, demo.committee.max_signers), (3, 4));
+
+        let beacon = beacon_demo(3)?;
+        assert_eq!(beacon.rounds.iter().map(|r| r.round).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let export = evm_export_demo()?;
+        assert_eq!(export.signature.len(), 2 + 2 * 65);
+        assert_eq!(export.committee.members.len(), 5);
+        Ok(())
+    }
+}
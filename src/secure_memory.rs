@@ -0,0 +1,172 @@
+//! Key material in mlock'ed, zeroized-on-drop buffers (`secure-memory` feature).
+//!
+//! The serialized key package and every preprocessed FROST nonce pair live in page-locked
+//! buffers so they are never written to swap; they are only deserialized inside the `with_*`
+//! accessors and the temporary is wiped when the closure returns. Values the closure copies
+//! onto the stack cannot be locked — keep the closures short and do not let secrets escape
+//! them.
+//!
+//! Each buffer is a `memsec::malloc_sized` allocation: its own pages between guard pages.
+//! mlock/munlock act on whole pages and do not nest, so buffers sharing a page would unlock
+//! each other on drop; separate pages rule that out.
+//!
+//! mlock is best effort: without CAP_IPC_LOCK / a large enough RLIMIT_MEMLOCK the buffer is
+//! still zeroized on drop, and `is_locked` reports false.
+
+use std::ptr::NonNull;
+
+use anyhow::{anyhow, Result};
+use frost_secp256k1_evm::round1::SigningNonces;
+use k256::Scalar;
+use zeroize::Zeroize;
+
+use crate::ddh_dvrf::scalar_from_keypackage;
+use crate::dkg::{Identifier, KeyPackage};
+
+pub struct LockedBuffer {
+    /// guard-paged allocation on pages of its own; None for empty data
+    buf: Option<NonNull<[u8]>>,
+    locked: bool,
+}
+
+// the allocation is owned exclusively and never written after construction
+unsafe impl Send for LockedBuffer {}
+unsafe impl Sync for LockedBuffer {}
+
+impl LockedBuffer {
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.is_empty() {
+            return Ok(Self { buf: None, locked: false });
+        }
+        let buf = unsafe { memsec::malloc_sized(data.len()) }.ok_or_else(|| anyhow!("secure allocation failed"))?;
+        // malloc_sized locks the pages but does not say whether it worked; asking again on
+        // pages nobody else uses is harmless
+        let locked = unsafe { memsec::mlock(buf.as_ptr() as *mut u8, data.len()) };
+        unsafe { (*buf.as_ptr()).copy_from_slice(data) };
+        Ok(Self { buf: Some(buf), locked })
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        match self.buf {
+            Some(buf) => unsafe { buf.as_ref() },
+            None => &[],
+        }
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        if let Some(buf) = self.buf {
+            // free munlocks and releases only this buffer's own pages
+            unsafe {
+                memsec::memzero(buf.as_ptr() as *mut u8, buf.len());
+                memsec::free(buf);
+            }
+        }
+    }
+}
+
+/// A key package kept serialized in locked memory
+pub struct SecureKeyPackage {
+    id: Identifier,
+    bytes: LockedBuffer,
+}
+
+impl SecureKeyPackage {
+    /// Moves `kp` into locked memory; the plain copy is zeroized
+    pub fn new(mut kp: KeyPackage) -> Result<Self> {
+        let id = *kp.identifier();
+        let ser = kp.serialize();
+        kp.zeroize();
+        let mut ser = ser?;
+        let bytes = LockedBuffer::from_slice(&ser);
+        ser.zeroize();
+        Ok(Self { id, bytes: bytes? })
+    }
+
+    pub fn id(&self) -> Identifier {
+        self.id
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.bytes.is_locked()
+    }
+
+    /// Scoped access to the key package; the deserialized copy is wiped afterwards
+    pub fn with_key_package<T>(&self, f: impl FnOnce(&KeyPackage) -> T) -> T {
+        let mut kp = KeyPackage::deserialize(self.bytes.as_slice()).expect("bytes written by SecureKeyPackage::new");
+        let out = f(&kp);
+        kp.zeroize();
+        out
+    }
+
+    /// Scoped access to the secret share scalar
+    pub fn with_secret_scalar<T>(&self, f: impl FnOnce(&Scalar) -> T) -> T {
+        self.with_key_package(|kp| {
+            let mut s = scalar_from_keypackage(kp);
+            let out = f(&s);
+            s.zeroize();
+            out
+        })
+    }
+}
+
+/// One preprocessed FROST nonce pair kept serialized in locked memory
+pub struct SecureNonces {
+    bytes: LockedBuffer,
+}
+
+impl SecureNonces {
+    /// Moves `nonces` into locked memory; the plain copy is zeroized when it drops
+    pub fn new(nonces: SigningNonces) -> Result<Self> {
+        let mut ser = nonces.serialize()?;
+        drop(nonces);
+        let bytes = LockedBuffer::from_slice(&ser);
+        ser.zeroize();
+        Ok(Self { bytes: bytes? })
+    }
+
+    /// Scoped access to the nonces; the deserialized copy is zeroized when it drops
+    pub fn with_nonces<T>(&self, f: impl FnOnce(&SigningNonces) -> T) -> T {
+        let nonces = SigningNonces::deserialize(self.bytes.as_slice()).expect("bytes written by SecureNonces::new");
+        f(&nonces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_secure_key_package_roundtrip() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let (id, kp) = out.key_packages.iter().next().unwrap();
+        let secure = SecureKeyPackage::new(kp.clone())?;
+        assert_eq!(secure.id(), *id);
+        assert!(secure.with_key_package(|k| k == kp));
+        assert_eq!(secure.with_secret_scalar(|s| *s), scalar_from_keypackage(kp));
+
+        let buf = LockedBuffer::from_slice(&[])?;
+        assert!(!buf.is_locked());
+        assert!(buf.as_slice().is_empty());
+
+        // dropping one buffer leaves the next one intact and locked as before
+        let a = LockedBuffer::from_slice(&[1u8; 40])?;
+        let b = LockedBuffer::from_slice(&[2u8; 40])?;
+        let b_locked = b.is_locked();
+        drop(a);
+        assert_eq!(b.as_slice(), &[2u8; 40]);
+        assert_eq!(b.is_locked(), b_locked);
+
+        let (nonces, _) = frost_secp256k1_evm::round1::commit(kp.signing_share(), &mut OsRng);
+        let secure = SecureNonces::new(nonces.clone())?;
+        assert!(secure.with_nonces(|n| *n == nonces));
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Which t identifiers take part in a round.
+//!
+//! The round runners used to take the first t identifiers, so the Lagrange path only ever saw
+//! {1..t}. `SignerSelection` lets tests and deployments pick the subset explicitly, at random,
+//! or deterministically from a previous VRF output (every node derives the same set).
+
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::cert::QuorumCert;
+use crate::ddh_dvrf::run_ddh_dvrf_cert_with_rng;
+use crate::dkg::{DkgOutput, Identifier};
+use crate::messages::DvrfInput;
+use crate::round_attestation::{run_dual_round, DualOutput};
+use crate::utils::keccak256;
+
+pub enum SignerSelection<'a> {
+    /// lowest t identifiers
+    FirstT,
+    /// uniform t-subset drawn from the RNG
+    Random(&'a mut dyn RngCore),
+    /// t-subset derived from a 32-byte seed, e.g. the previous round's randomness
+    VrfSeeded([u8; 32]),
+    /// exactly these signers (at least t, all known)
+    Explicit(Vec<Identifier>),
+}
+
+/// Fisher–Yates over `ids`, first `t` kept
+fn shuffle_take(mut ids: Vec<Identifier>, t: usize, mut next: impl FnMut() -> u64) -> Vec<Identifier> {
+    for i in (1..ids.len()).rev() {
+        // rejection sampling keeps the draw unbiased
+        let bound = (i + 1) as u64;
+        let zone = u64::MAX - u64::MAX % bound;
+        let j = loop {
+            let x = next();
+            if x < zone {
+                break (x % bound) as usize;
+            }
+        };
+        ids.swap(i, j);
+    }
+    ids.truncate(t);
+    ids
+}
+
+impl SignerSelection<'_> {
+    /// Sorted signer set of size t (or the explicit set) out of `all`
+    pub fn select(self, all: &[Identifier], threshold: u16) -> Result<Vec<Identifier>> {
+        let t = threshold as usize;
+        let mut all: Vec<Identifier> = all.iter().copied().collect::<BTreeSet<_>>().into_iter().collect();
+        if all.len() < t || t == 0 {
+            bail!("cannot pick {} signers out of {}", t, all.len());
+        }
+        let mut chosen = match self {
+            SignerSelection::FirstT => {
+                all.truncate(t);
+                all
+            }
+            SignerSelection::Random(rng) => shuffle_take(all, t, || rng.next_u64()),
+            SignerSelection::VrfSeeded(seed) => {
+                let mut counter = 0u64;
+                shuffle_take(all, t, || {
+                    counter += 1;
+                    let h = keccak256(&[&b"frostlab/signer-selection"[..], &seed, &counter.to_be_bytes()].concat());
+                    u64::from_be_bytes(h[..8].try_into().expect("8 bytes"))
+                })
+            }
+            SignerSelection::Explicit(ids) => {
+                let set: BTreeSet<Identifier> = ids.iter().copied().collect();
+                if set.len() != ids.len() {
+                    bail!("duplicate signer in explicit selection");
+                }
+                if set.len() < t {
+                    bail!("need {} signers, got {}", t, set.len());
+                }
+                if !set.iter().all(|id| all.contains(id)) {
+                    bail!("explicit selection names an unknown signer");
+                }
+                set.into_iter().collect()
+            }
+        };
+        chosen.sort();
+        Ok(chosen)
+    }
+}
+
+/// DVRF round over the selected signers
+pub fn run_dvrf_round(msg: &DvrfInput, out: &DkgOutput, selection: SignerSelection) -> Result<QuorumCert> {
+    run_dvrf_round_with_rng(msg, out, selection, &mut OsRng)
+}
+
+/// `run_dvrf_round` with the proof nonces drawn from `rng`
+pub fn run_dvrf_round_with_rng<R: RngCore + CryptoRng>(
+    msg: &DvrfInput,
+    out: &DkgOutput,
+    selection: SignerSelection,
+    rng: &mut R,
+) -> Result<QuorumCert> {
+    let signers = selection.select(&out.all_ids(), out.min_signers())?;
+    Ok(run_ddh_dvrf_cert_with_rng(msg, &out.key_packages, &out.public_key_package, &signers, rng))
+}
+
+/// Dual-output round over the selected signers
+pub fn run_dual_round_selected<R: RngCore + CryptoRng>(
+    epoch: u64,
+    round: u64,
+    msg: &DvrfInput,
+    out: &DkgOutput,
+    selection: SignerSelection,
+    rng: &mut R,
+) -> Result<DualOutput> {
+    let signers = selection.select(&out.all_ids(), out.min_signers())?;
+    run_dual_round(epoch, round, msg, out, &signers, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_selections_verify_on_non_prefix_subsets() -> Result<()> {
+        let cfg = DkgConfig::new(6, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let ids = out.all_ids();
+        let msg = DvrfInput::new(b"subsets".to_vec());
+
+        let first = run_dvrf_round(&msg, &out, SignerSelection::FirstT)?;
+        let explicit = run_dvrf_round(&msg, &out, SignerSelection::Explicit(vec![ids[5], ids[1], ids[3]]))?;
+        let random = run_dvrf_round(&msg, &out, SignerSelection::Random(&mut OsRng))?;
+        for cert in [&first, &explicit, &random] {
+            cert.verify(&out.public_key_package, cfg.min_signers)?;
+            assert_eq!(cert.randomness(), first.randomness());
+        }
+        assert_eq!(explicit.partials.iter().map(|p| p.id).collect::<Vec<_>>(), vec![ids[1], ids[3], ids[5]]);
+
+        // every node derives the same set from the same seed
+        let seed = first.randomness();
+        let a = SignerSelection::VrfSeeded(seed).select(&ids, 3)?;
+        assert_eq!(a, SignerSelection::VrfSeeded(seed).select(&ids, 3)?);
+        assert_eq!(a.len(), 3);
+        let dual = run_dual_round_selected(1, 2, &msg, &out, SignerSelection::VrfSeeded(seed), &mut OsRng)?;
+        dual.verify(&out.public_key_package, cfg.min_signers)?;
+
+        assert!(SignerSelection::Explicit(vec![ids[0], ids[0], ids[1]]).select(&ids, 3).is_err());
+        assert!(SignerSelection::Explicit(ids[..2].to_vec()).select(&ids, 3).is_err());
+        Ok(())
+    }
+}
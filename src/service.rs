@@ -0,0 +1,314 @@
+//! Participant-side request handling with per-requester and global rate limits and a bound
+//! on concurrently open signing sessions, so a misbehaving coordinator cannot make a node
+//! burn CPU on partial evals or pile up nonces.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use frost::{round1, round2};
+
+use crate::cert::PartialEval;
+use crate::messages::DvrfInput;
+use crate::participant::{Participant, SessionId};
+
+/// Token bucket: `burst` tokens, refilled at `per_sec` (0 = never refills)
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_sec: f64,
+}
+
+impl RateLimit {
+    fn validate(&self, name: &str) -> Result<()> {
+        if self.burst == 0 {
+            bail!("{name} rate limit needs a burst of at least one");
+        }
+        if !self.per_sec.is_finite() || self.per_sec < 0.0 {
+            bail!("{name} refill rate must be finite and non-negative, got {}", self.per_sec);
+        }
+        Ok(())
+    }
+}
+
+/// How much a rejection tells the caller
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectionMode {
+    /// reason plus a retry-after hint
+    Detailed,
+    /// bare `Busy`, reveals nothing about limits or load
+    Opaque,
+}
+
+#[derive(Clone, Debug)]
+pub struct ServiceLimits {
+    pub per_requester: RateLimit,
+    pub global: RateLimit,
+    pub max_concurrent_sessions: usize,
+    /// requesters tracked at once; idle (fully refilled) buckets are dropped to make room
+    pub max_requesters: usize,
+    pub rejection: RejectionMode,
+}
+
+impl ServiceLimits {
+    pub fn validate(&self) -> Result<()> {
+        self.per_requester.validate("per-requester")?;
+        self.global.validate("global")?;
+        if self.max_requesters == 0 {
+            bail!("max_requesters must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl Default for ServiceLimits {
+    fn default() -> Self {
+        Self {
+            per_requester: RateLimit { burst: 20, per_sec: 10.0 },
+            global: RateLimit { burst: 200, per_sec: 100.0 },
+            max_concurrent_sessions: 64,
+            max_requesters: 4096,
+            rejection: RejectionMode::Detailed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    RequesterRateLimited { retry_after: Duration },
+    GlobalRateLimited { retry_after: Duration },
+    TooManySessions { max: usize },
+    /// every tracked requester is still throttled; a new one cannot be admitted yet
+    TooManyRequesters { max: usize },
+    Busy,
+}
+
+/// Either the answer or a rejection; protocol errors stay in the outer `Result`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response<T> {
+    Ok(T),
+    Rejected(Rejection),
+}
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, last: now }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let dt = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + dt * limit.per_sec).min(limit.burst as f64);
+        self.last = now;
+    }
+
+    /// time until one token is available (zero if available now); saturates for tiny rates
+    fn wait(&self, limit: &RateLimit) -> Duration {
+        if self.tokens >= 1.0 { return Duration::ZERO; }
+        if limit.per_sec <= 0.0 { return Duration::MAX; }
+        Duration::try_from_secs_f64((1.0 - self.tokens) / limit.per_sec).unwrap_or(Duration::MAX)
+    }
+
+    /// refilled to the burst: forgetting it changes nothing
+    fn is_idle(&mut self, limit: &RateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        self.tokens >= limit.burst as f64
+    }
+}
+
+pub struct ParticipantService {
+    participant: Participant,
+    limits: ServiceLimits,
+    global: Bucket,
+    requesters: BTreeMap<String, Bucket>,
+}
+
+impl ParticipantService {
+    pub fn new(participant: Participant, limits: ServiceLimits) -> Result<Self> {
+        limits.validate()?;
+        let global = Bucket::new(&limits.global, Instant::now());
+        Ok(Self { participant, limits, global, requesters: BTreeMap::new() })
+    }
+
+    /// Requesters currently holding a bucket
+    pub fn tracked_requesters(&self) -> usize {
+        self.requesters.len()
+    }
+
+    pub fn participant(&self) -> &Participant {
+        &self.participant
+    }
+
+    pub fn partial_eval(&mut self, requester: &str, session_id: SessionId, msg: &DvrfInput) -> Result<Response<PartialEval>> {
+        if let Some(r) = self.admit(requester, Instant::now()) {
+            return Ok(Response::Rejected(r));
+        }
+        Ok(Response::Ok(self.participant.partial_eval(session_id, msg)?))
+    }
+
+    /// Opening a new signing session also counts against `max_concurrent_sessions`
+    pub fn commit<R: RngCore + CryptoRng>(&mut self, requester: &str, session_id: SessionId, rng: &mut R) -> Result<Response<round1::SigningCommitments>> {
+        let is_new = !self.participant.has_signing_session(&session_id);
+        if is_new && self.participant.open_signing_sessions() >= self.limits.max_concurrent_sessions {
+            return Ok(Response::Rejected(self.shape(Rejection::TooManySessions { max: self.limits.max_concurrent_sessions })));
+        }
+        if let Some(r) = self.admit(requester, Instant::now()) {
+            return Ok(Response::Rejected(r));
+        }
+        Ok(Response::Ok(self.participant.commit(session_id, rng)?))
+    }
+
+    pub fn sign(&mut self, requester: &str, session_id: SessionId, signing_package: &frost::SigningPackage) -> Result<Response<round2::SignatureShare>> {
+        if let Some(r) = self.admit(requester, Instant::now()) {
+            return Ok(Response::Rejected(r));
+        }
+        Ok(Response::Ok(self.participant.sign(session_id, signing_package)?))
+    }
+
+    /// Free the slot of a session the coordinator abandoned
+    pub fn abort(&mut self, session_id: &SessionId) {
+        self.participant.abort_signing(session_id);
+    }
+
+    /// Take one token from the requester and the global bucket, or say why not.
+    /// Tokens are only consumed when both buckets allow the request.
+    fn admit(&mut self, requester: &str, now: Instant) -> Option<Rejection> {
+        let limits = &self.limits;
+        if !self.requesters.contains_key(requester) && self.requesters.len() >= limits.max_requesters {
+            self.requesters.retain(|_, b| !b.is_idle(&limits.per_requester, now));
+            if self.requesters.len() >= limits.max_requesters {
+                return Some(self.shape(Rejection::TooManyRequesters { max: limits.max_requesters }));
+            }
+        }
+        let bucket = self.requesters
+            .entry(requester.to_string())
+            .or_insert_with(|| Bucket::new(&limits.per_requester, now));
+        bucket.refill(&limits.per_requester, now);
+        self.global.refill(&limits.global, now);
+
+        let wait = bucket.wait(&limits.per_requester);
+        if wait > Duration::ZERO {
+            return Some(self.shape(Rejection::RequesterRateLimited { retry_after: wait }));
+        }
+        let wait = self.global.wait(&limits.global);
+        if wait > Duration::ZERO {
+            return Some(self.shape(Rejection::GlobalRateLimited { retry_after: wait }));
+        }
+        bucket.tokens -= 1.0;
+        self.global.tokens -= 1.0;
+        None
+    }
+
+    fn shape(&self, r: Rejection) -> Rejection {
+        match self.limits.rejection {
+            RejectionMode::Detailed => r,
+            RejectionMode::Opaque => Rejection::Busy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::participant::participants_from_dkg;
+
+    fn service(limits: ServiceLimits) -> Result<ParticipantService> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let p = participants_from_dkg(&out)?.into_values().next().unwrap();
+        ParticipantService::new(p, limits)
+    }
+
+    #[test]
+    fn test_rate_limits() -> Result<()> {
+        // no refill: limits are pure bursts
+        let mut svc = service(ServiceLimits {
+            per_requester: RateLimit { burst: 2, per_sec: 0.0 },
+            global: RateLimit { burst: 3, per_sec: 0.0 },
+            ..ServiceLimits::default()
+        })?;
+
+        assert!(matches!(svc.partial_eval("a", [1; 32], &DvrfInput::new(b"m1".to_vec()))?, Response::Ok(_)));
+        assert!(matches!(svc.partial_eval("a", [2; 32], &DvrfInput::new(b"m2".to_vec()))?, Response::Ok(_)));
+        assert!(matches!(
+            svc.partial_eval("a", [3; 32], &DvrfInput::new(b"m3".to_vec()))?,
+            Response::Rejected(Rejection::RequesterRateLimited { .. })
+        ));
+        // other requester still has budget, until the global bucket runs dry
+        assert!(matches!(svc.partial_eval("b", [4; 32], &DvrfInput::new(b"m4".to_vec()))?, Response::Ok(_)));
+        assert!(matches!(
+            svc.partial_eval("b", [5; 32], &DvrfInput::new(b"m5".to_vec()))?,
+            Response::Rejected(Rejection::GlobalRateLimited { .. })
+        ));
+        assert_eq!(svc.participant().audit_log().entries().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_bound_and_opaque_rejection() -> Result<()> {
+        let mut rng = OsRng;
+        let mut svc = service(ServiceLimits {
+            max_concurrent_sessions: 2,
+            rejection: RejectionMode::Opaque,
+            ..ServiceLimits::default()
+        })?;
+
+        assert!(matches!(svc.commit("a", [1; 32], &mut rng)?, Response::Ok(_)));
+        assert!(matches!(svc.commit("a", [2; 32], &mut rng)?, Response::Ok(_)));
+        assert_eq!(svc.commit("a", [3; 32], &mut rng)?, Response::Rejected(Rejection::Busy));
+        // re-asking for an open session is not a new session
+        assert!(matches!(svc.commit("b", [1; 32], &mut rng)?, Response::Ok(_)));
+
+        svc.abort(&[1; 32]);
+        assert!(matches!(svc.commit("a", [3; 32], &mut rng)?, Response::Ok(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_requester_map_is_bounded() -> Result<()> {
+        let limits = ServiceLimits {
+            per_requester: RateLimit { burst: 1, per_sec: 1.0 },
+            max_requesters: 2,
+            ..ServiceLimits::default()
+        };
+        let mut svc = service(limits.clone())?;
+        let t0 = Instant::now();
+        assert!(svc.admit("a", t0).is_none());
+        assert!(svc.admit("b", t0).is_none());
+        // both still throttled: a third requester is turned away, not tracked
+        assert_eq!(svc.admit("c", t0), Some(Rejection::TooManyRequesters { max: 2 }));
+        assert_eq!(svc.tracked_requesters(), 2);
+        // once their buckets refill they are forgotten
+        assert!(svc.admit("c", t0 + Duration::from_secs(2)).is_none());
+        assert_eq!(svc.tracked_requesters(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits_validated() -> Result<()> {
+        let bad = |per_requester| ServiceLimits { per_requester, ..ServiceLimits::default() }.validate().is_err();
+        assert!(bad(RateLimit { burst: 0, per_sec: 1.0 }));
+        assert!(bad(RateLimit { burst: 1, per_sec: -1.0 }));
+        assert!(bad(RateLimit { burst: 1, per_sec: f64::NAN }));
+        assert!(bad(RateLimit { burst: 1, per_sec: f64::INFINITY }));
+        assert!(service(ServiceLimits { max_requesters: 0, ..ServiceLimits::default() }).is_err());
+
+        // a rate so small the wait overflows a Duration saturates instead of panicking
+        let mut svc = service(ServiceLimits {
+            per_requester: RateLimit { burst: 1, per_sec: 1e-300 },
+            ..ServiceLimits::default()
+        })?;
+        let now = Instant::now();
+        assert!(svc.admit("a", now).is_none());
+        assert_eq!(svc.admit("a", now), Some(Rejection::RequesterRateLimited { retry_after: Duration::MAX }));
+        Ok(())
+    }
+}
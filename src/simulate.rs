@@ -0,0 +1,225 @@
+//! Dry-run of the full DVRF-then-Sign pipeline (DKG > DDH-DVRF > FROST) for capacity planning.
+//! Everything runs in-process; message sizes are the serialized sizes that would go on the wire.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use frost::{round1, round2};
+use serde::Serialize;
+
+use crate::dkg::{run_dealerless_dkg, DkgConfig};
+use crate::messages::{AttestationMessage, DvrfInput};
+use crate::ddh_dvrf::{id_as_u64, scalar_from_keypackage, validated_vk_share};
+use crate::utils::{lagrange_combine_points, prove_eq_with_rng, verify_eq, Proof};
+use crate::version::ProtocolVersion;
+
+/// Simulation parameters
+#[derive(Clone, Debug, Serialize)]
+pub struct SimConfig {
+    pub max_signers: u16,
+    pub min_signers: u16,
+    /// message sizes (bytes) to run each round with
+    pub msg_sizes: Vec<usize>,
+    /// rounds per message size
+    pub rounds: usize,
+}
+
+/// Timing of one phase over all runs (microseconds)
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PhaseStats {
+    pub runs: usize,
+    pub total_us: u128,
+    pub min_us: u128,
+    pub max_us: u128,
+    pub mean_us: u128,
+}
+
+impl PhaseStats {
+    fn record(&mut self, d: Duration) {
+        let us = d.as_micros();
+        if self.runs == 0 || us < self.min_us { self.min_us = us; }
+        if us > self.max_us { self.max_us = us; }
+        self.runs += 1;
+        self.total_us += us;
+        self.mean_us = self.total_us / self.runs as u128;
+    }
+}
+
+/// Bytes exchanged by a phase, split per message kind
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SizeStats {
+    /// number of point-to-point / broadcast messages
+    pub messages: usize,
+    pub total_bytes: usize,
+    pub by_kind: BTreeMap<String, usize>,
+}
+
+impl SizeStats {
+    fn add(&mut self, kind: &str, count: usize, bytes_each: usize) {
+        self.messages += count;
+        self.total_bytes += count * bytes_each;
+        *self.by_kind.entry(kind.to_string()).or_default() += count * bytes_each;
+    }
+}
+
+/// Per message-size results
+#[derive(Clone, Debug, Serialize)]
+pub struct MsgSizeReport {
+    pub msg_size: usize,
+    pub dvrf: PhaseStats,
+    pub frost_sign: PhaseStats,
+    pub frost_verify: PhaseStats,
+    /// traffic of one DVRF round
+    pub dvrf_traffic: SizeStats,
+    /// traffic of one FROST signing round
+    pub frost_traffic: SizeStats,
+}
+
+/// Simulation output
+#[derive(Clone, Debug, Serialize)]
+pub struct SimReport {
+    pub config: SimConfig,
+    pub dkg: PhaseStats,
+    pub dkg_traffic: SizeStats,
+    pub per_msg_size: Vec<MsgSizeReport>,
+}
+
+impl SimReport {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Run DKG once, then `rounds` DVRF + FROST rounds for each message size.
+/// The first t identifiers are used as the signing quorum.
+pub fn simulate<R: RngCore + CryptoRng>(cfg: &SimConfig, rng: &mut R) -> Result<SimReport> {
+    let dkg_cfg = DkgConfig::new(cfg.max_signers, cfg.min_signers)?;
+    if cfg.rounds == 0 { bail!("rounds must be >= 1"); }
+    let n = cfg.max_signers as usize;
+    let t = cfg.min_signers as usize;
+
+    // --- DKG
+    let mut dkg = PhaseStats::default();
+    let start = Instant::now();
+    let out = run_dealerless_dkg(dkg_cfg, &mut *rng)?;
+    dkg.record(start.elapsed());
+
+    let any_kp = out.key_packages.values().next().expect("at least one key package");
+    let sample_id = *any_kp.identifier();
+    let (_, r1_pkg) = frost::keys::dkg::part1(sample_id, cfg.max_signers, cfg.min_signers, &mut *rng)?;
+    let r2_pkg = frost::keys::dkg::round2::Package::new(*any_kp.signing_share());
+
+    let mut dkg_traffic = SizeStats::default();
+    // round 1: broadcast to n-1 peers; round 2: one package per ordered pair
+    dkg_traffic.add("round1_package", n * (n - 1), r1_pkg.serialize()?.len());
+    dkg_traffic.add("round2_package", n * (n - 1), r2_pkg.serialize()?.len());
+
+    let ids = out.all_ids();
+    let signers = &ids[..t];
+
+    let mut per_msg_size = Vec::new();
+    for &msg_size in &cfg.msg_sizes {
+        let mut msg = vec![0u8; msg_size];
+        let mut dvrf = PhaseStats::default();
+        let mut frost_sign = PhaseStats::default();
+        let mut frost_verify = PhaseStats::default();
+        let mut dvrf_traffic = SizeStats::default();
+        let mut frost_traffic = SizeStats::default();
+
+        for round in 0..cfg.rounds {
+            rng.fill_bytes(&mut msg);
+            let input = DvrfInput::new(msg.clone());
+            let attestation = AttestationMessage::new(msg.clone()).to_signing_bytes();
+
+            // --- DDH-DVRF: request, partial evals + proofs, verification, combine
+            let start = Instant::now();
+            let mut points = Vec::with_capacity(t);
+            for id in signers {
+                let sk_i = scalar_from_keypackage(&out.key_packages[id]);
+                let vk_i = validated_vk_share(&out.public_key_package, *id)?;
+                let (v_i, proof) = prove_eq_with_rng(ProtocolVersion::CURRENT, &input, *vk_i, sk_i, &mut *rng)?;
+                if !verify_eq(ProtocolVersion::CURRENT, &input, &vk_i, &v_i, &proof) {
+                    bail!("partial eval of id={} failed verification", id_as_u64(*id));
+                }
+                points.push((id_as_u64(*id), *v_i));
+            }
+            let _v = lagrange_combine_points(&points);
+            dvrf.record(start.elapsed());
+
+            // --- FROST round 1 + 2 + aggregate
+            let start = Instant::now();
+            let mut nonces_map = BTreeMap::new();
+            let mut commits_map = BTreeMap::new();
+            for id in signers {
+                let (nonces, commitments) = round1::commit(out.key_packages[id].signing_share(), &mut *rng);
+                nonces_map.insert(*id, nonces);
+                commits_map.insert(*id, commitments);
+            }
+            let signing_pkg = frost::SigningPackage::new(commits_map.clone(), &attestation);
+            let mut shares = BTreeMap::new();
+            for (id, nonces) in &nonces_map {
+                shares.insert(*id, round2::sign(&signing_pkg, nonces, &out.key_packages[id])?);
+            }
+            let sig = frost::aggregate(&signing_pkg, &shares, &out.public_key_package)?;
+            frost_sign.record(start.elapsed());
+
+            let start = Instant::now();
+            out.public_key_package.verifying_key().verify(&attestation, &sig)?;
+            frost_verify.record(start.elapsed());
+
+            if round == 0 {
+                let id_len = sample_id.serialize().len();
+                dvrf_traffic.add("eval_request", t, msg_size);
+                dvrf_traffic.add("partial_eval", t, id_len + 33 + Proof::BYTES);
+
+                let commit_len = commits_map.values().next().expect("t >= 2").serialize()?.len();
+                let share_len = shares.values().next().expect("t >= 2").serialize().len();
+                frost_traffic.add("commitments", t, commit_len);
+                frost_traffic.add("signing_package", t, signing_pkg.serialize()?.len());
+                frost_traffic.add("signature_share", t, share_len);
+                frost_traffic.add("signature", 1, sig.serialize()?.len());
+            }
+        }
+
+        per_msg_size.push(MsgSizeReport {
+            msg_size,
+            dvrf,
+            frost_sign,
+            frost_verify,
+            dvrf_traffic,
+            frost_traffic,
+        });
+    }
+
+    Ok(SimReport { config: cfg.clone(), dkg, dkg_traffic, per_msg_size })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+
+    #[test]
+    fn test_simulate_report() -> Result<()> {
+        let cfg = SimConfig { max_signers: 5, min_signers: 3, msg_sizes: vec![32, 1024], rounds: 2 };
+        let report = simulate(&cfg, &mut OsRng)?;
+
+        assert_eq!(report.dkg.runs, 1);
+        assert_eq!(report.dkg_traffic.messages, 2 * 5 * 4);
+        assert_eq!(report.per_msg_size.len(), 2);
+        for r in &report.per_msg_size {
+            assert_eq!(r.dvrf.runs, 2);
+            assert_eq!(r.frost_sign.runs, 2);
+            assert_eq!(r.dvrf_traffic.by_kind["eval_request"], 3 * r.msg_size);
+        }
+        // bigger messages → bigger signing packages
+        assert!(report.per_msg_size[1].frost_traffic.total_bytes > report.per_msg_size[0].frost_traffic.total_bytes);
+
+        let json = report.to_json()?;
+        assert!(json.contains("\"dkg_traffic\""));
+        Ok(())
+    }
+}
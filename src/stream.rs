@@ -0,0 +1,108 @@
+//! Streaming front-end for high-volume randomness requests (feature `stream`).
+//!
+//! Requests are pulled from the input stream in chunks of at most `batch_size` (whatever is
+//! ready), and each chunk is evaluated under a single acquisition of the participants lock.
+//! Nothing more is pulled from upstream until the chunk's outputs have been yielded. Outputs
+//! come back in request order, each one a verified certificate.
+//!
+//! Evaluation is synchronous and runs on whichever task polls the stream: there is no
+//! concurrency between chunks. On an async runtime, poll it from a blocking context
+//! (e.g. a `spawn_blocking` task) so the DVRF work does not stall the executor.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use anyhow::{bail, Result};
+use futures::{Stream, StreamExt};
+
+use crate::cert::QuorumCert;
+use crate::coordinator::{derive_session_id, Coordinator, SessionStore};
+use crate::dkg::Identifier;
+use crate::messages::DvrfInput;
+use crate::participant::Participant;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DvrfRequest {
+    pub epoch: u64,
+    pub input: DvrfInput,
+}
+
+/// Verified result for one request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DvrfOutput {
+    pub epoch: u64,
+    pub cert: QuorumCert,
+}
+
+impl<S: SessionStore> Coordinator<S> {
+    /// One output per request, in order. A failed request yields an `Err` item, the stream goes on.
+    /// Each chunk of up to `batch_size` ready requests is evaluated while the stream is polled.
+    pub fn dvrf_stream<'a, St>(
+        &'a self,
+        requests: St,
+        participants: &'a Mutex<BTreeMap<Identifier, Participant>>,
+        signers: Vec<Identifier>,
+        batch_size: usize,
+    ) -> Result<impl Stream<Item = Result<DvrfOutput>> + 'a>
+    where
+        St: Stream<Item = DvrfRequest> + 'a,
+    {
+        if batch_size == 0 {
+            bail!("batch_size must be positive");
+        }
+        Ok(requests
+            .ready_chunks(batch_size)
+            .map(move |batch| {
+                let mut parts = participants.lock().expect("participants lock");
+                batch
+                    .into_iter()
+                    .map(|req| {
+                        let sid = derive_session_id(b"dvrf", req.epoch, &req.input.to_hash_input());
+                        let cert = self.run_dvrf(sid, &req.input, &mut parts, &signers)?;
+                        Ok(DvrfOutput { epoch: req.epoch, cert })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .flat_map(futures::stream::iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use futures::executor::block_on;
+    use crate::coordinator::MemorySessionStore;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::participant::participants_from_dkg;
+
+    #[test]
+    fn test_stream_in_order_with_bounded_pull() -> Result<()> {
+        let cfg = DkgConfig::new(4, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let parts = Mutex::new(participants_from_dkg(&out)?);
+        let coord = Coordinator::new(Arc::new(MemorySessionStore::default()), out.public_key_package.clone(), cfg.min_signers);
+        let signers = out.all_ids()[..3].to_vec();
+
+        let pulled = AtomicUsize::new(0);
+        let requests = futures::stream::iter((0..40u64).map(|i| DvrfRequest { epoch: i, input: DvrfInput::new(i.to_be_bytes().to_vec()) }))
+            .inspect(|_| { pulled.fetch_add(1, Ordering::SeqCst); });
+        let mut s = Box::pin(coord.dvrf_stream(requests, &parts, signers, 4)?);
+
+        let first = block_on(s.next()).expect("one output")?;
+        assert_eq!(first.epoch, 0);
+        // only the first chunk was pulled before the first output
+        assert!(pulled.load(Ordering::SeqCst) <= 4);
+
+        let rest: Vec<_> = block_on(s.collect::<Vec<_>>());
+        assert_eq!(rest.len(), 39);
+        for (k, o) in rest.into_iter().enumerate() {
+            let o = o?;
+            assert_eq!(o.epoch, k as u64 + 1);
+            o.cert.verify(&out.public_key_package, cfg.min_signers)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+//! Second threshold level: a participant's share s_i Shamir-split across their own devices
+//! (laptop, phone, backup …) with a local threshold t'.
+//!
+//! - DVRF partials are MPC-combined: each device contributes s_ij·PH and its part of the DLEQ
+//!   proof, so s_i is never reassembled. The result is an ordinary `PartialEval` under
+//!   `ProtocolVersion::CURRENT`.
+//! - FROST signing reassembles s_i from t' devices into a transient `KeyPackage`.
+//!
+//! Feldman commitments to the device polynomial let every device check its sub-share, and
+//! the constant term equals vk_i, so a split participant stays bound to its DKG share.
+
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::cert::PartialEval;
+use crate::ddh_dvrf::{scalar_from_keypackage, vk_share_from_public_pkg};
+use crate::dkg::{Identifier, KeyPackage, PublicKeyPackage};
+use crate::messages::DvrfInput;
+use crate::utils::{challenge_keccak, dvrf_base_point, Proof};
+use crate::validated::ValidatedPoint;
+use crate::version::ProtocolVersion;
+
+/// Sub-share f_i(index) held by one device; no `Debug`, so it cannot end up in logs
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DeviceShare {
+    pub owner: Identifier,
+    pub index: u16,
+    pub value: Scalar,
+}
+
+/// Public description of a split: local threshold and Feldman commitments (C_0 = vk_i)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitShareInfo {
+    pub owner: Identifier,
+    pub device_threshold: u16,
+    pub commitments: Vec<ProjectivePoint>,
+}
+
+/// One-time DLEQ nonce of a device; consumed by `eval_respond`
+pub struct DeviceNonce {
+    index: u16,
+    r: Scalar,
+}
+
+/// Device's first message in a distributed partial evaluation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceEvalCommitment {
+    pub index: u16,
+    pub v_j: ProjectivePoint,
+    pub com1: ProjectivePoint,
+    pub com2: ProjectivePoint,
+}
+
+/// Split the share in `kp` into `devices` sub-shares, any `device_threshold` of which rebuild it
+pub fn split_share<R: RngCore + CryptoRng>(
+    kp: &KeyPackage,
+    devices: u16,
+    device_threshold: u16,
+    rng: &mut R,
+) -> Result<(SplitShareInfo, Vec<DeviceShare>)> {
+    if device_threshold == 0 || device_threshold > devices {
+        bail!("invalid device threshold {} of {}", device_threshold, devices);
+    }
+    let mut coeffs = vec![scalar_from_keypackage(kp)];
+    coeffs.extend((1..device_threshold).map(|_| Scalar::random(&mut *rng)));
+    let shares = (1..=devices)
+        .map(|index| DeviceShare { owner: *kp.identifier(), index, value: eval_poly(&coeffs, index) })
+        .collect();
+    let info = SplitShareInfo {
+        owner: *kp.identifier(),
+        device_threshold,
+        commitments: coeffs.iter().map(|c| ProjectivePoint::GENERATOR * c).collect(),
+    };
+    Ok((info, shares))
+}
+
+fn eval_poly(coeffs: &[Scalar], x: u16) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::ZERO, |acc, c| acc * Scalar::from(x as u64) + c)
+}
+
+/// λ_j at 0 over the device indices in `set`
+fn device_lambda(index: u16, set: &[u16]) -> Scalar {
+    let (mut num, mut den) = (Scalar::ONE, Scalar::ONE);
+    for j in set.iter().filter(|j| **j != index) {
+        num *= Scalar::from(*j as u64);
+        den *= Scalar::from(*j as u64) - Scalar::from(index as u64);
+    }
+    num * den.invert().expect("distinct device indices")
+}
+
+/// Exactly `device_threshold` distinct, non-zero indices
+fn check_device_set(info: &SplitShareInfo, indices: &[u16]) -> Result<()> {
+    if info.commitments.len() != info.device_threshold as usize {
+        bail!("split has {} commitments for device threshold {}", info.commitments.len(), info.device_threshold);
+    }
+    let distinct: BTreeSet<_> = indices.iter().collect();
+    if distinct.len() != indices.len() || indices.contains(&0) {
+        bail!("device indices must be distinct and non-zero");
+    }
+    if indices.len() != info.device_threshold as usize {
+        bail!("need exactly {} devices, got {}", info.device_threshold, indices.len());
+    }
+    Ok(())
+}
+
+impl SplitShareInfo {
+    /// C_0 = vk_i
+    pub fn verifying_share(&self) -> Result<ProjectivePoint> {
+        self.commitments.first().copied().ok_or_else(|| anyhow!("split has no commitments"))
+    }
+
+    /// Feldman check: G·s_ij == Σ C_k·index^k
+    pub fn verify_device_share(&self, ds: &DeviceShare) -> Result<()> {
+        if ds.owner != self.owner || ds.index == 0 {
+            bail!("device share does not belong to this split");
+        }
+        let x = Scalar::from(ds.index as u64);
+        let expected = self.commitments.iter().rev().fold(ProjectivePoint::IDENTITY, |acc, c| acc * x + c);
+        if ProjectivePoint::GENERATOR * ds.value != expected {
+            bail!("device share {} fails its commitment check", ds.index);
+        }
+        Ok(())
+    }
+
+    /// Rebuild s_i from `device_threshold` device shares
+    pub fn reassemble(&self, devices: &[DeviceShare]) -> Result<Scalar> {
+        let indices: Vec<u16> = devices.iter().map(|d| d.index).collect();
+        check_device_set(self, &indices)?;
+        for d in devices {
+            self.verify_device_share(d)?;
+        }
+        Ok(devices.iter().map(|d| d.value * device_lambda(d.index, &indices)).sum())
+    }
+
+    /// Combine the device commitments: (λ per device, v_i, challenge). Devices run the same
+    /// computation before answering, so a combiner cannot make them respond to anything else.
+    fn combine_eval(&self, msg: &DvrfInput, commitments: &[DeviceEvalCommitment]) -> Result<(Vec<Scalar>, ValidatedPoint, Scalar)> {
+        let indices: Vec<u16> = commitments.iter().map(|c| c.index).collect();
+        check_device_set(self, &indices)?;
+        let lambdas: Vec<Scalar> = indices.iter().map(|i| device_lambda(*i, &indices)).collect();
+        let v_i: ProjectivePoint = commitments.iter().zip(&lambdas).map(|(c, l)| c.v_j * l).sum();
+        let com1: ProjectivePoint = commitments.iter().map(|c| c.com1).sum();
+        let com2: ProjectivePoint = commitments.iter().map(|c| c.com2).sum();
+        let v_i = ValidatedPoint::new(v_i)?;
+        let ch = challenge_keccak(ProtocolVersion::CURRENT, &ProjectivePoint::GENERATOR, &dvrf_base_point(msg), &self.verifying_share()?, &v_i, &com1, &com2);
+        Ok((lambdas, v_i, ch))
+    }
+
+    /// Assemble the device responses into the participant's partial evaluation
+    pub fn combine_partial(
+        &self,
+        msg: &DvrfInput,
+        commitments: &[DeviceEvalCommitment],
+        responses: &[Scalar],
+    ) -> Result<PartialEval> {
+        if responses.len() != commitments.len() {
+            bail!("expected {} device responses, got {}", commitments.len(), responses.len());
+        }
+        let (_, v_i, ch) = self.combine_eval(msg, commitments)?;
+        Ok(PartialEval { version: ProtocolVersion::CURRENT, id: self.owner, v_i, proof: Proof { ch, rs: responses.iter().sum() } })
+    }
+}
+
+impl DeviceShare {
+    /// Round 1 of a distributed partial evaluation: v_ij = s_ij·PH and DLEQ commitments
+    pub fn eval_commit<R: RngCore + CryptoRng>(&self, msg: &DvrfInput, rng: &mut R) -> (DeviceNonce, DeviceEvalCommitment) {
+        let r = Scalar::random(rng);
+        let ph = dvrf_base_point(msg);
+        let c = DeviceEvalCommitment { index: self.index, v_j: ph * self.value, com1: ProjectivePoint::GENERATOR * r, com2: ph * r };
+        (DeviceNonce { index: self.index, r }, c)
+    }
+
+    /// Round 2: rs_j = λ_j·s_ij·ch + r_j over the commitments of all participating devices
+    pub fn eval_respond(
+        &self,
+        nonce: DeviceNonce,
+        info: &SplitShareInfo,
+        msg: &DvrfInput,
+        commitments: &[DeviceEvalCommitment],
+    ) -> Result<Scalar> {
+        if nonce.index != self.index {
+            bail!("nonce belongs to another device");
+        }
+        let pos = commitments
+            .iter()
+            .position(|c| c.index == self.index)
+            .ok_or_else(|| anyhow!("own commitment missing from the set"))?;
+        let (lambdas, _, ch) = info.combine_eval(msg, commitments)?;
+        Ok(lambdas[pos] * self.value * ch + nonce.r)
+    }
+}
+
+/// Participant whose share only exists split across devices
+pub struct SplitParticipant {
+    info: SplitShareInfo,
+    public_key_package: PublicKeyPackage,
+    min_signers: u16,
+}
+
+impl SplitParticipant {
+    pub fn new(info: SplitShareInfo, public_key_package: PublicKeyPackage, min_signers: u16) -> Result<Self> {
+        if vk_share_from_public_pkg(&public_key_package, info.owner) != info.verifying_share()? {
+            bail!("split does not commit to the participant's verifying share");
+        }
+        Ok(Self { info, public_key_package, min_signers })
+    }
+
+    pub fn id(&self) -> Identifier {
+        self.info.owner
+    }
+
+    /// DVRF partial from `device_threshold` devices; both device rounds run in-process here
+    pub fn partial_eval<R: RngCore + CryptoRng>(&self, msg: &DvrfInput, devices: &[DeviceShare], rng: &mut R) -> Result<PartialEval> {
+        let (nonces, commitments): (Vec<_>, Vec<_>) = devices.iter().map(|d| d.eval_commit(msg, rng)).unzip();
+        let responses = devices
+            .iter()
+            .zip(nonces)
+            .map(|(d, n)| d.eval_respond(n, &self.info, msg, &commitments))
+            .collect::<Result<Vec<_>>>()?;
+        self.info.combine_partial(msg, &commitments, &responses)
+    }
+
+    /// Transient KeyPackage for FROST signing; drop it as soon as the share is produced
+    pub fn key_package(&self, devices: &[DeviceShare]) -> Result<KeyPackage> {
+        let s_i = self.info.reassemble(devices)?;
+        let signing_share = frost::keys::SigningShare::deserialize(&s_i.to_bytes())?;
+        let verifying_share = *self
+            .public_key_package
+            .verifying_shares()
+            .get(&self.info.owner)
+            .ok_or_else(|| anyhow!("participant not in the public key package"))?;
+        Ok(KeyPackage::new(self.info.owner, signing_share, verifying_share, *self.public_key_package.verifying_key(), self.min_signers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use frost::{round1, round2};
+    use crate::ddh_dvrf::validated_vk_share;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+    use crate::messages::AttestationMessage;
+
+    #[test]
+    fn test_split_share_evaluates_and_signs() -> Result<()> {
+        let mut rng = OsRng;
+        let cfg = DkgConfig::new(3, 2)?;
+        let out = run_dealerless_dkg(cfg, &mut rng)?;
+        let ids = out.all_ids();
+        let kp0 = &out.key_packages[&ids[0]];
+
+        let (info, devices) = split_share(kp0, 3, 2, &mut rng)?;
+        for d in &devices {
+            info.verify_device_share(d)?;
+        }
+        let sp = SplitParticipant::new(info, out.public_key_package.clone(), cfg.min_signers)?;
+
+        // DVRF: laptop + backup, share never rebuilt
+        let msg = DvrfInput::new(b"split".to_vec());
+        let p = sp.partial_eval(&msg, &[devices[0], devices[2]], &mut rng)?;
+        assert!(p.verify(&msg, &validated_vk_share(&out.public_key_package, ids[0])?));
+        assert_eq!(*p.v_i, dvrf_base_point(&msg) * scalar_from_keypackage(kp0));
+        assert!(sp.partial_eval(&msg, &devices[..1], &mut rng).is_err());
+
+        // FROST: phone + backup rebuild the key package for one signing
+        let kp = sp.key_package(&devices[1..])?;
+        assert_eq!(kp, *kp0);
+        let amsg = AttestationMessage::new(b"split attestation".to_vec());
+        let kps = [kp, out.key_packages[&ids[1]].clone()];
+        let mut nonces = BTreeMap::new();
+        let mut commitments = BTreeMap::new();
+        for k in &kps {
+            let (n, c) = round1::commit(k.signing_share(), &mut rng);
+            nonces.insert(*k.identifier(), n);
+            commitments.insert(*k.identifier(), c);
+        }
+        let pkg = frost::SigningPackage::new(commitments, &amsg.to_signing_bytes());
+        let shares = kps
+            .iter()
+            .map(|k| Ok((*k.identifier(), round2::sign(&pkg, &nonces[k.identifier()], k)?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let sig = frost::aggregate(&pkg, &shares, &out.public_key_package)?;
+        assert!(out.public_key_package.verifying_key().verify(&amsg.to_signing_bytes(), &sig).is_ok());
+
+        // a tampered device share is caught before use
+        let mut bad = devices[1];
+        bad.value += Scalar::ONE;
+        assert!(sp.key_package(&[devices[0], bad]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_split_rejected() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let ids = out.all_ids();
+        let (info, devices) = split_share(&out.key_packages[&ids[0]], 3, 2, &mut OsRng)?;
+        let empty = SplitShareInfo { commitments: Vec::new(), ..info.clone() };
+        assert!(empty.verifying_share().is_err());
+        assert!(SplitParticipant::new(empty.clone(), out.public_key_package.clone(), 2).is_err());
+        assert!(empty.reassemble(&devices[..2]).is_err());
+        let msg = DvrfInput::new(b"m".to_vec());
+        let (nonces, commitments): (Vec<_>, Vec<_>) = devices[..2].iter().map(|d| d.eval_commit(&msg, &mut OsRng)).unzip();
+        let nonce = nonces.into_iter().next().expect("two devices");
+        assert!(devices[0].eval_respond(nonce, &empty, &msg, &commitments).is_err());
+        Ok(())
+    }
+}
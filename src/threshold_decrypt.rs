@@ -0,0 +1,236 @@
+//! Threshold hashed ElGamal under the DKG group key.
+//!
+//! Encryption: k random, U = k·G, S = k·Y; the body is the plaintext xor a keccak stream
+//! keyed by S, and the tag is keccak(S, U, aad, body). The sender also attaches a Schnorr
+//! proof of knowledge of k bound to (U, aad, body, tag), so a ciphertext cannot be
+//! re-randomised into a fresh one that the committee would decrypt.
+//!
+//! Decryption: each participant releases d_i = sk_i·U with a DLEQ proof against vk_i;
+//! t valid shares Lagrange-combine to S.
+
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Result};
+use frost_secp256k1_evm as frost;
+use frost::rand_core::{CryptoRng, RngCore};
+use k256::{elliptic_curve::Field, ProjectivePoint, Scalar};
+
+use crate::ddh_dvrf::{id_as_u64, validated_vk_share, Identifier, PublicKeyPackage};
+use crate::transcript::{KeccakTranscript, Transcript};
+use crate::utils::{keccak256, lagrange_combine_points, point_bytes_compressed, point_from_compressed_bytes, verify_dleq, Proof};
+use crate::validated::ValidatedPoint;
+use crate::version::{ProtocolVersion, VersionRange};
+#[cfg(feature = "protocol")]
+use crate::ddh_dvrf::{scalar_from_keypackage, vk_share_from_public_pkg, KeyPackage};
+#[cfg(feature = "protocol")]
+use crate::utils::prove_dleq_with_rng;
+#[cfg(feature = "protocol")]
+use frost::rand_core::OsRng;
+
+const KDF_LABEL: &[u8] = b"frostlab/tdec/kdf/v1";
+const MAC_LABEL: &[u8] = b"frostlab/tdec/mac/v1";
+const POK_PROTOCOL: &[u8] = b"frostlab/tdec/pok/v1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub u: ValidatedPoint,
+    pub body: Vec<u8>,
+    pub tag: [u8; 32],
+    /// Schnorr proof of knowledge of log_G(U): (W, z)
+    pub pok_commitment: ProjectivePoint,
+    pub pok_response: Scalar,
+}
+
+/// One participant's d_i = sk_i·U with its DLEQ proof, bound to `version`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecryptionShare {
+    pub version: ProtocolVersion,
+    pub id: Identifier,
+    pub d_i: ValidatedPoint,
+    pub proof: Proof,
+}
+
+fn keystream(s: &ProjectivePoint, u: &ProjectivePoint, len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 32);
+    let mut ctr = 0u32;
+    while out.len() < len {
+        let mut buf = KDF_LABEL.to_vec();
+        buf.extend_from_slice(&point_bytes_compressed(s));
+        buf.extend_from_slice(&point_bytes_compressed(u));
+        buf.extend_from_slice(&ctr.to_be_bytes());
+        out.extend_from_slice(&keccak256(&buf));
+        ctr += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+fn mac(s: &ProjectivePoint, u: &ProjectivePoint, aad: &[u8], body: &[u8]) -> [u8; 32] {
+    let mut buf = MAC_LABEL.to_vec();
+    buf.extend_from_slice(&point_bytes_compressed(s));
+    buf.extend_from_slice(&point_bytes_compressed(u));
+    buf.extend_from_slice(&(aad.len() as u64).to_be_bytes());
+    buf.extend_from_slice(aad);
+    buf.extend_from_slice(body);
+    keccak256(&buf)
+}
+
+fn pok_challenge(u: &ProjectivePoint, w: &ProjectivePoint, aad: &[u8], body: &[u8], tag: &[u8; 32]) -> Scalar {
+    let mut t = KeccakTranscript::new(POK_PROTOCOL);
+    t.append_point(b"U", u);
+    t.append_point(b"W", w);
+    t.append_message(b"aad", aad);
+    t.append_message(b"body", body);
+    t.append_message(b"tag", tag);
+    t.challenge_scalar(b"ch")
+}
+
+fn group_element(group_key: &frost::VerifyingKey) -> Result<ProjectivePoint> {
+    point_from_compressed_bytes(&group_key.serialize()?)
+}
+
+/// Encrypt `plaintext` to the group key; `aad` is authenticated but not encrypted
+pub fn encrypt<R: RngCore + CryptoRng>(
+    group_key: &frost::VerifyingKey,
+    aad: &[u8],
+    plaintext: &[u8],
+    rng: &mut R,
+) -> Result<Ciphertext> {
+    let y = group_element(group_key)?;
+    let k = Scalar::random(&mut *rng);
+    let u = ValidatedPoint::new(ProjectivePoint::GENERATOR * k)?;
+    let s = y * k;
+
+    let body: Vec<u8> = plaintext.iter().zip(keystream(&s, &u, plaintext.len())).map(|(p, k)| p ^ k).collect();
+    let tag = mac(&s, &u, aad, &body);
+
+    let w = Scalar::random(&mut *rng);
+    let pok_commitment = ProjectivePoint::GENERATOR * w;
+    let e = pok_challenge(&u, &pok_commitment, aad, &body, &tag);
+    Ok(Ciphertext { u, body, tag, pok_commitment, pok_response: w + e * k })
+}
+
+impl Ciphertext {
+    /// Well-formed: the sender knows log_G(U) for this exact `aad`
+    pub fn check(&self, aad: &[u8]) -> Result<()> {
+        let e = pok_challenge(&self.u, &self.pok_commitment, aad, &self.body, &self.tag);
+        if ProjectivePoint::GENERATOR * self.pok_response != self.pok_commitment + *self.u * e {
+            bail!("invalid proof of knowledge on ciphertext");
+        }
+        Ok(())
+    }
+
+    /// keccak(U || tag), stable handle for logs and share routing
+    pub fn id(&self) -> [u8; 32] {
+        let mut buf = point_bytes_compressed(&self.u).to_vec();
+        buf.extend_from_slice(&self.tag);
+        keccak256(&buf)
+    }
+}
+
+/// Participant side: d_i for a well-formed ciphertext, under `ProtocolVersion::CURRENT`
+#[cfg(feature = "protocol")]
+pub fn decryption_share(
+    ct: &Ciphertext,
+    aad: &[u8],
+    key_package: &KeyPackage,
+    public_key_package: &PublicKeyPackage,
+) -> Result<DecryptionShare> {
+    decryption_share_with_rng(ct, aad, key_package, public_key_package, &mut OsRng)
+}
+
+/// `decryption_share` with the proof nonce drawn from `rng`
+#[cfg(feature = "protocol")]
+pub fn decryption_share_with_rng<R: RngCore + CryptoRng>(
+    ct: &Ciphertext,
+    aad: &[u8],
+    key_package: &KeyPackage,
+    public_key_package: &PublicKeyPackage,
+    rng: &mut R,
+) -> Result<DecryptionShare> {
+    ct.check(aad)?;
+    let id = *key_package.identifier();
+    let vk_i = vk_share_from_public_pkg(public_key_package, id);
+    let version = ProtocolVersion::CURRENT;
+    let (d_i, proof) = prove_dleq_with_rng(version, &ct.u, vk_i, scalar_from_keypackage(key_package), rng)?;
+    Ok(DecryptionShare { version, id, d_i, proof })
+}
+
+pub fn verify_decryption_share(ct: &Ciphertext, public_key_package: &PublicKeyPackage, share: &DecryptionShare) -> Result<()> {
+    if !VersionRange::supported().contains(share.version) {
+        bail!("decryption share under unsupported protocol version {}", share.version.0);
+    }
+    let vk_i = validated_vk_share(public_key_package, share.id)?;
+    if !verify_dleq(share.version, &ct.u, &vk_i, &share.d_i, &share.proof) {
+        bail!("invalid decryption share from id={}", id_as_u64(share.id));
+    }
+    Ok(())
+}
+
+/// Verify ≥ threshold distinct shares, combine them and open the ciphertext
+pub fn combine_decryption_shares(
+    ct: &Ciphertext,
+    aad: &[u8],
+    public_key_package: &PublicKeyPackage,
+    shares: &[DecryptionShare],
+    threshold: u16,
+) -> Result<Vec<u8>> {
+    ct.check(aad)?;
+    if shares.len() < threshold as usize {
+        bail!("need {} decryption shares, got {}", threshold, shares.len());
+    }
+    let mut seen = BTreeSet::new();
+    let mut points = Vec::with_capacity(shares.len());
+    for sh in shares {
+        if !seen.insert(sh.id) {
+            bail!("duplicate decryption share for id={}", id_as_u64(sh.id));
+        }
+        verify_decryption_share(ct, public_key_package, sh)?;
+        points.push((id_as_u64(sh.id), *sh.d_i));
+    }
+    let s = lagrange_combine_points(&points);
+    if mac(&s, &ct.u, aad, &ct.body) != ct.tag {
+        bail!("ciphertext authentication failed");
+    }
+    Ok(ct.body.iter().zip(keystream(&s, &ct.u, ct.body.len())).map(|(c, k)| c ^ k).collect())
+}
+
+#[cfg(all(test, feature = "protocol"))]
+mod tests {
+    use super::*;
+    use frost_secp256k1_evm::rand_core::OsRng;
+    use crate::dkg::{run_dealerless_dkg, DkgConfig};
+
+    #[test]
+    fn test_threshold_decrypt_roundtrip() -> Result<()> {
+        let cfg = DkgConfig::new(5, 3)?;
+        let out = run_dealerless_dkg(cfg, &mut OsRng)?;
+        let pk = &out.public_key_package;
+        let ct = encrypt(pk.verifying_key(), b"aad", b"swap 10 ETH -> USDC", &mut OsRng)?;
+
+        let shares = out.key_packages.values().take(3)
+            .map(|kp| decryption_share(&ct, b"aad", kp, pk))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(combine_decryption_shares(&ct, b"aad", pk, &shares, 3)?, b"swap 10 ETH -> USDC");
+
+        // too few, wrong aad, bad share
+        assert!(combine_decryption_shares(&ct, b"aad", pk, &shares[..2], 3).is_err());
+        assert!(combine_decryption_shares(&ct, b"other", pk, &shares, 3).is_err());
+        let mut bad = shares.clone();
+        bad[0].d_i = ValidatedPoint::new(*bad[0].d_i + ProjectivePoint::GENERATOR)?;
+        assert!(combine_decryption_shares(&ct, b"aad", pk, &bad, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rerandomised_ciphertext_rejected() -> Result<()> {
+        let out = run_dealerless_dkg(DkgConfig::new(3, 2)?, &mut OsRng)?;
+        let pk = &out.public_key_package;
+        let mut ct = encrypt(pk.verifying_key(), b"", b"secret", &mut OsRng)?;
+        ct.check(b"")?;
+        ct.u = ValidatedPoint::new(*ct.u + ProjectivePoint::GENERATOR)?;
+        let kp = out.key_packages.values().next().unwrap();
+        assert!(decryption_share(&ct, b"", kp, pk).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//! Fiat–Shamir transcripts. Every absorbed element is framed as
+//! `len(label) || label || len(data) || data`, so distinct element sequences never hash alike,
+//! and each proof type starts from its own protocol label.
+//!
+//! `KeccakTranscript` is the default (EVM-friendly); `MerlinTranscript` (feature `merlin`)
+//! is a STROBE-based alternative for off-chain-only proofs.
+
+use k256::{
+    elliptic_curve::{bigint::U256, ops::Reduce, FieldBytes},
+    ProjectivePoint, Scalar, Secp256k1,
+};
+
+use crate::utils::{keccak256, point_bytes_compressed};
+
+/// Sponge-like transcript used to derive challenges
+pub trait Transcript {
+    fn append_message(&mut self, label: &'static [u8], data: &[u8]);
+
+    /// 32 challenge bytes bound to everything absorbed so far
+    fn challenge_bytes(&mut self, label: &'static [u8]) -> [u8; 32];
+
+    fn append_point(&mut self, label: &'static [u8], p: &ProjectivePoint) {
+        self.append_message(label, &point_bytes_compressed(p));
+    }
+
+    fn append_scalar(&mut self, label: &'static [u8], s: &Scalar) {
+        self.append_message(label, &s.to_bytes());
+    }
+
+    fn append_u64(&mut self, label: &'static [u8], x: u64) {
+        self.append_message(label, &x.to_be_bytes());
+    }
+
+    /// challenge mod r
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar {
+        let fb: FieldBytes<Secp256k1> = self.challenge_bytes(label).into();
+        <Scalar as Reduce<U256>>::reduce_bytes(&fb)
+    }
+}
+
+/// Keccak transcript: buffers framed elements, challenges are keccak(buffer) and are absorbed
+/// back so consecutive challenges differ.
+#[derive(Clone, Debug)]
+pub struct KeccakTranscript {
+    buf: Vec<u8>,
+}
+
+impl KeccakTranscript {
+    pub fn new(protocol: &'static [u8]) -> Self {
+        let mut t = Self { buf: Vec::new() };
+        t.append_message(b"dom-sep", protocol);
+        t
+    }
+
+    fn frame(&mut self, label: &[u8], data: &[u8]) {
+        self.buf.extend_from_slice(&(label.len() as u32).to_be_bytes());
+        self.buf.extend_from_slice(label);
+        self.buf.extend_from_slice(&(data.len() as u64).to_be_bytes());
+        self.buf.extend_from_slice(data);
+    }
+}
+
+impl Transcript for KeccakTranscript {
+    fn append_message(&mut self, label: &'static [u8], data: &[u8]) {
+        self.frame(label, data);
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8]) -> [u8; 32] {
+        self.frame(label, &[]);
+        let out = keccak256(&self.buf);
+        self.frame(b"challenge", &out);
+        out
+    }
+}
+
+#[cfg(feature = "merlin")]
+pub struct MerlinTranscript(merlin::Transcript);
+
+#[cfg(feature = "merlin")]
+impl MerlinTranscript {
+    pub fn new(protocol: &'static [u8]) -> Self {
+        Self(merlin::Transcript::new(protocol))
+    }
+}
+
+#[cfg(feature = "merlin")]
+impl Transcript for MerlinTranscript {
+    fn append_message(&mut self, label: &'static [u8], data: &[u8]) {
+        self.0.append_message(label, data);
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.0.challenge_bytes(label, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framing_prevents_concatenation_collisions() {
+        let mut a = KeccakTranscript::new(b"test");
+        a.append_message(b"x", b"ab");
+        a.append_message(b"y", b"c");
+        let mut b = KeccakTranscript::new(b"test");
+        b.append_message(b"x", b"a");
+        b.append_message(b"y", b"bc");
+        assert_ne!(a.challenge_bytes(b"ch"), b.challenge_bytes(b"ch"));
+
+        // protocol label separates proof types
+        let mut c = KeccakTranscript::new(b"other");
+        c.append_message(b"x", b"ab");
+        c.append_message(b"y", b"c");
+        let mut a2 = KeccakTranscript::new(b"test");
+        a2.append_message(b"x", b"ab");
+        a2.append_message(b"y", b"c");
+        assert_ne!(a2.challenge_bytes(b"ch"), c.challenge_bytes(b"ch"));
+    }
+
+    #[test]
+    fn test_consecutive_challenges_differ() {
+        let mut t = KeccakTranscript::new(b"test");
+        t.append_scalar(b"s", &Scalar::ONE);
+        let c1 = t.challenge_scalar(b"ch");
+        let c2 = t.challenge_scalar(b"ch");
+        assert_ne!(c1, c2);
+    }
+
+    #[cfg(feature = "merlin")]
+    #[test]
+    fn test_merlin_backend() {
+        let mut a = MerlinTranscript::new(b"test");
+        let mut b = MerlinTranscript::new(b"test");
+        a.append_point(b"P", &ProjectivePoint::GENERATOR);
+        b.append_point(b"P", &ProjectivePoint::GENERATOR);
+        assert_eq!(a.challenge_scalar(b"ch"), b.challenge_scalar(b"ch"));
+    }
+}
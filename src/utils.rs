@@ -4,11 +4,19 @@ use k256::{
 };
 use tiny_keccak::{Hasher, Keccak};
 
+use crate::messages::DvrfInput;
+use crate::transcript::{KeccakTranscript, Transcript};
+use crate::validated::{ValidatedPoint, ValidatedScalar};
+use crate::version::ProtocolVersion;
+
 use k256::{
     AffinePoint,
-    elliptic_curve::{group::GroupEncoding},
+    elliptic_curve::{group::GroupEncoding, hash2curve::{ExpandMsgXmd, GroupDigest}, BatchNormalize, PrimeField},
 };
-use rand::rngs::OsRng;
+use sha2::Sha256;
+#[cfg(feature = "protocol")]
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+use anyhow::{bail, Result};
 
 /// Keccak256 hash fonksiyonu
 pub fn keccak256(data: &[u8]) -> [u8; 32] {
@@ -26,11 +34,23 @@ pub fn hash_to_scalar_keccak(data: &[u8]) -> Scalar {
     <Scalar as Reduce<U256>>::reduce_bytes(&fb)
 }
 
+/// G·keccak(data): its discrete log is public, so it must never serve as a DVRF base
+#[deprecated(note = "log_G of the result is known; use dvrf_base_point")]
 pub fn hash_to_curve_point_keccak(data: &[u8]) -> ProjectivePoint {
     let s = hash_to_scalar_keccak(data);
     ProjectivePoint::GENERATOR * s
 }
 
+/// RFC 9380 domain separation tag of the DVRF base point
+pub const DVRF_HASH_TO_CURVE_DST: &[u8] = b"FROSTLAB-DDH-DVRF-V02-CS01-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+/// PH = hash_to_curve(domain || m), RFC 9380 secp256k1_XMD:SHA-256_SSWU_RO_. Nobody knows
+/// log_G(PH), so v = s·PH cannot be computed from the group key.
+pub fn dvrf_base_point(input: &DvrfInput) -> ProjectivePoint {
+    Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(&[input.to_hash_input().as_slice()], &[DVRF_HASH_TO_CURVE_DST])
+        .expect("DST is non-empty and under 256 bytes")
+}
+
 pub fn lagrange_combine_points(points: &[(u64, ProjectivePoint)]) -> ProjectivePoint {
     let ids: Vec<u64> = points.iter().map(|(id, _)| *id).collect();
     let mut result = ProjectivePoint::IDENTITY;
@@ -58,15 +78,56 @@ pub fn lagrange_combine_points(points: &[(u64, ProjectivePoint)]) -> ProjectiveP
 
 /// Sıkıştırılmış (SEC1) nokta baytları
 #[inline]
-fn point_bytes_compressed(p: &ProjectivePoint) -> [u8; 33] {
+pub fn point_bytes_compressed(p: &ProjectivePoint) -> [u8; 33] {
     let enc = AffinePoint::from(*p).to_bytes();
     let mut out = [0u8; 33];
     out.copy_from_slice(enc.as_ref());
     out
 }
 
-/// Challenge = Keccak(G || PH || vk || v || com1 || com2) mod r
+/// Bulk `point_bytes_compressed`: one shared field inversion for the whole slice instead of
+/// one per point. Use it when a round exports many partials / commitments at once.
+pub fn points_bytes_compressed(points: &[ProjectivePoint]) -> Vec<[u8; 33]> {
+    // k256's batch inversion panics on an empty batch
+    if points.is_empty() {
+        return Vec::new();
+    }
+    <ProjectivePoint as BatchNormalize<[ProjectivePoint]>>::batch_normalize(points)
+        .iter()
+        .map(|a| {
+            let mut out = [0u8; 33];
+            out.copy_from_slice(a.to_bytes().as_ref());
+            out
+        })
+        .collect()
+}
+
+pub const DLEQ_PROTOCOL: &[u8] = b"frostlab/dleq/v1";
+
+/// Absorb the protocol version, the DLEQ statement and commitments, squeeze ch
+pub fn dleq_challenge<T: Transcript>(
+    t: &mut T,
+    version: ProtocolVersion,
+    g: &ProjectivePoint,
+    ph: &ProjectivePoint,
+    vk: &ProjectivePoint,
+    v:  &ProjectivePoint,
+    com1: &ProjectivePoint,
+    com2: &ProjectivePoint,
+) -> Scalar {
+    t.append_u64(b"version", version.0 as u64);
+    t.append_point(b"G", g);
+    t.append_point(b"PH", ph);
+    t.append_point(b"vk", vk);
+    t.append_point(b"v", v);
+    t.append_point(b"com1", com1);
+    t.append_point(b"com2", com2);
+    t.challenge_scalar(b"ch")
+}
+
+/// Challenge = KeccakTranscript("frostlab/dleq/v1"; version, G, PH, vk, v, com1, com2) mod r
 pub fn challenge_keccak(
+    version: ProtocolVersion,
     g: &ProjectivePoint,
     ph: &ProjectivePoint,
     vk: &ProjectivePoint,
@@ -74,127 +135,256 @@ pub fn challenge_keccak(
     com1: &ProjectivePoint,
     com2: &ProjectivePoint,
 ) -> Scalar {
-    let mut k = Keccak::v256();
-    for pp in [g, ph, vk, v, com1, com2] {
-        k.update(&point_bytes_compressed(pp));
+    dleq_challenge(&mut KeccakTranscript::new(DLEQ_PROTOCOL), version, g, ph, vk, v, com1, com2)
+}
+
+/// Strict scalar decoding: 32 big-endian bytes, must be < r (no reduction)
+pub fn scalar_from_canonical_bytes(bytes: &[u8]) -> Result<Scalar> {
+    if bytes.len() != 32 {
+        bail!("scalar must be 32 bytes, got {}", bytes.len());
     }
-    let mut out = [0u8; 32];
-    k.finalize(&mut out);
-    // reduce mod r
-    let fb: FieldBytes<Secp256k1> = out.into();
-    <Scalar as Reduce<U256>>::reduce_bytes(&fb)
+    let fb: FieldBytes<Secp256k1> = *FieldBytes::<Secp256k1>::from_slice(bytes);
+    Option::<Scalar>::from(Scalar::from_repr(fb))
+        .ok_or_else(|| anyhow::anyhow!("scalar is not canonical (>= group order)"))
+}
+
+/// Strict point decoding: 33-byte compressed SEC1 (0x02/0x03), on curve, not identity
+pub fn point_from_compressed_bytes(bytes: &[u8]) -> Result<ProjectivePoint> {
+    if bytes.len() != 33 {
+        bail!("point must be 33 bytes (compressed SEC1), got {}", bytes.len());
+    }
+    if bytes[0] != 0x02 && bytes[0] != 0x03 {
+        bail!("invalid SEC1 tag 0x{:02x}", bytes[0]);
+    }
+    let mut enc = k256::CompressedPoint::default();
+    enc.copy_from_slice(bytes);
+    let p = Option::<AffinePoint>::from(AffinePoint::from_bytes(&enc))
+        .ok_or_else(|| anyhow::anyhow!("point is not on the curve"))?;
+    Ok(ProjectivePoint::from(p))
 }
 
 /// Prova çıktısı (π_i)
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Proof {
     pub ch: Scalar, // pi_i_1
     pub rs: Scalar, // pi_i_2
 }
 
+impl Proof {
+    pub const BYTES: usize = 64;
+
+    /// ch || rs, both 32-byte big-endian
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut out = [0u8; Self::BYTES];
+        out[..32].copy_from_slice(&self.ch.to_bytes());
+        out[32..].copy_from_slice(&self.rs.to_bytes());
+        out
+    }
+
+    /// Rejects wrong lengths and non-canonical scalars, so each proof has exactly one encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::BYTES {
+            bail!("proof must be {} bytes, got {}", Self::BYTES, bytes.len());
+        }
+        let ch = ValidatedScalar::from_bytes(&bytes[..32])?;
+        let rs = ValidatedScalar::from_bytes(&bytes[32..])?;
+        Ok(Self { ch: *ch, rs: *rs })
+    }
+}
+
 /// proveEq(G, m, vk_i, sk_i) -> (v_i, pi_i)
 ///
-/// - PH = H(m) (hash_to_curve_point_keccak)
+/// - PH = hash_to_curve(domain || m) (dvrf_base_point)
 /// - v_i = PH * sk_i
 /// - r  ~ U(Z_r)
 /// - com1 = G  * r
 /// - com2 = PH * r
-/// - ch   = Keccak(G, PH, vk_i, v_i, com1, com2) mod r
+/// - ch   = Keccak(version, G, PH, vk_i, v_i, com1, com2) mod r
 /// - rs   = sk_i * ch + r
+///
+/// Fails on a zero share or a zero nonce instead of producing an identity output.
+#[cfg(feature = "protocol")]
 pub fn prove_eq(
-    msg: &[u8],
+    version: ProtocolVersion,
+    msg: &DvrfInput,
     vk_i: ProjectivePoint,  // DKG'den gelen public (G*sk_i)
     sk_i: Scalar,           // DKG'den gelen secret
-) -> (ProjectivePoint, Proof) {
-    let g  = ProjectivePoint::GENERATOR;
-    let ph = hash_to_curve_point_keccak(msg);
-
-    // partialEval: v_i = sk_i * PH
-    let v_i = ph * sk_i;
+) -> Result<(ValidatedPoint, Proof)> {
+    prove_eq_with_rng(version, msg, vk_i, sk_i, &mut OsRng)
+}
 
+/// proveEq drawing the nonce r from `rng` (record / replay harness)
+#[cfg(feature = "protocol")]
+pub fn prove_eq_with_rng<R: RngCore + CryptoRng>(
+    version: ProtocolVersion,
+    msg: &DvrfInput,
+    vk_i: ProjectivePoint,
+    sk_i: Scalar,
+    rng: &mut R,
+) -> Result<(ValidatedPoint, Proof)> {
     // nonce r
-    let r = Scalar::generate_biased(&mut OsRng); // veya generate_vartime(&mut OsRng)
+    let r = Scalar::generate_biased(rng); // veya generate_vartime(rng)
+    prove_eq_with_nonce(version, msg, vk_i, sk_i, r)
+}
+
+/// proveEq with a caller-chosen nonce r (test vectors). r must never repeat for the same sk_i.
+#[cfg(feature = "protocol")]
+pub fn prove_eq_with_nonce(
+    version: ProtocolVersion,
+    msg: &DvrfInput,
+    vk_i: ProjectivePoint,
+    sk_i: Scalar,
+    r: Scalar,
+) -> Result<(ValidatedPoint, Proof)> {
+    prove_dleq_with_nonce(version, &dvrf_base_point(msg), vk_i, sk_i, r)
+}
+
+/// Same proof over an arbitrary base B: v_i = sk_i * B and log_G(vk_i) == log_B(v_i).
+/// Fails on an identity base, a zero share or a zero nonce.
+#[cfg(feature = "protocol")]
+pub fn prove_dleq(version: ProtocolVersion, base: &ProjectivePoint, vk_i: ProjectivePoint, sk_i: Scalar) -> Result<(ValidatedPoint, Proof)> {
+    prove_dleq_with_rng(version, base, vk_i, sk_i, &mut OsRng)
+}
+
+/// proveDleq drawing the nonce r from `rng`
+#[cfg(feature = "protocol")]
+pub fn prove_dleq_with_rng<R: RngCore + CryptoRng>(
+    version: ProtocolVersion,
+    base: &ProjectivePoint,
+    vk_i: ProjectivePoint,
+    sk_i: Scalar,
+    rng: &mut R,
+) -> Result<(ValidatedPoint, Proof)> {
+    let r = Scalar::generate_biased(rng);
+    prove_dleq_with_nonce(version, base, vk_i, sk_i, r)
+}
+
+#[cfg(feature = "protocol")]
+pub fn prove_dleq_with_nonce(
+    version: ProtocolVersion,
+    base: &ProjectivePoint,
+    vk_i: ProjectivePoint,
+    sk_i: Scalar,
+    r: Scalar,
+) -> Result<(ValidatedPoint, Proof)> {
+    if sk_i.is_zero().into() {
+        bail!("DLEQ proof over a zero share");
+    }
+    if r.is_zero().into() {
+        bail!("DLEQ proof with a zero nonce");
+    }
+    let g  = ProjectivePoint::GENERATOR;
+    let ph = *base;
+
+    // partialEval: v_i = sk_i * PH; an identity base is rejected here
+    let v_i = ValidatedPoint::new(ph * sk_i)?;
 
     // taahhütler
     let com1 = g  * r;
     let com2 = ph * r;
 
     // challenge
-    let ch = challenge_keccak(&g, &ph, &vk_i, &v_i, &com1, &com2);
+    let ch = challenge_keccak(version, &g, &ph, &vk_i, &v_i, &com1, &com2);
     // response
     let rs = (sk_i * ch) + r;
 
-    (v_i, Proof { ch, rs })
+    Ok((v_i, Proof { ch, rs }))
 }
 
 /// verifyEq(G, PH, vk_i, v_i, pi_i)
 /// com1' = (G * rs)  + (vk_i * -ch)
 /// com2' = (PH * rs) + (v_i  * -ch)
-/// Keccak(G,PH,vk_i,v_i,com1',com2') ?= ch
+/// Keccak(version,G,PH,vk_i,v_i,com1',com2') ?= ch
 pub fn verify_eq(
-    msg: &[u8],
-    vk_i: &ProjectivePoint,
-    v_i:  &ProjectivePoint,
+    version: ProtocolVersion,
+    msg: &DvrfInput,
+    vk_i: &ValidatedPoint,
+    v_i:  &ValidatedPoint,
+    pi:   &Proof,
+) -> bool {
+    match ValidatedPoint::new(dvrf_base_point(msg)) {
+        Ok(ph) => verify_dleq(version, &ph, vk_i, v_i, pi),
+        Err(_) => false,
+    }
+}
+
+/// `verify_eq` over an arbitrary base B. Identity keys / outputs are excluded by the types.
+pub fn verify_dleq(
+    version: ProtocolVersion,
+    base: &ValidatedPoint,
+    vk_i: &ValidatedPoint,
+    v_i:  &ValidatedPoint,
     pi:   &Proof,
 ) -> bool {
     let g  = ProjectivePoint::GENERATOR;
-    let ph = hash_to_curve_point_keccak(msg);
+    let ph = **base;
 
     let minus_ch = Scalar::ZERO - pi.ch;
 
-    let com1_p = (g  * pi.rs) + (*vk_i * minus_ch);
-    let com2_p = (ph * pi.rs) + (*v_i  * minus_ch);
+    let com1_p = (g  * pi.rs) + (**vk_i * minus_ch);
+    let com2_p = (ph * pi.rs) + (**v_i  * minus_ch);
 
-    let ch2 = challenge_keccak(&g, &ph, vk_i, v_i, &com1_p, &com2_p);
+    let ch2 = challenge_keccak(version, &g, &ph, vk_i, v_i, &com1_p, &com2_p);
     ch2 == pi.ch
 }
 
+#[cfg(feature = "protocol")]
+use crate::encoding::{from_hex0x, hex0x};
 
-use std::fs::File;
-use std::io::Write;
-use serde::Serialize;
-use sha3::{Digest, Keccak256};
-use k256::ecdsa::{Signature, VerifyingKey};
+/// 0x + 33-byte compressed SEC1
+#[cfg(feature = "protocol")]
+pub fn point_to_hex(p: &ProjectivePoint) -> String {
+    hex0x(&point_bytes_compressed(p))
+}
 
-#[derive(Serialize)]
-pub struct FrostVerificationInput {
-    pub message_hash: String,
-    pub signature: String,
-    pub expected_signer: String,
+/// With or without 0x; exactly 33 bytes, on the curve, not the identity
+#[cfg(feature = "protocol")]
+pub fn point_from_hex(s: &str) -> Result<ValidatedPoint> {
+    ValidatedPoint::from_compressed(&from_hex0x(s)?)
 }
 
-pub fn export_verification_input(
-    sig: &Signature,
-    vk: &VerifyingKey,
-    msg: &[u8],
-) -> std::io::Result<()> {
-    let msg_hash = Keccak256::digest(msg);
-    let pub_bytes = vk.to_encoded_point(false);
-    let hash = Keccak256::digest(&pub_bytes.as_bytes()[1..]);
-    let eth_addr = &hash[12..];
+/// 0x + 32 big-endian bytes
+#[cfg(feature = "protocol")]
+pub fn scalar_to_hex(s: &Scalar) -> String {
+    hex0x(&s.to_bytes())
+}
+
+/// With or without 0x; exactly 32 bytes, canonical (< r)
+#[cfg(feature = "protocol")]
+pub fn scalar_from_hex(s: &str) -> Result<Scalar> {
+    scalar_from_canonical_bytes(&from_hex0x(s)?)
+}
 
-    let data = FrostVerificationInput {
-        message_hash: format!("0x{}", hex::encode(msg_hash)),
-        signature: format!("0x{}", hex::encode(sig.to_bytes())),
-        expected_signer: format!("0x{}", hex::encode(eth_addr)),
-    };
+/// 0x + ch || rs
+#[cfg(feature = "protocol")]
+pub fn proof_to_hex(p: &Proof) -> String {
+    hex0x(&p.to_bytes())
+}
 
-    let mut file = File::create("frost_verification_input.json")?;
-    file.write_all(serde_json::to_string_pretty(&data)?.as_bytes())?;
-    Ok(())
+/// With or without 0x; 64 bytes, both halves canonical
+#[cfg(feature = "protocol")]
+pub fn proof_from_hex(s: &str) -> Result<Proof> {
+    Proof::from_bytes(&from_hex0x(s)?)
 }
 
+#[cfg(feature = "service")]
+pub use crate::export::FrostVerificationInput;
+
+/// Writes `frost_verification_input.json` in the working directory
+#[cfg(feature = "service")]
+#[deprecated(note = "use export::export_verification_input, which takes the output path")]
+pub fn export_verification_input(
+    sig: &k256::ecdsa::Signature,
+    vk: &k256::ecdsa::VerifyingKey,
+    msg: &[u8],
+) -> std::io::Result<()> {
+    crate::export::export_verification_input(sig, vk, msg, std::path::Path::new("frost_verification_input.json"))
+}
 
-#[cfg(test)]
+#[cfg(all(test, feature = "protocol"))]
 mod tests {
     use super::*;
-    use k256::{AffinePoint, ProjectivePoint, Scalar};
-    use k256::{
-    elliptic_curve::{
-        group::GroupEncoding,
-             // <-- generic parametre: Integer = U256
-    }, 
-};
+    use k256::{ProjectivePoint, Scalar};
 
     #[test]
     fn test_lagrange_combine_points() {
@@ -219,34 +409,186 @@ mod tests {
 
         assert_eq!(v, expected, "Lagrange combine result is incorrect");
 
-        println!("v (compressed):      0x{}", hex::encode(AffinePoint::from(v).to_bytes()));
-        println!("G*5 (compressed):    0x{}", hex::encode(AffinePoint::from(expected).to_bytes()));
+        println!("v (compressed):      {}", point_to_hex(&v));
+        println!("G*5 (compressed):    {}", point_to_hex(&expected));
     }
     #[test]
     fn test_hash_to_map() {
-    let msg = b"hello world";
+    let msg = DvrfInput::new(b"hello world".to_vec());
 
-    let s = hash_to_scalar_keccak(msg);
-    let p = hash_to_curve_point_keccak(msg);
+    let s = hash_to_scalar_keccak(&msg.to_hash_input());
+    let p = dvrf_base_point(&msg);
+    // not G·keccak(m): nobody knows log_G of the base point
+    assert_ne!(p, ProjectivePoint::GENERATOR * s);
+    assert_eq!(p, dvrf_base_point(&msg));
 
     println!("Scalar mod r: {:?}", s);
-    println!("Curve point compressed: 0x{}", hex::encode(k256::AffinePoint::from(p).to_bytes()));
+    println!("Curve point compressed: {}", point_to_hex(&p));
 }
 
     #[test]
-    fn test_prove_and_verify_EQ()
+    fn test_prove_and_verify_eq()
     {
     // sahte DKG çıktısı gibi: sk_i ve vk_i = G*sk_i
     let sk_i = Scalar::generate_biased(&mut OsRng);
-    let vk_i = ProjectivePoint::GENERATOR * sk_i;
+    let vk_i = ValidatedPoint::new(ProjectivePoint::GENERATOR * sk_i).unwrap();
 
-    let msg = b"hello FROST";
+    let msg = &DvrfInput::new(b"hello FROST".to_vec());
 
-    let (v_i, proof) = prove_eq(msg, vk_i, sk_i);
-    let ok = verify_eq(msg, &vk_i, &v_i, &proof);
+    let (v_i, proof) = prove_eq(ProtocolVersion::CURRENT, msg, *vk_i, sk_i).unwrap();
+    let ok = verify_eq(ProtocolVersion::CURRENT, msg, &vk_i, &v_i, &proof);
 
     println!("verifyEq: {}", ok); // true
 }
 
+    /// secp256k1 group order r, big-endian
+    const ORDER_BE: [u8; 32] = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE,
+        0xBA, 0xAE, 0xDC, 0xE6, 0xAF, 0x48, 0xA0, 0x3B, 0xBF, 0xD2, 0x5E, 0x8C, 0xD0, 0x36, 0x41, 0x41,
+    ];
+
+    /// big-endian a + b (mod 2^256)
+    fn add_be(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        let mut carry = 0u16;
+        for i in (0..32).rev() {
+            let s = a[i] as u16 + b[i] as u16 + carry;
+            out[i] = s as u8;
+            carry = s >> 8;
+        }
+        out
+    }
+
+    #[test]
+    fn test_strict_decoding() {
+        // r itself and r + 1 are non-canonical encodings of 0 and 1
+        assert!(scalar_from_canonical_bytes(&ORDER_BE).is_err());
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert!(scalar_from_canonical_bytes(&add_be(&ORDER_BE, &one)).is_err());
+        assert_eq!(scalar_from_canonical_bytes(&one).unwrap(), Scalar::ONE);
+        assert!(scalar_from_canonical_bytes(&[0u8; 31]).is_err());
+
+        let p = ProjectivePoint::GENERATOR * Scalar::from(7u64);
+        let enc = point_bytes_compressed(&p);
+        assert_eq!(point_from_compressed_bytes(&enc).unwrap(), p);
+
+        let mut bad_tag = enc;
+        bad_tag[0] = 0x04;
+        assert!(point_from_compressed_bytes(&bad_tag).is_err());
+        assert!(point_from_compressed_bytes(&enc[..32]).is_err());
+        assert!(point_from_compressed_bytes(&[0u8; 33]).is_err());
+        // x = 5 is not on secp256k1 (5^3 + 7 = 132 is a non-residue)
+        let mut off_curve = [0u8; 33];
+        off_curve[0] = 0x02;
+        off_curve[32] = 5;
+        assert!(point_from_compressed_bytes(&off_curve).is_err());
+    }
+
+    #[test]
+    fn test_hex_helpers() {
+        let p = ProjectivePoint::GENERATOR * Scalar::from(9u64);
+        let h = point_to_hex(&p);
+        assert!(h.starts_with("0x") && h.len() == 2 + 66);
+        assert_eq!(*point_from_hex(&h).unwrap(), p);
+        assert_eq!(*point_from_hex(&h[2..]).unwrap(), p);
+        assert!(point_from_hex(&h[..h.len() - 2]).is_err());
+        assert!(point_from_hex(&format!("0x{}", "00".repeat(33))).is_err());
+
+        assert_eq!(scalar_from_hex(&scalar_to_hex(&Scalar::from(5u64))).unwrap(), Scalar::from(5u64));
+        assert!(scalar_from_hex(&hex0x(&ORDER_BE)).is_err());
+        assert!(scalar_from_hex("0x05").is_err());
+        assert!(scalar_from_hex("0xzz").is_err());
+
+        let proof = Proof { ch: Scalar::ONE, rs: Scalar::from(2u64) };
+        assert_eq!(proof_from_hex(&proof_to_hex(&proof)).unwrap(), proof);
+        assert!(proof_from_hex(&hex0x(&[0xFF; 64])).is_err());
+    }
+
+    #[test]
+    fn test_batch_compressed_matches_single() {
+        let points: Vec<ProjectivePoint> = (0..9u64)
+            .map(|k| ProjectivePoint::GENERATOR * Scalar::from(k * 1000 + 3) + ProjectivePoint::GENERATOR)
+            .chain([ProjectivePoint::IDENTITY])
+            .collect();
+        let batch = points_bytes_compressed(&points);
+        for (p, enc) in points.iter().zip(&batch) {
+            assert_eq!(*enc, point_bytes_compressed(p));
+        }
+        assert!(points_bytes_compressed(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_degenerate_dleq_inputs_rejected() {
+        let sk_i = Scalar::from(7u64);
+        let vk_i = ProjectivePoint::GENERATOR * sk_i;
+        let base = ProjectivePoint::GENERATOR * Scalar::from(3u64);
+        let r = Scalar::from(11u64);
+        assert!(prove_dleq_with_nonce(ProtocolVersion::CURRENT, &base, vk_i, sk_i, r).is_ok());
+        assert!(prove_dleq_with_nonce(ProtocolVersion::CURRENT, &ProjectivePoint::IDENTITY, vk_i, sk_i, r).is_err());
+        assert!(prove_dleq_with_nonce(ProtocolVersion::CURRENT, &base, ProjectivePoint::IDENTITY, Scalar::ZERO, r).is_err());
+        assert!(prove_dleq_with_nonce(ProtocolVersion::CURRENT, &base, vk_i, sk_i, Scalar::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_malleated_proofs_rejected() {
+        let sk_i = Scalar::generate_biased(&mut OsRng);
+        let vk_i = ValidatedPoint::new(ProjectivePoint::GENERATOR * sk_i).unwrap();
+        let msg = &DvrfInput::new(b"malleability".to_vec());
+
+        let (v_i, proof) = prove_eq(ProtocolVersion::CURRENT, msg, *vk_i, sk_i).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(Proof::from_bytes(&bytes).unwrap(), proof);
+        assert!(verify_eq(ProtocolVersion::CURRENT, msg, &vk_i, &v_i, &proof));
+
+        // encoding-level malleations: must not even parse
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut encodings = Vec::new();
+        for high in [ORDER_BE, add_be(&ORDER_BE, &one), [0xFF; 32]] {
+            let mut e = bytes;
+            e[..32].copy_from_slice(&high);
+            encodings.push(e.to_vec());
+            let mut e = bytes;
+            e[32..].copy_from_slice(&high);
+            encodings.push(e.to_vec());
+        }
+        encodings.push(bytes[..63].to_vec());
+        encodings.push([&bytes[..], &[0u8]].concat());
+        for enc in &encodings {
+            assert!(Proof::from_bytes(enc).is_err());
+        }
+
+        // algebraic malleations: parse fine, must fail verification
+        let mut malleated = vec![
+            Proof { ch: proof.ch, rs: -proof.rs },
+            Proof { ch: -proof.ch, rs: proof.rs },
+            Proof { ch: proof.ch + Scalar::ONE, rs: proof.rs },
+            Proof { ch: proof.ch, rs: proof.rs + Scalar::ONE },
+            Proof { ch: proof.rs, rs: proof.ch },
+            Proof { ch: Scalar::ZERO, rs: Scalar::ZERO },
+        ];
+        for bit in [0usize, 100, 255, 256, 400, 511] {
+            let mut b = bytes;
+            b[bit / 8] ^= 1 << (bit % 8);
+            if let Ok(p) = Proof::from_bytes(&b) {
+                malleated.push(p);
+            }
+        }
+        for p in &malleated {
+            assert!(!verify_eq(ProtocolVersion::CURRENT, msg, &vk_i, &v_i, p), "malleated proof accepted: {:?}", p);
+        }
+
+        // statement malleations with the honest proof
+        let neg = |p: &ValidatedPoint| ValidatedPoint::new(-**p).unwrap();
+        assert!(!verify_eq(ProtocolVersion::CURRENT, msg, &vk_i, &neg(&v_i), &proof));
+        assert!(!verify_eq(ProtocolVersion::CURRENT, msg, &neg(&vk_i), &v_i, &proof));
+        assert!(!verify_eq(ProtocolVersion::CURRENT, &DvrfInput::new(b"other".to_vec()), &vk_i, &v_i, &proof));
+        // the version is part of the statement
+        assert!(!verify_eq(ProtocolVersion(3), msg, &vk_i, &v_i, &proof));
+        // identity keys / outputs cannot even be passed in
+        assert!(ValidatedPoint::new(ProjectivePoint::IDENTITY).is_err());
+    }
+
 
 }
@@ -0,0 +1,103 @@
+//! Checked group elements and scalars for everything that crosses a trust boundary.
+//!
+//! secp256k1 has prime order (cofactor 1): every on-curve point except the identity generates
+//! the whole group, so there is no small-subgroup or torsion component to clear. The checks
+//! that remain are "on the curve" (enforced by decoding) and "not the identity", and
+//! `ValidatedPoint` can only be built after both.
+//!
+//! A k256 `Scalar` in memory is always reduced mod r; the only place a non-canonical value
+//! can appear is its byte encoding, which `ValidatedScalar::from_bytes` rejects.
+//!
+//! FROST types (`VerifyingKey`, `Signature`, …) get the same checks from frost-core's
+//! deserializers.
+
+use std::ops::Deref;
+
+use anyhow::{bail, Result};
+use k256::{ProjectivePoint, Scalar};
+
+use crate::utils::{point_bytes_compressed, point_from_compressed_bytes, points_bytes_compressed, scalar_from_canonical_bytes};
+
+/// On-curve, non-identity point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidatedPoint(ProjectivePoint);
+
+impl ValidatedPoint {
+    pub fn new(p: ProjectivePoint) -> Result<Self> {
+        if p == ProjectivePoint::IDENTITY {
+            bail!("point is the identity");
+        }
+        Ok(Self(p))
+    }
+
+    /// 33-byte compressed SEC1
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self> {
+        Self::new(point_from_compressed_bytes(bytes)?)
+    }
+
+    pub fn to_compressed(&self) -> [u8; 33] {
+        point_bytes_compressed(&self.0)
+    }
+
+    /// `to_compressed` for many points with a single batched inversion
+    pub fn batch_to_compressed(points: &[ValidatedPoint]) -> Vec<[u8; 33]> {
+        let raw: Vec<ProjectivePoint> = points.iter().map(|p| p.0).collect();
+        points_bytes_compressed(&raw)
+    }
+
+    pub fn point(&self) -> ProjectivePoint {
+        self.0
+    }
+}
+
+impl Deref for ValidatedPoint {
+    type Target = ProjectivePoint;
+
+    fn deref(&self) -> &ProjectivePoint {
+        &self.0
+    }
+}
+
+/// Scalar decoded from its unique canonical encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidatedScalar(Scalar);
+
+impl ValidatedScalar {
+    /// 32 big-endian bytes, < r
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self(scalar_from_canonical_bytes(bytes)?))
+    }
+
+    pub fn scalar(&self) -> Scalar {
+        self.0
+    }
+}
+
+impl From<Scalar> for ValidatedScalar {
+    fn from(s: Scalar) -> Self {
+        Self(s)
+    }
+}
+
+impl Deref for ValidatedScalar {
+    type Target = Scalar;
+
+    fn deref(&self) -> &Scalar {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_and_bad_encodings_rejected() {
+        assert!(ValidatedPoint::new(ProjectivePoint::IDENTITY).is_err());
+        assert!(ValidatedPoint::from_compressed(&[0u8; 33]).is_err());
+        let g = ValidatedPoint::new(ProjectivePoint::GENERATOR).unwrap();
+        assert_eq!(ValidatedPoint::from_compressed(&g.to_compressed()).unwrap(), g);
+        assert!(ValidatedScalar::from_bytes(&[0xFF; 32]).is_err());
+        assert_eq!(*ValidatedScalar::from_bytes(&Scalar::ONE.to_bytes()).unwrap(), Scalar::ONE);
+    }
+}
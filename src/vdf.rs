@@ -0,0 +1,36 @@
+//! Optional verifiable-delay stage on top of the combined DVRF output.
+//!
+//! seed = keccak("frostlab/vdf/v1" || compressed v), the VDF output replaces v as the round's
+//! randomness. The last partial to arrive can no longer grind on the final value before it is
+//! public, which matters for small committees.
+
+use k256::ProjectivePoint;
+
+use crate::utils::{keccak256, point_bytes_compressed};
+
+#[cfg(feature = "vdf-wesolowski")]
+pub mod wesolowski;
+
+pub const VDF_DOMAIN: &[u8] = b"frostlab/vdf/v1";
+
+/// VDF evaluation result
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VdfOutput {
+    pub output: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+/// Pluggable VDF backend
+pub trait Vdf {
+    /// slow: sequential evaluation plus proof
+    fn eval(&self, seed: &[u8]) -> VdfOutput;
+    /// fast verification
+    fn verify(&self, seed: &[u8], out: &VdfOutput) -> bool;
+}
+
+/// VDF input derived from the combined output
+pub fn vdf_seed(v: &ProjectivePoint) -> [u8; 32] {
+    let mut buf = VDF_DOMAIN.to_vec();
+    buf.extend_from_slice(&point_bytes_compressed(v));
+    keccak256(&buf)
+}
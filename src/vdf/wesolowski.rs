@@ -0,0 +1,208 @@
+//! Wesolowski VDF in an RSA group (reference implementation).
+//!
+//! x = H(seed) mod N,  y = x^(2^T),  l = HashToPrime(x, y),  π = x^⌊2^T / l⌋
+//! verify: π^l · x^(2^T mod l) == y
+//!
+//! The default modulus is the RSA-2048 factoring challenge number published by RSA Laboratories
+//! in 1991: its factors were never revealed and it has not been factored, so no party is known
+//! to hold a trapdoor. Deployments wanting a different group supply it via `with_modulus`.
+
+use num_bigint::BigUint;
+
+use super::{Vdf, VdfOutput};
+use crate::utils::keccak256;
+
+/// RSA-2048 challenge number
+const RSA_2048_HEX: &str = concat!(
+    "c7970ceedcc3b0754490201a7aa613cd73911081c790f5f1a8726f463550bb5b7ff0db8e1ea1189ec72f93d1650011bd",
+    "721aeeacc2acde32a04107f0648c2813a31f5b0b7765ff8b44b4b6ffc93384b646eb09c7cf5e8592d40ea33c80039f35",
+    "b4f14a04b51f7bfd781be4d1673164ba8eb991c2c4d730bbbe35f592bdef524af7e8daefd26c66fc02c479af89d64d37",
+    "3f442709439de66ceb955f3ea37d5159f6135809f85334b5cb1813addc80cd05609f10ac6a95ad65872c909525bdad32",
+    "bc729592642920f24c61dc5b3c3b7923e56b16a4d9d373d8721f24a3fc0f1b3131f55615172866bccc30f95054c824e7",
+    "33a5eb6817f7bc16399d48c6361cc7e5",
+);
+
+/// Wesolowski VDF with `iterations` sequential squarings
+#[derive(Clone, Debug)]
+pub struct Wesolowski {
+    modulus: BigUint,
+    iterations: u64,
+}
+
+impl Wesolowski {
+    pub fn new(iterations: u64) -> Self {
+        let modulus = BigUint::parse_bytes(RSA_2048_HEX.as_bytes(), 16).expect("valid modulus hex");
+        Self { modulus, iterations }
+    }
+
+    pub fn with_modulus(modulus: BigUint, iterations: u64) -> Self {
+        Self { modulus, iterations }
+    }
+
+    fn byte_len(&self) -> usize {
+        (self.modulus.bits() as usize).div_ceil(8)
+    }
+
+    fn to_fixed_bytes(&self, x: &BigUint) -> Vec<u8> {
+        let raw = x.to_bytes_be();
+        let mut out = vec![0u8; self.byte_len() - raw.len()];
+        out.extend_from_slice(&raw);
+        out
+    }
+
+    /// expand keccak(seed || ctr) to |N| + 16 bytes and reduce, so the bias is negligible
+    fn hash_to_group(&self, seed: &[u8]) -> BigUint {
+        let mut wide = Vec::with_capacity(self.byte_len() + 48);
+        let mut ctr = 0u32;
+        while wide.len() < self.byte_len() + 16 {
+            let mut buf = b"frostlab/vdf/h2g".to_vec();
+            buf.extend_from_slice(seed);
+            buf.extend_from_slice(&ctr.to_be_bytes());
+            wide.extend_from_slice(&keccak256(&buf));
+            ctr += 1;
+        }
+        BigUint::from_bytes_be(&wide) % &self.modulus
+    }
+}
+
+/// 128-bit prime derived from (x, y)
+fn hash_to_prime(x: &[u8], y: &[u8]) -> BigUint {
+    let mut ctr = 0u64;
+    loop {
+        let mut buf = b"frostlab/vdf/h2p".to_vec();
+        buf.extend_from_slice(x);
+        buf.extend_from_slice(y);
+        buf.extend_from_slice(&ctr.to_be_bytes());
+        let mut c = keccak256(&buf)[..16].to_vec();
+        c[0] |= 0x80;
+        c[15] |= 1;
+        let cand = BigUint::from_bytes_be(&c);
+        if is_probable_prime(&cand) {
+            return cand;
+        }
+        ctr += 1;
+    }
+}
+
+/// Miller–Rabin with the first 20 primes as bases
+fn is_probable_prime(n: &BigUint) -> bool {
+    const BASES: [u32; 20] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    if *n < two { return false; }
+    for p in BASES {
+        let p = BigUint::from(p);
+        if *n == p { return true; }
+        if (n % &p) == BigUint::ZERO { return false; }
+    }
+    let n_1 = n - &one;
+    let s = n_1.trailing_zeros().expect("n > 1");
+    let d = &n_1 >> s;
+    'bases: for a in BASES {
+        let mut x = BigUint::from(a).modpow(&d, n);
+        if x == one || x == n_1 { continue; }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_1 { continue 'bases; }
+        }
+        return false;
+    }
+    true
+}
+
+impl Vdf for Wesolowski {
+    fn eval(&self, seed: &[u8]) -> VdfOutput {
+        let n = &self.modulus;
+        let x = self.hash_to_group(seed);
+
+        let mut y = x.clone();
+        for _ in 0..self.iterations {
+            y = &y * &y % n;
+        }
+        let x_bytes = self.to_fixed_bytes(&x);
+        let y_bytes = self.to_fixed_bytes(&y);
+        let l = hash_to_prime(&x_bytes, &y_bytes);
+
+        // π = x^⌊2^T / l⌋ by long division of 2^T, one bit at a time
+        let two = BigUint::from(2u32);
+        let mut pi = BigUint::from(1u32);
+        let mut r = BigUint::from(1u32);
+        for _ in 0..self.iterations {
+            let r2 = &r * &two;
+            let bit = r2 >= l;
+            r = if bit { r2 - &l } else { r2 };
+            pi = &pi * &pi % n;
+            if bit {
+                pi = pi * &x % n;
+            }
+        }
+
+        VdfOutput { output: y_bytes, proof: self.to_fixed_bytes(&pi) }
+    }
+
+    fn verify(&self, seed: &[u8], out: &VdfOutput) -> bool {
+        let n = &self.modulus;
+        if out.output.len() != self.byte_len() || out.proof.len() != self.byte_len() {
+            return false;
+        }
+        let y = BigUint::from_bytes_be(&out.output);
+        let pi = BigUint::from_bytes_be(&out.proof);
+        if y >= *n || pi >= *n || pi == BigUint::ZERO {
+            return false;
+        }
+
+        let x = self.hash_to_group(seed);
+        let l = hash_to_prime(&self.to_fixed_bytes(&x), &out.output);
+        let r = BigUint::from(2u32).modpow(&BigUint::from(self.iterations), &l);
+        (pi.modpow(&l, n) * x.modpow(&r, n)) % n == y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wesolowski_eval_verify() {
+        let vdf = Wesolowski::new(2_000);
+        let out = vdf.eval(b"seed");
+        assert!(vdf.verify(b"seed", &out));
+        assert!(!vdf.verify(b"other seed", &out));
+
+        let mut bad = out.clone();
+        bad.output[10] ^= 1;
+        assert!(!vdf.verify(b"seed", &bad));
+
+        let mut bad = out.clone();
+        bad.proof[200] ^= 1;
+        assert!(!vdf.verify(b"seed", &bad));
+
+        // fewer squarings than claimed
+        let fast = Wesolowski::new(1_999);
+        assert!(!vdf.verify(b"seed", &fast.eval(b"seed")));
+    }
+
+    #[test]
+    fn test_default_modulus_is_rsa_2048() {
+        // decimal form as published with the challenge
+        let published = BigUint::parse_bytes(concat!(
+            "25195908475657893494027183240048398571429282126204032027777137836043662020707595556264018525880784",
+            "40691829064124951508218929855914917618450280848912007284499268739280728777673597141834727026189637",
+            "50149718246911650776133798590957000973304597488084284017974291006424586918171951187461215151726546",
+            "32282216869987549182422433637259085141865462043576798423387184774447920739934236584823824281198163",
+            "81501067481045166037730605620161967625613384414360383390441495263443219011465754445417842402092461",
+            "65157233507787077498171257724679629263863563732899121548314381678998850404453640235273819513786365",
+            "64391212010397122822120720357",
+        ).as_bytes(), 10).unwrap();
+        assert_eq!(Wesolowski::new(1).modulus, published);
+        assert_eq!(published.bits(), 2048);
+    }
+
+    #[test]
+    fn test_small_primes() {
+        assert!(is_probable_prime(&BigUint::from(1_000_000_007u64)));
+        assert!(!is_probable_prime(&BigUint::from(1_000_000_007u64 * 3)));
+        // Carmichael number
+        assert!(!is_probable_prime(&BigUint::from(561u32)));
+    }
+}
@@ -0,0 +1,148 @@
+//! Golden-file regression: every stable encoding is regenerated from fixed seeds and compared
+//! with `tests/golden/`, and the stored bytes are decoded and verified with the current code.
+//! Run with `cargo test --features interop --test golden`.
+//! `FROSTLAB_REGEN_VECTORS=1` rewrites tests/golden/ from the library.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use frost_secp256k1_evm as frost;
+use frost::keys::{PublicKeyPackage, VerifyingShare};
+use serde_json::Value;
+
+use frostlab::beacon_proof::{BeaconInclusionProof, BeaconLightClient, ChainInfo, TimingPolicy};
+use frostlab::cert::{PartialEval, QuorumCert};
+use frostlab::committee::CommitteeInfo;
+use frostlab::dkg::Identifier;
+use frostlab::encoding::from_hex0x;
+use frostlab::golden::golden_files;
+use frostlab::messages::DvrfInput;
+use frostlab::randomness_cert::{round_transcript_hash, RandomnessCertificate};
+use frostlab::round_attestation::RoundAttestation;
+use frostlab::utils::{dvrf_base_point, point_from_hex, point_to_hex, proof_from_hex, verify_eq};
+use frostlab::version::ProtocolVersion;
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+fn read_golden(name: &str) -> Result<String> {
+    let path = Path::new(GOLDEN_DIR).join(name);
+    let s = fs::read_to_string(&path).with_context(|| format!("{} missing; generate with FROSTLAB_REGEN_VECTORS=1", path.display()))?;
+    Ok(s.replace("\r\n", "\n"))
+}
+
+fn unhex(v: &Value) -> Vec<u8> {
+    from_hex0x(v.as_str().expect("hex string")).expect("valid hex")
+}
+
+fn unhex32(v: &Value) -> [u8; 32] {
+    unhex(v).try_into().expect("32 bytes")
+}
+
+fn version(v: &Value) -> ProtocolVersion {
+    ProtocolVersion(v.as_u64().expect("numeric version") as u16)
+}
+
+/// Public key package of the stored committee report
+fn public_key_package(committee: &Value) -> Result<PublicKeyPackage> {
+    let mut shares = BTreeMap::new();
+    for m in committee["members"].as_array().unwrap() {
+        shares.insert(Identifier::deserialize(&unhex(&m["identifier"]))?, VerifyingShare::deserialize(&unhex(&m["verifying_share"]))?);
+    }
+    Ok(PublicKeyPackage::new(shares, frost::VerifyingKey::deserialize(&unhex(&committee["group_key"]))?))
+}
+
+#[test]
+fn golden_files_are_stable() -> Result<()> {
+    let generated = golden_files()?;
+    assert_eq!(generated, golden_files()?, "golden generation is not deterministic");
+    if std::env::var_os("FROSTLAB_REGEN_VECTORS").is_some() {
+        fs::create_dir_all(GOLDEN_DIR)?;
+        for (name, contents) in &generated {
+            fs::write(Path::new(GOLDEN_DIR).join(name), contents)?;
+        }
+    }
+    let mut drifted = Vec::new();
+    for (name, contents) in &generated {
+        if read_golden(name)? != *contents {
+            drifted.push(*name);
+        }
+    }
+    assert!(drifted.is_empty(), "encodings drifted in {drifted:?}; regenerate golden files only if intended");
+    Ok(())
+}
+
+#[test]
+fn golden_bytes_decode_and_verify() -> Result<()> {
+    // DLEQ proof: verifies over the stored base point
+    let dleq: Value = serde_json::from_str(&read_golden("dleq_proof.json")?)?;
+    let msg = DvrfInput::new(unhex(&dleq["msg"]));
+    assert_eq!(dleq["base_point"], point_to_hex(&dvrf_base_point(&msg)));
+    let vk = point_from_hex(dleq["verifying_share"].as_str().unwrap())?;
+    let v = point_from_hex(dleq["partial_eval"].as_str().unwrap())?;
+    assert!(verify_eq(version(&dleq["version"]), &msg, &vk, &v, &proof_from_hex(dleq["proof"].as_str().unwrap())?));
+
+    // committee: the report rebuilt from the decoded key package is the stored one
+    let committee: Value = serde_json::from_str(&read_golden("committee.json")?)?;
+    let pkpkg = public_key_package(&committee)?;
+    let t = committee["min_signers"].as_u64().unwrap() as u16;
+    assert_eq!(serde_json::to_value(CommitteeInfo::from_public_key_package(&pkpkg, t)?.to_report())?, committee);
+
+    // quorum certificate: every partial verifies and they combine to the stored output
+    let qc: Value = serde_json::from_str(&read_golden("quorum_cert.json")?)?;
+    let partials = qc["partials"].as_array().unwrap().iter().map(|p| Ok(PartialEval {
+        version: version(&p["version"]),
+        id: Identifier::deserialize(&unhex(&p["identifier"]))?,
+        v_i: point_from_hex(p["partial_eval"].as_str().unwrap())?,
+        proof: proof_from_hex(p["proof"].as_str().unwrap())?,
+    })).collect::<Result<Vec<_>>>()?;
+    let cert = QuorumCert {
+        version: version(&qc["version"]),
+        msg: DvrfInput::new(unhex(&qc["msg"])),
+        output: *point_from_hex(qc["output"].as_str().unwrap())?,
+        partials,
+        vdf: None,
+    };
+    cert.verify(&pkpkg, t)?;
+    assert_eq!(cert.randomness(), unhex32(&qc["randomness"]));
+    assert_eq!(round_transcript_hash(&cert), unhex32(&qc["transcript_hash"]));
+
+    // randomness certificate: the group signature covers exactly that round
+    let rc: Value = serde_json::from_str(&read_golden("randomness_certificate.json")?)?;
+    let rc = RandomnessCertificate {
+        version: version(&rc["version"]),
+        epoch: rc["epoch"].as_u64().unwrap(),
+        round: rc["round"].as_u64().unwrap(),
+        msg_hash: unhex32(&rc["msg_hash"]),
+        transcript_hash: unhex32(&rc["transcript_hash"]),
+        randomness: unhex32(&rc["randomness"]),
+        signature: frost::Signature::deserialize(&unhex(&rc["signature"]))?,
+    };
+    rc.verify_with_round(&cert, &pkpkg, t)?;
+
+    // beacon proofs: a light client follows the stored chain
+    let beacon: Value = serde_json::from_str(&read_golden("beacon.json")?)?;
+    let chain = ChainInfo {
+        group_id: unhex(&beacon["group_id"]).try_into().expect("32 bytes"),
+        group_key: unhex(&beacon["group_key"]).try_into().expect("33 bytes"),
+        min_signers: beacon["min_signers"].as_u64().unwrap() as u16,
+        genesis_time: beacon["genesis_time"].as_u64().unwrap(),
+        period: beacon["period"].as_u64().unwrap(),
+    };
+    assert_eq!(chain.hash().to_vec(), unhex(&beacon["chain_hash"]));
+    let mut client = BeaconLightClient::new(chain.clone()).with_timing(TimingPolicy { max_skew: 0 });
+    for r in beacon["rounds"].as_array().unwrap() {
+        let proof = BeaconInclusionProof::from_bytes(&unhex(&r["inclusion_proof"]))?;
+        let now = chain.round_time(proof.round).unwrap();
+        assert_eq!(client.accept(&proof, now)?.to_vec(), unhex(&r["randomness"]));
+        assert_eq!(client.head.to_vec(), unhex(&r["link"]));
+    }
+
+    // EVM export: the attestation decodes and its randomness matches
+    let export: Value = serde_json::from_str(&read_golden("evm_export.json")?)?;
+    let att = RoundAttestation::from_abi(&unhex(&export["attestation_abi"]))?;
+    assert_eq!(att.vrf_output.to_vec(), unhex(&export["randomness"]));
+    assert_eq!(att.to_abi(), unhex(&export["attestation_abi"]));
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Interop against the published FROST(secp256k1, KECCAK-256) vectors and our own DDH-DVRF vectors.
+//! Run with `cargo test --features interop --test interop`.
+//! `FROSTLAB_REGEN_VECTORS=1` rewrites tests/vectors/ddh_dvrf.json from the library.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use anyhow::Result;
+use frost_secp256k1_evm as frost;
+use frost::keys::{KeyPackage, PublicKeyPackage, SigningShare, VerifyingShare};
+use frost::rand_core::{CryptoRng, RngCore};
+use serde_json::Value;
+
+use frostlab::dkg::{DkgOutput, Identifier};
+use frostlab::frost_ext::{frost_sign_raw, frost_verify_raw, frost_verify_with_key};
+use frostlab::interop::{check_dvrf_vectors, generate_dvrf_vectors, DvrfVectors};
+use frostlab::messages::AttestationMessage;
+
+const FROST_VECTORS: &str = include_str!("vectors/frost_secp256k1_evm.json");
+const DVRF_VECTORS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/ddh_dvrf.json");
+
+/// Replays fixed bytes so `round1::commit` reproduces the vector nonces
+struct ScriptedRng(VecDeque<u8>);
+
+impl RngCore for ScriptedRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut b = [0u8; 4];
+        self.fill_bytes(&mut b);
+        u32::from_le_bytes(b)
+    }
+    fn next_u64(&mut self) -> u64 {
+        let mut b = [0u8; 8];
+        self.fill_bytes(&mut b);
+        u64::from_le_bytes(b)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for d in dest {
+            *d = self.0.pop_front().expect("script exhausted");
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), frost::rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ScriptedRng {}
+
+fn unhex(v: &Value) -> Vec<u8> {
+    hex::decode(v.as_str().expect("hex string")).expect("valid hex")
+}
+
+fn id_of(v: &Value) -> Identifier {
+    Identifier::try_from(v.as_u64().expect("numeric id") as u16).expect("nonzero id")
+}
+
+/// Key material of the vectors as if it came out of our DKG
+fn dkg_output_from_vectors(v: &Value) -> DkgOutput {
+    let inputs = &v["inputs"];
+    let min_signers: u16 = v["config"]["MIN_PARTICIPANTS"].as_str().unwrap().parse().unwrap();
+    let vk = frost::VerifyingKey::deserialize(&unhex(&inputs["verifying_key_key"])).unwrap();
+
+    let mut key_packages = BTreeMap::new();
+    let mut verifying_shares = BTreeMap::new();
+    for s in inputs["participant_shares"].as_array().unwrap() {
+        let id = id_of(&s["identifier"]);
+        let share = SigningShare::deserialize(&unhex(&s["participant_share"])).unwrap();
+        let vshare = VerifyingShare::from(share);
+        key_packages.insert(id, KeyPackage::new(id, share, vshare, vk, min_signers));
+        verifying_shares.insert(id, vshare);
+    }
+    DkgOutput {
+        key_packages,
+        public_key_package: PublicKeyPackage::new(verifying_shares, vk),
+        transcript_hash: [0u8; 32],
+        possession_proofs: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn frost_reference_vectors() -> Result<()> {
+    let v: Value = serde_json::from_str(FROST_VECTORS)?;
+    let out = dkg_output_from_vectors(&v);
+    let msg = unhex(&v["inputs"]["message"]);
+
+    // serialization of the group key round-trips to the vector bytes
+    assert_eq!(
+        out.public_key_package.verifying_key().serialize()?,
+        unhex(&v["inputs"]["verifying_key_key"])
+    );
+
+    let signers: Vec<Identifier> = v["inputs"]["participant_list"].as_array().unwrap().iter().map(id_of).collect();
+    let mut script = VecDeque::new();
+    for o in v["round_one_outputs"]["outputs"].as_array().unwrap() {
+        let id = id_of(&o["identifier"]);
+        // nonces and commitments per participant
+        let mut rng = ScriptedRng(VecDeque::from([unhex(&o["hiding_nonce_randomness"]), unhex(&o["binding_nonce_randomness"])].concat()));
+        let (_, commitments) = frost::round1::commit(out.key_packages[&id].signing_share(), &mut rng);
+        assert_eq!(commitments.hiding().serialize()?, unhex(&o["hiding_nonce_commitment"]));
+        assert_eq!(commitments.binding().serialize()?, unhex(&o["binding_nonce_commitment"]));
+
+        script.extend(unhex(&o["hiding_nonce_randomness"]));
+        script.extend(unhex(&o["binding_nonce_randomness"]));
+    }
+
+    // full frost_ext signing path reproduces the reference signature
+    let sig = frost_sign_raw(&msg, &out, &signers, &mut ScriptedRng(script))?;
+    assert_eq!(sig.serialize()?, unhex(&v["final_output"]["sig"]));
+    assert!(frost_verify_raw(&msg, &sig, out.public_key_package.verifying_key())?);
+
+    let parsed = frost::Signature::deserialize(&unhex(&v["final_output"]["sig"]))?;
+    assert_eq!(parsed, sig);
+
+    // the same bytes as an attestation are domain-separated: the raw signature does not carry over
+    let att = AttestationMessage::new(msg);
+    assert!(!frost_verify_with_key(&att, &sig, out.public_key_package.verifying_key())?);
+    Ok(())
+}
+
+#[test]
+fn dvrf_vectors_are_stable() -> Result<()> {
+    let generated = generate_dvrf_vectors()?;
+    if std::env::var_os("FROSTLAB_REGEN_VECTORS").is_some() {
+        std::fs::write(DVRF_VECTORS_PATH, serde_json::to_string_pretty(&generated)? + "\n")?;
+    }
+    let stored: DvrfVectors = serde_json::from_str(&std::fs::read_to_string(DVRF_VECTORS_PATH)?)?;
+    check_dvrf_vectors(&stored)?;
+    assert_eq!(generated, stored, "DVRF encoding drifted; regenerate vectors only if intended");
+    Ok(())
+}